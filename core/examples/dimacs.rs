@@ -0,0 +1,16 @@
+use core::{cdcl::CDCLSolver, dimacs};
+
+fn main() {
+    let input = "p cnf 3 4\n1 2 0\n-1 3 0\n-2 -3 0\n-1 -2 0\n";
+    let clauses = dimacs::parse_dimacs(input).unwrap();
+    println!("variables:   {}", dimacs::variable_count(&clauses));
+    println!("clauses:     {}", clauses.len());
+    let mut solver = CDCLSolver::from(clauses);
+    match solver.try_solve() {
+        Ok(true) => {
+            println!("satisfiable: {}", solver.model().unwrap());
+        }
+        Ok(false) => println!("unsatisfiable"),
+        Err(err) => println!("unknown: {}", err),
+    }
+}
@@ -0,0 +1,12 @@
+use core::{parse, rewrite::RewriteRuleset};
+
+fn main() {
+    let formula = parse::parse("NOT (a ? (b NAND c) : NOT d)").unwrap();
+    let ruleset = RewriteRuleset::dnf();
+    let (result, report) = ruleset
+        .rewrite_recursive_hull_with_report(formula.clone())
+        .unwrap();
+    println!("formula:     {}", formula);
+    println!("rewritten:   {}", result);
+    print!("{}", report);
+}
@@ -0,0 +1,739 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    rc::Rc,
+};
+
+use crate::{
+    assignment::Assignment,
+    ast::{ASTNode, AbstractSyntaxTree, Ident, Model, Node, AST},
+    cdcl::{CDCLSolver, SolverConfig},
+    minimize,
+    proof::{ProofError, ResolutionProof},
+    sat::{Clause, DPLLSolver, Literal, Polarity},
+    smart::SmartBuild,
+    tseitin,
+};
+
+const DIFF_WITNESSES: usize = 4;
+const DIFF_SYNTHESIS_LIMIT: usize = 10;
+const DECOMPOSITION_LIMIT: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unateness {
+    Independent,
+    Positive,
+    Negative,
+    Binate,
+}
+
+impl fmt::Display for Unateness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unateness::Independent => write!(f, "independent"),
+            Unateness::Positive => write!(f, "positive unate"),
+            Unateness::Negative => write!(f, "negative unate"),
+            Unateness::Binate => write!(f, "binate"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Properties {
+    pub monotone: bool,
+    pub unateness: BTreeMap<Ident, Unateness>,
+    pub symmetric_pairs: Vec<(Ident, Ident)>,
+    pub self_dual: bool,
+    pub linear: bool,
+}
+
+impl fmt::Display for Properties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "monotone:  {}", self.monotone)?;
+        writeln!(f, "self-dual: {}", self.self_dual)?;
+        writeln!(f, "linear:    {}", self.linear)?;
+        for (ident, unateness) in &self.unateness {
+            writeln!(f, "var{}: {}", ident, unateness)?;
+        }
+        for (first, second) in &self.symmetric_pairs {
+            writeln!(f, "symmetric in var{} and var{}", first, second)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn properties(formula: &AST) -> Properties {
+    let variables = formula.variables();
+    let unateness = variables
+        .iter()
+        .map(|ident| (*ident, unateness(formula, *ident)))
+        .collect::<BTreeMap<_, _>>();
+    let mut symmetric_pairs = vec![];
+    for (index, first) in variables.iter().enumerate() {
+        for second in variables.iter().skip(index + 1) {
+            if is_symmetric_in(formula, *first, *second) {
+                symmetric_pairs.push((*first, *second));
+            }
+        }
+    }
+    Properties {
+        monotone: unateness
+            .values()
+            .all(|unateness| matches!(unateness, Unateness::Independent | Unateness::Positive)),
+        unateness,
+        symmetric_pairs,
+        self_dual: is_self_dual(formula),
+        linear: is_linear(formula),
+    }
+}
+
+pub fn unateness(formula: &AST, ident: Ident) -> Unateness {
+    let increasing = cofactors_differ(formula, ident, false);
+    let decreasing = cofactors_differ(formula, ident, true);
+    match (increasing, decreasing) {
+        (false, false) => Unateness::Independent,
+        (true, false) => Unateness::Positive,
+        (false, true) => Unateness::Negative,
+        (true, true) => Unateness::Binate,
+    }
+}
+
+pub fn is_monotone(formula: &AST) -> bool {
+    formula
+        .variables()
+        .into_iter()
+        .all(|ident| !cofactors_differ(formula, ident, true))
+}
+
+pub fn is_symmetric_in(formula: &AST, first: Ident, second: Ident) -> bool {
+    let mut query = Query::new();
+    let shared = query.rename(formula);
+    let mut swapped = shared.clone();
+    swapped.insert(first, query.constant(false));
+    swapped.insert(second, query.constant(true));
+    let mut crossed = shared;
+    crossed.insert(first, query.constant(true));
+    crossed.insert(second, query.constant(false));
+    let lhs = query.encode(formula, &swapped);
+    let rhs = query.encode(formula, &crossed);
+    let differ = query.xor(&lhs, &rhs);
+    !query.satisfiable(&[differ])
+}
+
+pub fn is_self_dual(formula: &AST) -> bool {
+    let mut query = Query::new();
+    let original = query.rename(formula);
+    let negated = original
+        .iter()
+        .map(|(ident, literal)| (*ident, literal.not()))
+        .collect();
+    let lhs = query.encode(formula, &original);
+    let rhs = query.encode(formula, &negated);
+    let differ = query.xor(&lhs, &rhs);
+    !query.satisfiable(&[differ.not()])
+}
+
+pub fn is_linear(formula: &AST) -> bool {
+    let mut query = Query::new();
+    let lhs = query.rename(formula);
+    let rhs = query.rename(formula);
+    let mut sum = HashMap::new();
+    for ident in formula.variables() {
+        let literal = query.xor(&lhs[&ident], &rhs[&ident]);
+        sum.insert(ident, literal);
+    }
+    let zero = formula
+        .variables()
+        .into_iter()
+        .map(|ident| (ident, query.constant(false)))
+        .collect();
+    let outputs = [
+        query.encode(formula, &lhs),
+        query.encode(formula, &rhs),
+        query.encode(formula, &sum),
+        query.encode(formula, &zero),
+    ];
+    let partial = query.xor(&outputs[0], &outputs[1]);
+    let partial = query.xor(&partial, &outputs[2]);
+    let parity = query.xor(&partial, &outputs[3]);
+    !query.satisfiable(&[parity])
+}
+
+pub fn depends_on(formula: &AST, ident: Ident) -> bool {
+    if !formula.variables().contains(&ident) {
+        return false;
+    }
+    let mut query = Query::new();
+    let (low, high) = query.cofactors(formula, ident);
+    let differ = query.xor(&low, &high);
+    query.satisfiable(&[differ])
+}
+
+pub fn propagate<T: AbstractSyntaxTree>(formula: &T, assignment: &Assignment) -> T {
+    restrict(formula, assignment).into_ast(formula)
+}
+
+pub fn support(formula: &AST) -> BTreeSet<Ident> {
+    formula
+        .variables()
+        .into_iter()
+        .filter(|ident| depends_on(formula, *ident))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    pub difference: AST,
+    pub witnesses: Vec<Model>,
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.difference)?;
+        for witness in &self.witnesses {
+            let mut assignment = witness.iter().collect::<Vec<_>>();
+            assignment.sort();
+            let literals = assignment
+                .into_iter()
+                .map(|(ident, value)| match value {
+                    true => format!("var{}", ident),
+                    false => format!("¬var{}", ident),
+                })
+                .collect::<Vec<_>>();
+            write!(f, "\n  {}", literals.join(" ∧ "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Diff {
+    pub fn is_equivalent(&self) -> bool {
+        self.witnesses.is_empty()
+    }
+}
+
+pub fn diff(lhs: &AST, rhs: &AST) -> Diff {
+    let variables = lhs
+        .variables()
+        .union(&rhs.variables())
+        .copied()
+        .collect::<Vec<_>>();
+    let witnesses = witnesses(lhs, rhs, DIFF_WITNESSES);
+    let symmetric = || lhs.and(rhs.not()).or(lhs.not().and(rhs.clone()));
+    let difference = match (
+        witnesses.is_empty(),
+        variables.len() <= DIFF_SYNTHESIS_LIMIT,
+    ) {
+        (true, _) => {
+            let var = <AST as AbstractSyntaxTree>::variable(variables[0]);
+            var.and(var.not())
+        }
+        (false, true) => {
+            let bits = (0..1u64 << variables.len())
+                .map(|minterm| {
+                    let model = variables
+                        .iter()
+                        .enumerate()
+                        .map(|(index, ident)| (*ident, minterm >> index & 1 == 1))
+                        .collect::<Model>();
+                    lhs.verify_model(&model) != rhs.verify_model(&model)
+                })
+                .collect::<Vec<_>>();
+            minimize::synthesize(&bits, &variables).unwrap_or_else(symmetric)
+        }
+        (false, false) => symmetric(),
+    };
+    Diff {
+        difference,
+        witnesses,
+    }
+}
+
+pub(crate) fn witnesses(lhs: &AST, rhs: &AST, limit: usize) -> Vec<Model> {
+    let mut query = Query::new();
+    let renaming = query.rename(&lhs.or(rhs.clone()));
+    let (left, right) = (query.encode(lhs, &renaming), query.encode(rhs, &renaming));
+    let differ = query.xor(&left, &right);
+    query.solver.add_clause(&Clause::new([differ]));
+    let mut witnesses = vec![];
+    while witnesses.len() < limit && query.satisfiable(&[]) {
+        let model = query.model(&renaming);
+        query.block(&renaming, &model);
+        witnesses.push(model);
+    }
+    witnesses
+}
+
+#[derive(Debug, Clone)]
+pub enum Evidence {
+    Proof {
+        clauses: Vec<Clause>,
+        proof: ResolutionProof,
+    },
+    Countermodel(Model),
+}
+
+impl fmt::Display for Evidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Evidence::Proof { proof, .. } => write!(f, "equivalent by refutation\n{}", proof),
+            Evidence::Countermodel(model) => {
+                write!(f, "differ under {}", model)
+            }
+        }
+    }
+}
+
+impl Evidence {
+    pub fn is_equivalent(&self) -> bool {
+        matches!(self, Evidence::Proof { .. })
+    }
+
+    pub fn check(&self, lhs: &AST, rhs: &AST) -> bool {
+        match self {
+            Evidence::Proof { clauses, proof } => {
+                *clauses == miter(lhs, rhs) && proof.check(clauses).is_ok()
+            }
+            Evidence::Countermodel(model) => lhs.verify_model(model) != rhs.verify_model(model),
+        }
+    }
+}
+
+pub fn equivalent(lhs: &AST, rhs: &AST) -> bool {
+    let mut query = Query::new();
+    let renaming = lhs
+        .variables()
+        .union(&rhs.variables())
+        .map(|ident| (*ident, query.fresh()))
+        .collect::<HashMap<_, _>>();
+    let (left, right) = (query.encode(lhs, &renaming), query.encode(rhs, &renaming));
+    let differ = query.xor(&left, &right);
+    !query.satisfiable(&[differ])
+}
+
+pub fn equivalent_with_evidence(lhs: &AST, rhs: &AST) -> Result<Evidence, ProofError> {
+    let clauses = miter(lhs, rhs);
+    let model = match DPLLSolver::from(clauses.clone()).model_or_refutation() {
+        Ok(model) => model,
+        Err(proof) => {
+            proof.check(&clauses)?;
+            return Ok(Evidence::Proof { clauses, proof });
+        }
+    };
+    Ok(Evidence::Countermodel(
+        lhs.variables()
+            .union(&rhs.variables())
+            .map(|ident| (*ident, model.get(*ident).unwrap_or(false)))
+            .collect(),
+    ))
+}
+
+fn miter(lhs: &AST, rhs: &AST) -> Vec<Clause> {
+    let differ = lhs.and(rhs.not()).or(lhs.not().and(rhs.clone()));
+    tseitin::cnf_clauses(&differ)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decomposition {
+    pub bound: BTreeSet<Ident>,
+    pub free: BTreeSet<Ident>,
+    pub ident: Ident,
+    pub inner: AST,
+    pub outer: AST,
+}
+
+impl fmt::Display for Decomposition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "var{} := {}", self.ident, self.inner)?;
+        write!(f, "{}", self.outer)
+    }
+}
+
+impl Decomposition {
+    pub fn compose(&self) -> AST {
+        substitute(&self.outer, self.ident, &self.inner)
+    }
+}
+
+pub fn decompose(formula: &AST) -> Option<Decomposition> {
+    let support = support(formula).into_iter().collect::<Vec<_>>();
+    if support.len() > DECOMPOSITION_LIMIT {
+        return None;
+    }
+    (2..support.len())
+        .flat_map(|size| masks_of_size(size, support.len()))
+        .find_map(|mask| {
+            let bound = support
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| mask >> index & 1 == 1)
+                .map(|(_, ident)| *ident)
+                .collect();
+            decompose_with(formula, &bound)
+        })
+}
+
+fn masks_of_size(size: usize, width: usize) -> impl Iterator<Item = u64> {
+    std::iter::successors(Some((1u64 << size) - 1), move |mask| {
+        let lowest = mask & mask.wrapping_neg();
+        let ripple = mask + lowest;
+        let next = ripple | (((ripple ^ mask) >> 2) / lowest);
+        (next < 1 << width).then_some(next)
+    })
+}
+
+pub fn decompose_with(formula: &AST, bound: &BTreeSet<Ident>) -> Option<Decomposition> {
+    let variables = formula.variables();
+    let free = variables
+        .difference(bound)
+        .copied()
+        .collect::<BTreeSet<_>>();
+    let bound = bound.iter().copied().collect::<Vec<_>>();
+    if bound.is_empty() || free.is_empty() || bound.len() > DECOMPOSITION_LIMIT {
+        return None;
+    }
+    let mut query = Query::new();
+    let shared = query.rename(formula);
+    let assignment = |index: u64| {
+        bound
+            .iter()
+            .enumerate()
+            .map(|(position, ident)| (*ident, index >> position & 1 == 1))
+            .collect::<Assignment>()
+    };
+    let mut classes: Vec<(u64, Literal, Vec<u64>)> = vec![];
+    for index in 0..1u64 << bound.len() {
+        let mut renaming = shared.clone();
+        for (ident, value) in assignment(index) {
+            renaming.insert(ident, query.constant(value));
+        }
+        let literal = query.encode(formula, &renaming);
+        let mut found = None;
+        for (position, (_, representative, _)) in classes.iter().enumerate() {
+            let differ = query.xor(&literal, representative);
+            if !query.satisfiable(&[differ]) {
+                found = Some(position);
+                break;
+            }
+        }
+        match found {
+            Some(position) => classes[position].2.push(index),
+            None if classes.len() == 2 => return None,
+            None => classes.push((index, literal, vec![index])),
+        }
+    }
+    if classes.len() != 2 {
+        return None;
+    }
+    classes.sort_by_key(|(_, _, members)| members.len());
+    let ident = variables.iter().max().map_or(0, |ident| ident + 1);
+    let minterms = classes[0].2.iter().map(|index| {
+        let mut literals = assignment(*index)
+            .into_iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(ident, value)| {
+                let var = <AST as AbstractSyntaxTree>::variable(ident);
+                match value {
+                    true => var,
+                    false => var.not(),
+                }
+            });
+        let first = literals.next().unwrap();
+        literals.fold(first, |product, literal| product.and(literal))
+    });
+    let inner = minterms.reduce(|sum, minterm| sum.or(minterm)).unwrap();
+    let outer = Residual::ite(
+        <AST as AbstractSyntaxTree>::variable(ident),
+        restrict(formula, &assignment(classes[0].0)),
+        restrict(formula, &assignment(classes[1].0)),
+    );
+    let Residual::Formula(outer) = outer else {
+        return None;
+    };
+    Some(Decomposition {
+        bound: bound.into_iter().collect(),
+        free,
+        ident,
+        inner,
+        outer,
+    })
+}
+
+#[derive(Clone)]
+pub(crate) enum Residual<T = AST> {
+    Constant(bool),
+    Formula(T),
+}
+
+impl<T: AbstractSyntaxTree> From<T> for Residual<T> {
+    fn from(formula: T) -> Self {
+        match formula.as_constant() {
+            Some(value) => Residual::Constant(value),
+            None => Residual::Formula(formula),
+        }
+    }
+}
+
+impl<T: AbstractSyntaxTree> Residual<T> {
+    pub(crate) fn into_ast(self, within: &T) -> T {
+        match self {
+            Residual::Formula(residual) => residual,
+            Residual::Constant(value) => {
+                let ident = within.variables().first().copied().unwrap_or(0);
+                let var = T::variable(ident);
+                match value {
+                    true => var.or(var.not()),
+                    false => var.and(var.not()),
+                }
+            }
+        }
+    }
+
+    pub(crate) fn not(self) -> Self {
+        match self {
+            Residual::Constant(value) => Residual::Constant(!value),
+            Residual::Formula(p) => Residual::from(p.smart_not()),
+        }
+    }
+
+    pub(crate) fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Residual::Constant(false), _) | (_, Residual::Constant(false)) => {
+                Residual::Constant(false)
+            }
+            (Residual::Constant(true), p) | (p, Residual::Constant(true)) => p,
+            (Residual::Formula(p1), Residual::Formula(p2)) => Residual::from(p1.smart_and(p2)),
+        }
+    }
+
+    pub(crate) fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Residual::Constant(true), _) | (_, Residual::Constant(true)) => {
+                Residual::Constant(true)
+            }
+            (Residual::Constant(false), p) | (p, Residual::Constant(false)) => p,
+            (Residual::Formula(p1), Residual::Formula(p2)) => Residual::from(p1.smart_or(p2)),
+        }
+    }
+
+    pub(crate) fn ite(condition: T, then: Self, otherwise: Self) -> Self {
+        match (then, otherwise) {
+            (Residual::Constant(then), Residual::Constant(otherwise)) if then == otherwise => {
+                Residual::Constant(then)
+            }
+            (Residual::Constant(true), Residual::Constant(false)) => Residual::Formula(condition),
+            (Residual::Constant(false), Residual::Constant(true)) => {
+                Residual::Formula(condition.not())
+            }
+            (Residual::Constant(true), otherwise) => Residual::Formula(condition).or(otherwise),
+            (Residual::Constant(false), otherwise) => {
+                Residual::Formula(condition.not()).and(otherwise)
+            }
+            (then, Residual::Constant(true)) => Residual::Formula(condition.not()).or(then),
+            (then, Residual::Constant(false)) => Residual::Formula(condition).and(then),
+            (Residual::Formula(then), Residual::Formula(otherwise)) => {
+                Residual::Formula(condition.ite(then, otherwise))
+            }
+        }
+    }
+}
+
+pub(crate) fn restrict<T: AbstractSyntaxTree>(formula: &T, assignment: &Assignment) -> Residual<T> {
+    restrict_memoized(formula, assignment, &mut HashMap::new())
+}
+
+fn restrict_memoized<T: AbstractSyntaxTree>(
+    formula: &T,
+    assignment: &Assignment,
+    memo: &mut HashMap<usize, Residual<T>>,
+) -> Residual<T> {
+    if let Some(residual) = memo.get(&formula.key()) {
+        return residual.clone();
+    }
+    let mut recurse = |p: &T| restrict_memoized(p, assignment, memo);
+    let residual = match formula.node() {
+        Node::Variable(ident) => match assignment.get(ident) {
+            Some(value) => Residual::Constant(value),
+            None => Residual::Formula(formula.clone()),
+        },
+        Node::Not(p) => recurse(&p).not(),
+        Node::And(p1, p2) => recurse(&p1).and(recurse(&p2)),
+        Node::Or(p1, p2) => recurse(&p1).or(recurse(&p2)),
+        Node::Nand(p1, p2) => recurse(&p1).and(recurse(&p2)).not(),
+        Node::Nor(p1, p2) => recurse(&p1).or(recurse(&p2)).not(),
+        Node::Ite(p1, p2, p3) => match recurse(&p1) {
+            Residual::Constant(true) => recurse(&p2),
+            Residual::Constant(false) => recurse(&p3),
+            Residual::Formula(condition) => Residual::ite(condition, recurse(&p2), recurse(&p3)),
+        },
+    };
+    memo.insert(formula.key(), residual.clone());
+    residual
+}
+
+fn substitute(formula: &AST, ident: Ident, replacement: &AST) -> AST {
+    let recurse = |p: &AST| substitute(p, ident, replacement);
+    match &**formula {
+        ASTNode::Variable(var) if *var == ident => replacement.clone(),
+        ASTNode::Variable(_) => formula.clone(),
+        ASTNode::Not(p) => recurse(p).not(),
+        ASTNode::And(p1, p2) => recurse(p1).and(recurse(p2)),
+        ASTNode::Or(p1, p2) => recurse(p1).or(recurse(p2)),
+        ASTNode::Nand(p1, p2) => recurse(p1).nand(recurse(p2)),
+        ASTNode::Nor(p1, p2) => recurse(p1).nor(recurse(p2)),
+        ASTNode::Ite(p1, p2, p3) => recurse(p1).ite(recurse(p2), recurse(p3)),
+    }
+}
+
+fn cofactors_differ(formula: &AST, ident: Ident, decreasing: bool) -> bool {
+    let mut query = Query::new();
+    let (low, high) = query.cofactors(formula, ident);
+    match decreasing {
+        false => query.satisfiable(&[low.not(), high]),
+        true => query.satisfiable(&[low, high.not()]),
+    }
+}
+
+struct Query {
+    solver: CDCLSolver,
+    truth: Literal,
+}
+
+impl Query {
+    fn new() -> Self {
+        let mut solver = CDCLSolver::new(SolverConfig::default());
+        let truth = Literal::new(solver.new_variable(), Polarity::Positive);
+        solver.add_clause(&Clause::new([truth.clone()]));
+        Self { solver, truth }
+    }
+
+    fn fresh(&mut self) -> Literal {
+        Literal::new(self.solver.new_variable(), Polarity::Positive)
+    }
+
+    fn constant(&self, value: bool) -> Literal {
+        match value {
+            true => self.truth.clone(),
+            false => self.truth.not(),
+        }
+    }
+
+    fn rename(&mut self, formula: &AST) -> HashMap<Ident, Literal> {
+        formula
+            .variables()
+            .into_iter()
+            .map(|ident| (ident, self.fresh()))
+            .collect()
+    }
+
+    fn cofactors(&mut self, formula: &AST, ident: Ident) -> (Literal, Literal) {
+        let shared = self.rename(formula);
+        let mut low = shared.clone();
+        low.insert(ident, self.constant(false));
+        let mut high = shared;
+        high.insert(ident, self.constant(true));
+        (self.encode(formula, &low), self.encode(formula, &high))
+    }
+
+    fn xor(&mut self, lhs: &Literal, rhs: &Literal) -> Literal {
+        let output = self.fresh();
+        for (a, b, c) in [
+            (output.not(), lhs.clone(), rhs.clone()),
+            (output.not(), lhs.not(), rhs.not()),
+            (output.clone(), lhs.not(), rhs.clone()),
+            (output.clone(), lhs.clone(), rhs.not()),
+        ] {
+            self.solver.add_clause(&Clause::new([a, b, c]));
+        }
+        output
+    }
+
+    fn encode(&mut self, formula: &AST, renaming: &HashMap<Ident, Literal>) -> Literal {
+        self.encode_memoized(formula, renaming, &mut HashMap::new())
+    }
+
+    fn encode_memoized(
+        &mut self,
+        formula: &AST,
+        renaming: &HashMap<Ident, Literal>,
+        memo: &mut HashMap<*const ASTNode, Literal>,
+    ) -> Literal {
+        if let Some(literal) = memo.get(&Rc::as_ptr(formula)) {
+            return literal.clone();
+        }
+        let mut encode = |query: &mut Self, p: &AST| query.encode_memoized(p, renaming, memo);
+        let literal = match &**formula {
+            ASTNode::Variable(ident) => renaming[ident].clone(),
+            ASTNode::Not(p) => encode(self, p).not(),
+            ASTNode::And(p1, p2) => {
+                let (lhs, rhs) = (encode(self, p1), encode(self, p2));
+                self.and_gate(lhs, rhs)
+            }
+            ASTNode::Or(p1, p2) => {
+                let (lhs, rhs) = (encode(self, p1), encode(self, p2));
+                self.and_gate(lhs.not(), rhs.not()).not()
+            }
+            ASTNode::Nand(p1, p2) => {
+                let (lhs, rhs) = (encode(self, p1), encode(self, p2));
+                self.and_gate(lhs, rhs).not()
+            }
+            ASTNode::Nor(p1, p2) => {
+                let (lhs, rhs) = (encode(self, p1), encode(self, p2));
+                self.and_gate(lhs.not(), rhs.not())
+            }
+            ASTNode::Ite(p1, p2, p3) => {
+                let condition = encode(self, p1);
+                let then = encode(self, p2);
+                let otherwise = encode(self, p3);
+                let output = self.fresh();
+                for (a, b, c) in [
+                    (condition.not(), then.not(), output.clone()),
+                    (condition.not(), then.clone(), output.not()),
+                    (condition.clone(), otherwise.not(), output.clone()),
+                    (condition.clone(), otherwise.clone(), output.not()),
+                ] {
+                    self.solver.add_clause(&Clause::new([a, b, c]));
+                }
+                output
+            }
+        };
+        memo.insert(Rc::as_ptr(formula), literal.clone());
+        literal
+    }
+
+    fn and_gate(&mut self, lhs: Literal, rhs: Literal) -> Literal {
+        let output = self.fresh();
+        self.solver
+            .add_clause(&Clause::new([output.not(), lhs.clone()]));
+        self.solver
+            .add_clause(&Clause::new([output.not(), rhs.clone()]));
+        self.solver
+            .add_clause(&Clause::new([output.clone(), lhs.not(), rhs.not()]));
+        output
+    }
+
+    fn model(&self, renaming: &HashMap<Ident, Literal>) -> Model {
+        let model = self.solver.model().unwrap();
+        renaming
+            .iter()
+            .map(|(ident, literal)| {
+                let value = model.get(literal.identifier()).unwrap_or(false);
+                (*ident, value == (*literal.polarity() == Polarity::Positive))
+            })
+            .collect()
+    }
+
+    fn block(&mut self, renaming: &HashMap<Ident, Literal>, model: &Model) {
+        self.solver.add_clause(&Clause::new(model.iter().map(
+            |(ident, value)| match value {
+                true => renaming[&ident].not(),
+                false => renaming[&ident].clone(),
+            },
+        )));
+    }
+
+    fn satisfiable(&mut self, assumptions: &[Literal]) -> bool {
+        self.solver.solve_complete(assumptions)
+    }
+}
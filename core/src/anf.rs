@@ -0,0 +1,214 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt, ops,
+};
+
+use crate::ast::{AbstractSyntaxTree, Ident, Model, Node, AST};
+
+pub type Monomial = BTreeSet<Ident>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Anf {
+    monomials: BTreeSet<Monomial>,
+}
+
+impl fmt::Display for Anf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.monomials.is_empty() {
+            return write!(f, "0");
+        }
+        let mut monomials = self.monomials.iter().collect::<Vec<_>>();
+        monomials.sort_by_key(|monomial| monomial.len());
+        let terms = monomials
+            .into_iter()
+            .map(|monomial| match monomial.is_empty() {
+                true => "1".to_owned(),
+                false => monomial
+                    .iter()
+                    .map(|ident| format!("var{}", ident))
+                    .collect::<Vec<_>>()
+                    .join("·"),
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", terms.join(" ⊕ "))
+    }
+}
+
+impl<T: AbstractSyntaxTree> From<&T> for Anf {
+    fn from(value: &T) -> Self {
+        convert(value, &mut HashMap::new())
+    }
+}
+
+fn convert<T: AbstractSyntaxTree>(formula: &T, memo: &mut HashMap<usize, Anf>) -> Anf {
+    if let Some(anf) = memo.get(&formula.key()) {
+        return anf.clone();
+    }
+    let mut recurse = |p: &T| convert(p, memo);
+    let anf = match formula.node() {
+        Node::Variable(ident) => Anf::variable(ident),
+        Node::Not(p) => !recurse(&p),
+        Node::And(p1, p2) => recurse(&p1) & recurse(&p2),
+        Node::Or(p1, p2) => recurse(&p1) | recurse(&p2),
+        Node::Nand(p1, p2) => !(recurse(&p1) & recurse(&p2)),
+        Node::Nor(p1, p2) => !(recurse(&p1) | recurse(&p2)),
+        Node::Ite(p1, p2, p3) => {
+            let otherwise = recurse(&p3);
+            (recurse(&p1) & (recurse(&p2) ^ otherwise.clone())) ^ otherwise
+        }
+    };
+    memo.insert(formula.key(), anf.clone());
+    anf
+}
+
+impl ops::Not for Anf {
+    type Output = Anf;
+
+    fn not(self) -> Anf {
+        self ^ Anf::one()
+    }
+}
+
+impl ops::BitXor for Anf {
+    type Output = Anf;
+
+    fn bitxor(mut self, rhs: Anf) -> Anf {
+        for monomial in rhs.monomials {
+            self.toggle(monomial);
+        }
+        self
+    }
+}
+
+impl ops::BitAnd for Anf {
+    type Output = Anf;
+
+    fn bitand(self, rhs: Anf) -> Anf {
+        let mut product = Anf::zero();
+        for lhs in &self.monomials {
+            for rhs in &rhs.monomials {
+                product.toggle(lhs.union(rhs).copied().collect());
+            }
+        }
+        product
+    }
+}
+
+impl ops::BitOr for Anf {
+    type Output = Anf;
+
+    fn bitor(self, rhs: Anf) -> Anf {
+        self.clone() ^ rhs.clone() ^ (self & rhs)
+    }
+}
+
+impl Anf {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self {
+            monomials: BTreeSet::from([Monomial::new()]),
+        }
+    }
+
+    pub fn variable(ident: Ident) -> Self {
+        Self {
+            monomials: BTreeSet::from([Monomial::from([ident])]),
+        }
+    }
+
+    pub fn monomials(&self) -> impl Iterator<Item = &Monomial> {
+        self.monomials.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.monomials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.monomials.is_empty()
+    }
+
+    pub fn degree(&self) -> usize {
+        self.monomials.iter().map(Monomial::len).max().unwrap_or(0)
+    }
+
+    pub fn degree_in(&self, ident: Ident) -> usize {
+        self.monomials
+            .iter()
+            .filter(|monomial| monomial.contains(&ident))
+            .map(Monomial::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn is_affine(&self) -> bool {
+        self.degree() <= 1
+    }
+
+    pub fn variables(&self) -> BTreeSet<Ident> {
+        self.monomials.iter().flatten().copied().collect()
+    }
+
+    pub fn evaluate(&self, model: &Model) -> bool {
+        self.monomials
+            .iter()
+            .filter(|monomial| {
+                monomial
+                    .iter()
+                    .all(|ident| model.get(*ident).unwrap_or(false))
+            })
+            .count()
+            % 2
+            == 1
+    }
+
+    pub fn as_constant(&self) -> Option<bool> {
+        match self.monomials.iter().next() {
+            None => Some(false),
+            Some(monomial) if monomial.is_empty() && self.monomials.len() == 1 => Some(true),
+            Some(_) => None,
+        }
+    }
+
+    pub fn to_ast(&self) -> Option<AST> {
+        let constant = self.monomials.contains(&Monomial::new());
+        let terms = self
+            .monomials
+            .iter()
+            .filter(|monomial| !monomial.is_empty())
+            .map(|monomial| {
+                let mut factors = monomial
+                    .iter()
+                    .map(|ident| <AST as AbstractSyntaxTree>::variable(*ident));
+                let first = factors.next().unwrap();
+                factors.fold(first, |product, factor| product.and(factor))
+            })
+            .collect::<Vec<_>>();
+        let sum = exclusive_or(&terms)?;
+        match constant {
+            true => Some(sum.not()),
+            false => Some(sum),
+        }
+    }
+
+    fn toggle(&mut self, monomial: Monomial) {
+        if !self.monomials.remove(&monomial) {
+            self.monomials.insert(monomial);
+        }
+    }
+}
+
+fn exclusive_or(terms: &[AST]) -> Option<AST> {
+    match terms {
+        [] => None,
+        [term] => Some(term.clone()),
+        _ => {
+            let (lhs, rhs) = terms.split_at(terms.len() / 2);
+            let (lhs, rhs) = (exclusive_or(lhs)?, exclusive_or(rhs)?);
+            Some(lhs.and(rhs.not()).or(lhs.not().and(rhs)))
+        }
+    }
+}
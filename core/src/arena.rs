@@ -0,0 +1,124 @@
+use std::{cell::RefCell, collections::HashMap, fmt};
+
+use crate::ast::{AbstractSyntaxTree, Node, AST};
+
+thread_local! {
+    static ARENA: RefCell<Arena> = RefCell::new(Arena::default());
+}
+
+#[derive(Default)]
+struct Arena {
+    nodes: Vec<Node<ArenaAst>>,
+    interned: HashMap<Node<ArenaAst>, u32>,
+    generation: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArenaAst {
+    index: u32,
+    generation: u32,
+}
+
+impl fmt::Display for ArenaAst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.node() {
+            Node::Variable(ident) => write!(f, "var{}", ident),
+            Node::Not(p) => write!(f, "¬{}", p),
+            Node::And(lhs, rhs) => write!(f, "({} ∧ {})", lhs, rhs),
+            Node::Or(lhs, rhs) => write!(f, "({} ∨ {})", lhs, rhs),
+            Node::Nand(lhs, rhs) => write!(f, "({} ↑ {})", lhs, rhs),
+            Node::Nor(lhs, rhs) => write!(f, "({} ↓ {})", lhs, rhs),
+            Node::Ite(condition, then, otherwise) => {
+                write!(f, "({} ? {} : {})", condition, then, otherwise)
+            }
+        }
+    }
+}
+
+impl From<&AST> for ArenaAst {
+    fn from(value: &AST) -> Self {
+        Self::from_ast(value)
+    }
+}
+
+impl ArenaAst {
+    pub fn allocated() -> usize {
+        ARENA.with(|arena| arena.borrow().nodes.len())
+    }
+
+    pub fn reserve(additional: usize) {
+        ARENA.with(|arena| {
+            let mut arena = arena.borrow_mut();
+            arena.nodes.reserve(additional);
+            arena.interned.reserve(additional);
+        });
+    }
+
+    pub fn reset() {
+        ARENA.with(|arena| {
+            let mut arena = arena.borrow_mut();
+            arena.nodes.clear();
+            arena.interned.clear();
+            arena.generation += 1;
+        });
+    }
+
+    fn allocate(node: Node<ArenaAst>) -> Self {
+        ARENA.with(|arena| {
+            let mut arena = arena.borrow_mut();
+            let generation = arena.generation;
+            let index = match arena.interned.get(&node) {
+                Some(index) => *index,
+                None => {
+                    let index = arena.nodes.len() as u32;
+                    arena.nodes.push(node);
+                    arena.interned.insert(node, index);
+                    index
+                }
+            };
+            Self { index, generation }
+        })
+    }
+}
+
+impl AbstractSyntaxTree for ArenaAst {
+    fn variable(ident: u32) -> Self {
+        Self::allocate(Node::Variable(ident))
+    }
+
+    fn and(&self, other: Self) -> Self {
+        Self::allocate(Node::And(*self, other))
+    }
+
+    fn or(&self, other: Self) -> Self {
+        Self::allocate(Node::Or(*self, other))
+    }
+
+    fn not(&self) -> Self {
+        Self::allocate(Node::Not(*self))
+    }
+
+    fn nand(&self, other: Self) -> Self {
+        Self::allocate(Node::Nand(*self, other))
+    }
+
+    fn nor(&self, other: Self) -> Self {
+        Self::allocate(Node::Nor(*self, other))
+    }
+
+    fn ite(&self, then: Self, otherwise: Self) -> Self {
+        Self::allocate(Node::Ite(*self, then, otherwise))
+    }
+
+    fn node(&self) -> Node<Self> {
+        ARENA.with(|arena| {
+            let arena = arena.borrow();
+            assert_eq!(self.generation, arena.generation, "stale arena handle");
+            arena.nodes[self.index as usize]
+        })
+    }
+
+    fn key(&self) -> usize {
+        self.index as usize
+    }
+}
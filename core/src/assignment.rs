@@ -0,0 +1,200 @@
+use std::{
+    collections::{btree_map, BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::{
+    ast::{Ident, AST},
+    kleene::{ThreeValued, Truth},
+    parse::SymbolTable,
+    sat::{Clause, Literal},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Assignment {
+    values: BTreeMap<Ident, bool>,
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let literals = self
+            .literals()
+            .map(|literal| literal.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", literals.join(", "))
+    }
+}
+
+impl FromIterator<(Ident, bool)> for Assignment {
+    fn from_iter<I: IntoIterator<Item = (Ident, bool)>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl FromIterator<Literal> for Assignment {
+    fn from_iter<I: IntoIterator<Item = Literal>>(iter: I) -> Self {
+        iter.into_iter()
+            .map(|literal| (literal.identifier(), literal.is_positive()))
+            .collect()
+    }
+}
+
+impl Extend<(Ident, bool)> for Assignment {
+    fn extend<I: IntoIterator<Item = (Ident, bool)>>(&mut self, iter: I) {
+        self.values.extend(iter)
+    }
+}
+
+impl IntoIterator for Assignment {
+    type Item = (Ident, bool);
+    type IntoIter = btree_map::IntoIter<Ident, bool>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Assignment {
+    type Item = (Ident, bool);
+    type IntoIter = Box<dyn Iterator<Item = (Ident, bool)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl Assignment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, ident: Ident) -> Option<bool> {
+        self.values.get(&ident).copied()
+    }
+
+    pub fn set(&mut self, ident: Ident, value: bool) -> Option<bool> {
+        self.values.insert(ident, value)
+    }
+
+    pub fn set_default(&mut self, ident: Ident, value: bool) -> bool {
+        *self.values.entry(ident).or_insert(value)
+    }
+
+    pub fn contains(&self, ident: Ident) -> bool {
+        self.values.contains_key(&ident)
+    }
+
+    pub fn unset(&mut self, ident: Ident) -> Option<bool> {
+        self.values.remove(&ident)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn variables(&self) -> BTreeSet<Ident> {
+        self.values.keys().copied().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Ident, bool)> + '_ {
+        self.values.iter().map(|(ident, value)| (*ident, *value))
+    }
+
+    pub fn literals(&self) -> impl Iterator<Item = Literal> + '_ {
+        self.iter().map(|(ident, value)| match value {
+            true => Literal::positive(ident),
+            false => Literal::negative(ident),
+        })
+    }
+
+    pub fn is_total(&self, variables: &BTreeSet<Ident>) -> bool {
+        variables
+            .iter()
+            .all(|ident| self.values.contains_key(ident))
+    }
+
+    pub fn restrict(&self, variables: impl IntoIterator<Item = Ident>) -> Self {
+        variables
+            .into_iter()
+            .filter_map(|ident| self.get(ident).map(|value| (ident, value)))
+            .collect()
+    }
+
+    pub fn extend(&self, other: &Assignment) -> Option<Self> {
+        if !self.agrees_with(other) {
+            return None;
+        }
+        let mut extended = self.clone();
+        extended.values.extend(other.iter());
+        Some(extended)
+    }
+
+    pub fn agrees_with(&self, other: &Assignment) -> bool {
+        self.iter()
+            .all(|(ident, value)| other.get(ident).is_none_or(|other| other == value))
+    }
+
+    pub fn satisfies(&self, literal: &Literal) -> Option<bool> {
+        self.get(literal.identifier())
+            .map(|value| value == literal.is_positive())
+    }
+
+    pub fn evaluate(&self, formula: &AST) -> Truth {
+        formula.evaluate_partial(self)
+    }
+
+    pub fn to_unit_clauses(&self) -> Vec<Clause> {
+        self.literals()
+            .map(|literal| Clause::new([literal]))
+            .collect()
+    }
+
+    pub fn from_unit_clauses<'a>(clauses: impl IntoIterator<Item = &'a Clause>) -> Option<Self> {
+        let mut assignment = Self::new();
+        for clause in clauses {
+            let [literal] = clause.literals().collect::<Vec<_>>()[..] else {
+                return None;
+            };
+            let value = literal.is_positive();
+            if assignment.set(literal.identifier(), value) == Some(!value) {
+                return None;
+            }
+        }
+        Some(assignment)
+    }
+
+    pub fn named<'a>(&'a self, symbols: &'a SymbolTable) -> Named<'a> {
+        Named {
+            assignment: self,
+            symbols,
+        }
+    }
+}
+
+pub struct Named<'a> {
+    assignment: &'a Assignment,
+    symbols: &'a SymbolTable,
+}
+
+impl fmt::Display for Named<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bindings = self
+            .assignment
+            .iter()
+            .map(|(ident, value)| {
+                let name = match self.symbols.name(ident) {
+                    Some(name) => name.to_owned(),
+                    None => format!("var{}", ident),
+                };
+                format!("{} = {}", name, value)
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", bindings.join(", "))
+    }
+}
@@ -1,24 +1,67 @@
-use std::{fmt, rc::Rc};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    hash::Hash,
+    rc::Rc,
+};
 
 use crate::{
-    rewrite::{RewriteRule, RewriteRuleset},
-    sat::DPLLSolver,
+    analysis,
+    assignment::Assignment,
+    budget::Budget,
+    canonical,
+    error::Error,
+    kleene::{ThreeValued, Truth},
+    minimize,
+    nary::{NaryNode, NaryTree},
+    portfolio,
+    rewrite::{RewriteError, RewriteRuleset},
+    tseitin::{self, CnfConversion},
 };
 
 pub type Ident = u32;
 pub type AST = Rc<ASTNode>;
+pub type Model = Assignment;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParseError {
-    UnexpectedEndOfInput,
+    UnexpectedEndOfInput { position: usize },
+    UnexpectedCharacter { character: char, position: usize },
+    UnexpectedToken { token: String, position: usize },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEndOfInput { position } => {
+                write!(f, "unexpected end of input at position {}", position)
+            }
+            ParseError::UnexpectedCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "unexpected character '{}' at position {}",
+                character, position
+            ),
+            ParseError::UnexpectedToken { token, position } => {
+                write!(f, "unexpected token '{}' at position {}", token, position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ASTNode {
     Variable(Ident),
     Not(AST),
     And(AST, AST),
     Or(AST, AST),
+    Nand(AST, AST),
+    Nor(AST, AST),
+    Ite(AST, AST, AST),
 }
 
 impl fmt::Display for ASTNode {
@@ -28,18 +71,242 @@ impl fmt::Display for ASTNode {
             ASTNode::Not(p) => write!(f, "¬{}", p),
             ASTNode::And(lhs, rhs) => write!(f, "({} ∧ {})", lhs, rhs),
             ASTNode::Or(lhs, rhs) => write!(f, "({} ∨ {})", lhs, rhs),
+            ASTNode::Nand(lhs, rhs) => write!(f, "({} ↑ {})", lhs, rhs),
+            ASTNode::Nor(lhs, rhs) => write!(f, "({} ↓ {})", lhs, rhs),
+            ASTNode::Ite(condition, then, otherwise) => {
+                write!(f, "({} ? {} : {})", condition, then, otherwise)
+            }
         }
     }
 }
 
-pub trait AbstractSyntaxTree {
-    fn variable(ident: u32) -> AST;
-    fn and(&self, other: AST) -> AST;
-    fn or(&self, other: AST) -> AST;
-    fn not(&self) -> AST;
-    fn dnf(&self) -> AST;
-    fn cnf(&self) -> AST;
-    fn sat(&self) -> bool;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Node<T> {
+    Variable(Ident),
+    Not(T),
+    And(T, T),
+    Or(T, T),
+    Nand(T, T),
+    Nor(T, T),
+    Ite(T, T, T),
+}
+
+pub trait AbstractSyntaxTree: Sized + Clone + Eq + Hash {
+    fn variable(ident: u32) -> Self;
+    fn and(&self, other: Self) -> Self;
+    fn or(&self, other: Self) -> Self;
+    fn not(&self) -> Self;
+    fn nand(&self, other: Self) -> Self;
+    fn nor(&self, other: Self) -> Self;
+    fn ite(&self, then: Self, otherwise: Self) -> Self;
+    fn node(&self) -> Node<Self>;
+    fn key(&self) -> usize;
+
+    fn from_node(node: Node<Self>) -> Self {
+        match node {
+            Node::Variable(ident) => Self::variable(ident),
+            Node::Not(p) => p.not(),
+            Node::And(p1, p2) => p1.and(p2),
+            Node::Or(p1, p2) => p1.or(p2),
+            Node::Nand(p1, p2) => p1.nand(p2),
+            Node::Nor(p1, p2) => p1.nor(p2),
+            Node::Ite(p1, p2, p3) => p1.ite(p2, p3),
+        }
+    }
+
+    fn from_ast(formula: &AST) -> Self {
+        fn convert<T: AbstractSyntaxTree>(
+            formula: &AST,
+            memo: &mut HashMap<*const ASTNode, T>,
+        ) -> T {
+            if let Some(converted) = memo.get(&Rc::as_ptr(formula)) {
+                return converted.clone();
+            }
+            let mut lift = |p: &AST| convert(p, memo);
+            let converted = T::from_node(match &**formula {
+                ASTNode::Variable(ident) => Node::Variable(*ident),
+                ASTNode::Not(p) => Node::Not(lift(p)),
+                ASTNode::And(p1, p2) => Node::And(lift(p1), lift(p2)),
+                ASTNode::Or(p1, p2) => Node::Or(lift(p1), lift(p2)),
+                ASTNode::Nand(p1, p2) => Node::Nand(lift(p1), lift(p2)),
+                ASTNode::Nor(p1, p2) => Node::Nor(lift(p1), lift(p2)),
+                ASTNode::Ite(p1, p2, p3) => Node::Ite(lift(p1), lift(p2), lift(p3)),
+            });
+            memo.insert(Rc::as_ptr(formula), converted.clone());
+            converted
+        }
+        convert(formula, &mut HashMap::new())
+    }
+
+    fn to_ast(&self) -> AST {
+        fn convert<T: AbstractSyntaxTree>(formula: &T, memo: &mut HashMap<usize, AST>) -> AST {
+            if let Some(converted) = memo.get(&formula.key()) {
+                return converted.clone();
+            }
+            let mut lower = |p: &T| convert(p, memo);
+            let converted = match formula.node() {
+                Node::Variable(ident) => AST::variable(ident),
+                Node::Not(p) => lower(&p).not(),
+                Node::And(p1, p2) => lower(&p1).and(lower(&p2)),
+                Node::Or(p1, p2) => lower(&p1).or(lower(&p2)),
+                Node::Nand(p1, p2) => lower(&p1).nand(lower(&p2)),
+                Node::Nor(p1, p2) => lower(&p1).nor(lower(&p2)),
+                Node::Ite(p1, p2, p3) => lower(&p1).ite(lower(&p2), lower(&p3)),
+            };
+            memo.insert(formula.key(), converted.clone());
+            converted
+        }
+        convert(self, &mut HashMap::new())
+    }
+
+    fn from_truth_table(bits: &[bool], variables: usize) -> Option<Self> {
+        let variables = (0..Ident::try_from(variables).ok()?).collect::<Vec<_>>();
+        Some(Self::from_ast(&minimize::synthesize(bits, &variables)?))
+    }
+
+    fn minimize_equivalent(&self, max_size: usize) -> Self {
+        Self::from_ast(&minimize::minimize_equivalent(&self.to_ast(), max_size))
+    }
+
+    fn variables(&self) -> BTreeSet<Ident> {
+        let mut variables = BTreeSet::new();
+        let mut visited = HashSet::new();
+        let mut subtrees = vec![self.clone()];
+        while let Some(subtree) = subtrees.pop() {
+            if !visited.insert(subtree.key()) {
+                continue;
+            }
+            match subtree.node() {
+                Node::Variable(ident) => {
+                    variables.insert(ident);
+                }
+                Node::Not(p) => subtrees.push(p),
+                Node::And(p1, p2) | Node::Or(p1, p2) | Node::Nand(p1, p2) | Node::Nor(p1, p2) => {
+                    subtrees.push(p1);
+                    subtrees.push(p2);
+                }
+                Node::Ite(p1, p2, p3) => {
+                    subtrees.push(p1);
+                    subtrees.push(p2);
+                    subtrees.push(p3);
+                }
+            }
+        }
+        variables
+    }
+
+    fn depends_on(&self, ident: Ident) -> bool {
+        analysis::depends_on(&self.to_ast(), ident)
+    }
+
+    fn propagate(&self, assignment: &Assignment) -> Self {
+        analysis::propagate(self, assignment)
+    }
+
+    fn support(&self) -> BTreeSet<Ident> {
+        analysis::support(&self.to_ast())
+    }
+
+    fn diff(&self, other: &Self) -> analysis::Diff {
+        analysis::diff(&self.to_ast(), &other.to_ast())
+    }
+
+    fn to_nand_only(&self) -> Self {
+        fn convert<T: AbstractSyntaxTree>(formula: &T, memo: &mut HashMap<usize, T>) -> T {
+            if let Some(converted) = memo.get(&formula.key()) {
+                return converted.clone();
+            }
+            let not = |p: &T| p.nand(p.clone());
+            let mut lift = |p: &T| convert(p, memo);
+            let converted = match formula.node() {
+                Node::Variable(_) => formula.clone(),
+                Node::Not(p) => not(&lift(&p)),
+                Node::And(p1, p2) => not(&lift(&p1).nand(lift(&p2))),
+                Node::Or(p1, p2) => not(&lift(&p1)).nand(not(&lift(&p2))),
+                Node::Nand(p1, p2) => lift(&p1).nand(lift(&p2)),
+                Node::Nor(p1, p2) => not(&not(&lift(&p1)).nand(not(&lift(&p2)))),
+                Node::Ite(p1, p2, p3) => {
+                    let condition = lift(&p1);
+                    condition
+                        .nand(lift(&p2))
+                        .nand(not(&condition).nand(lift(&p3)))
+                }
+            };
+            memo.insert(formula.key(), converted.clone());
+            converted
+        }
+        convert(self, &mut HashMap::new())
+    }
+
+    fn to_nor_only(&self) -> Self {
+        fn convert<T: AbstractSyntaxTree>(formula: &T, memo: &mut HashMap<usize, T>) -> T {
+            if let Some(converted) = memo.get(&formula.key()) {
+                return converted.clone();
+            }
+            let not = |p: &T| p.nor(p.clone());
+            let mut lift = |p: &T| convert(p, memo);
+            let converted = match formula.node() {
+                Node::Variable(_) => formula.clone(),
+                Node::Not(p) => not(&lift(&p)),
+                Node::And(p1, p2) => not(&lift(&p1)).nor(not(&lift(&p2))),
+                Node::Or(p1, p2) => not(&lift(&p1).nor(lift(&p2))),
+                Node::Nand(p1, p2) => not(&not(&lift(&p1)).nor(not(&lift(&p2)))),
+                Node::Nor(p1, p2) => lift(&p1).nor(lift(&p2)),
+                Node::Ite(p1, p2, p3) => {
+                    let condition = lift(&p1);
+                    not(&condition).nor(lift(&p2)).nor(condition.nor(lift(&p3)))
+                }
+            };
+            memo.insert(formula.key(), converted.clone());
+            converted
+        }
+        convert(self, &mut HashMap::new())
+    }
+
+    fn dnf(&self) -> Result<Self, Error> {
+        self.dnf_within(&tseitin::distribution_budget())
+    }
+
+    fn cnf(&self) -> CnfConversion<Self> {
+        tseitin::cnf_conversion(self)
+    }
+
+    fn distributive_cnf(&self) -> Result<Self, RewriteError> {
+        tseitin::distributive_cnf(self)
+    }
+
+    fn cnf_within(&self, budget: &Budget) -> Result<CnfConversion<Self>, Error> {
+        let first = self.variables().last().map_or(0, |ident| ident + 1);
+        tseitin::cnf_conversion_within(self, first, budget)
+    }
+
+    fn dnf_within(&self, budget: &Budget) -> Result<Self, Error> {
+        budget.check_clauses(tseitin::estimate_dnf_terms(self))?;
+        Ok(RewriteRuleset::dnf()
+            .rewrite_recursive_hull_within(self.clone(), budget)?
+            .0)
+    }
+
+    fn canonical_dnf(&self) -> Self {
+        Self::from_ast(&canonical::canonical_dnf(&self.to_ast()))
+    }
+
+    fn canonical_cnf(&self) -> Self {
+        Self::from_ast(&canonical::canonical_cnf(&self.to_ast()))
+    }
+
+    fn sat(&self) -> bool {
+        portfolio::solve(&tseitin::cnf_clauses(self)).is_some()
+    }
+
+    fn verify_model(&self, model: &Model) -> bool {
+        self.evaluate_partial(model) == Truth::True
+    }
+
+    fn equal_modulo_ac(&self, other: &Self) -> bool {
+        let normalize = |formula: &Self| Rc::new(NaryNode::from(formula)).sorted();
+        normalize(self) == normalize(other)
+    }
 }
 
 impl AbstractSyntaxTree for AST {
@@ -59,65 +326,39 @@ impl AbstractSyntaxTree for AST {
         Rc::new(ASTNode::Or(self.clone(), other))
     }
 
-    fn dnf(&self) -> AST {
-        let ruleset = RewriteRuleset {
-            name: "DNF conversion",
-            rules: vec![
-                RewriteRule {
-                    name: "double negation elimination",
-                    top: procmacro::propositional_logic! { NOT NOT x },
-                    bot: procmacro::propositional_logic! { x },
-                },
-                RewriteRule {
-                    name: "de morgan's theorem for disjunction",
-                    top: procmacro::propositional_logic! { NOT (x OR y) },
-                    bot: procmacro::propositional_logic! { (NOT x AND NOT y) },
-                },
-                RewriteRule {
-                    name: "de morgan's theorem for conjunction",
-                    top: procmacro::propositional_logic! { NOT (x AND y) },
-                    bot: procmacro::propositional_logic! { (NOT x OR NOT y) },
-                },
-                RewriteRule {
-                    name: "left-distributive property of conjunction over disjunction",
-                    top: procmacro::propositional_logic! { (x AND (y OR z)) },
-                    bot: procmacro::propositional_logic! { ((x AND y) OR (x AND z)) },
-                },
-            ],
-        };
-        ruleset.rewrite_recursive_hull(self.clone())
-    }
-
-    fn cnf(&self) -> AST {
-        let ruleset = RewriteRuleset {
-            name: "CNF conversion",
-            rules: vec![
-                RewriteRule {
-                    name: "double negation elimination",
-                    top: procmacro::propositional_logic! { NOT NOT x },
-                    bot: procmacro::propositional_logic! { x },
-                },
-                RewriteRule {
-                    name: "de morgan's theorem for disjunction",
-                    top: procmacro::propositional_logic! { NOT (x OR y) },
-                    bot: procmacro::propositional_logic! { (NOT x AND NOT y) },
-                },
-                RewriteRule {
-                    name: "de morgan's theorem for disjunction",
-                    top: procmacro::propositional_logic! { NOT (x OR y) },
-                    bot: procmacro::propositional_logic! { (NOT x AND NOT y) },
-                },
-                RewriteRule {
-                    name: "left-distributive property of disjunction over conjunction",
-                    top: procmacro::propositional_logic! { (x OR (y AND z)) },
-                    bot: procmacro::propositional_logic! { ((x OR y) AND (x OR z)) },
-                },
-            ],
-        };
-        ruleset.rewrite_recursive_hull(self.clone())
+    fn nand(&self, other: AST) -> AST {
+        Rc::new(ASTNode::Nand(self.clone(), other))
     }
 
-    fn sat(&self) -> bool {
-        DPLLSolver::from(self).dpll()
+    fn nor(&self, other: AST) -> AST {
+        Rc::new(ASTNode::Nor(self.clone(), other))
+    }
+
+    fn ite(&self, then: AST, otherwise: AST) -> AST {
+        Rc::new(ASTNode::Ite(self.clone(), then, otherwise))
+    }
+
+    fn node(&self) -> Node<AST> {
+        match &**self {
+            ASTNode::Variable(ident) => Node::Variable(*ident),
+            ASTNode::Not(p) => Node::Not(p.clone()),
+            ASTNode::And(p1, p2) => Node::And(p1.clone(), p2.clone()),
+            ASTNode::Or(p1, p2) => Node::Or(p1.clone(), p2.clone()),
+            ASTNode::Nand(p1, p2) => Node::Nand(p1.clone(), p2.clone()),
+            ASTNode::Nor(p1, p2) => Node::Nor(p1.clone(), p2.clone()),
+            ASTNode::Ite(p1, p2, p3) => Node::Ite(p1.clone(), p2.clone(), p3.clone()),
+        }
+    }
+
+    fn key(&self) -> usize {
+        Rc::as_ptr(self) as usize
+    }
+
+    fn from_ast(formula: &AST) -> AST {
+        formula.clone()
+    }
+
+    fn to_ast(&self) -> AST {
+        self.clone()
     }
 }
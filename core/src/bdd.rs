@@ -0,0 +1,281 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    rc::Rc,
+};
+
+use crate::ast::{ASTNode, AbstractSyntaxTree, Ident, Model, AST};
+
+const FALSE: usize = 0;
+const TRUE: usize = 1;
+const TERMINAL: Ident = Ident::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Node {
+    var: Ident,
+    low: usize,
+    high: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bdd {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+impl fmt::Display for Bdd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |index: usize| match index {
+            FALSE => "0".to_owned(),
+            TRUE => "1".to_owned(),
+            _ => format!("n{}", index),
+        };
+        if self.root <= TRUE {
+            return write!(f, "{}", name(self.root));
+        }
+        let lines = self
+            .nodes
+            .iter()
+            .enumerate()
+            .skip(2)
+            .map(|(index, node)| {
+                format!(
+                    "{} := (var{} ? {} : {})",
+                    name(index),
+                    node.var,
+                    name(node.high),
+                    name(node.low)
+                )
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl From<&AST> for Bdd {
+    fn from(value: &AST) -> Self {
+        let mut manager = Manager::new();
+        let root = manager.build(value, &mut HashMap::new());
+        manager.canonicalize(root)
+    }
+}
+
+impl Bdd {
+    pub fn size(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    pub fn is_tautology(&self) -> bool {
+        self.root == TRUE
+    }
+
+    pub fn is_contradiction(&self) -> bool {
+        self.root == FALSE
+    }
+
+    pub fn variables(&self) -> BTreeSet<Ident> {
+        self.nodes[2..].iter().map(|node| node.var).collect()
+    }
+
+    pub fn evaluate(&self, model: &Model) -> bool {
+        let mut index = self.root;
+        while index > TRUE {
+            let node = &self.nodes[index];
+            index = match model.get(node.var).unwrap_or(false) {
+                true => node.high,
+                false => node.low,
+            };
+        }
+        index == TRUE
+    }
+
+    pub fn paths(&self, terminal: bool) -> Vec<BTreeMap<Ident, bool>> {
+        let target = match terminal {
+            true => TRUE,
+            false => FALSE,
+        };
+        let mut paths = vec![];
+        let mut stack = vec![(self.root, BTreeMap::new())];
+        while let Some((index, path)) = stack.pop() {
+            if index <= TRUE {
+                if index == target {
+                    paths.push(path);
+                }
+                continue;
+            }
+            let node = &self.nodes[index];
+            let mut low = path.clone();
+            low.insert(node.var, false);
+            let mut high = path;
+            high.insert(node.var, true);
+            stack.push((node.high, high));
+            stack.push((node.low, low));
+        }
+        paths
+    }
+
+    pub fn to_ast(&self) -> AST {
+        let var = <AST as AbstractSyntaxTree>::variable(0);
+        match self.root {
+            FALSE => var.and(var.not()),
+            TRUE => var.or(var.not()),
+            _ => {
+                let mut formulas: Vec<Option<AST>> = vec![None, None];
+                for node in &self.nodes[2..] {
+                    let var = <AST as AbstractSyntaxTree>::variable(node.var);
+                    let (low, high) = (&formulas[node.low], &formulas[node.high]);
+                    let formula = match ((node.low, low), (node.high, high)) {
+                        ((FALSE, _), (TRUE, _)) => var,
+                        ((TRUE, _), (FALSE, _)) => var.not(),
+                        ((FALSE, _), (_, Some(high))) => var.and(high.clone()),
+                        ((TRUE, _), (_, Some(high))) => var.not().or(high.clone()),
+                        ((_, Some(low)), (FALSE, _)) => var.not().and(low.clone()),
+                        ((_, Some(low)), (TRUE, _)) => var.or(low.clone()),
+                        ((_, Some(low)), (_, Some(high))) => var.ite(high.clone(), low.clone()),
+                        _ => unreachable!(),
+                    };
+                    formulas.push(Some(formula));
+                }
+                formulas[self.root].clone().unwrap()
+            }
+        }
+    }
+}
+
+struct Manager {
+    nodes: Vec<Node>,
+    unique: HashMap<Node, usize>,
+    computed: HashMap<(usize, usize, usize), usize>,
+}
+
+impl Manager {
+    fn new() -> Self {
+        let terminal = |value| Node {
+            var: TERMINAL,
+            low: value,
+            high: value,
+        };
+        Self {
+            nodes: vec![terminal(FALSE), terminal(TRUE)],
+            unique: HashMap::new(),
+            computed: HashMap::new(),
+        }
+    }
+
+    fn node(&mut self, var: Ident, low: usize, high: usize) -> usize {
+        if low == high {
+            return low;
+        }
+        let node = Node { var, low, high };
+        if let Some(index) = self.unique.get(&node) {
+            return *index;
+        }
+        self.nodes.push(node);
+        self.unique.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    fn cofactors(&self, index: usize, var: Ident) -> (usize, usize) {
+        let node = self.nodes[index];
+        match node.var == var {
+            true => (node.low, node.high),
+            false => (index, index),
+        }
+    }
+
+    fn ite(&mut self, condition: usize, then: usize, otherwise: usize) -> usize {
+        match (condition, then, otherwise) {
+            (TRUE, _, _) => return then,
+            (FALSE, _, _) => return otherwise,
+            (_, TRUE, FALSE) => return condition,
+            _ if then == otherwise => return then,
+            _ => (),
+        }
+        if let Some(index) = self.computed.get(&(condition, then, otherwise)) {
+            return *index;
+        }
+        let var = [condition, then, otherwise]
+            .iter()
+            .map(|index| self.nodes[*index].var)
+            .min()
+            .unwrap();
+        let (c0, c1) = self.cofactors(condition, var);
+        let (t0, t1) = self.cofactors(then, var);
+        let (e0, e1) = self.cofactors(otherwise, var);
+        let low = self.ite(c0, t0, e0);
+        let high = self.ite(c1, t1, e1);
+        let index = self.node(var, low, high);
+        self.computed.insert((condition, then, otherwise), index);
+        index
+    }
+
+    fn build(&mut self, formula: &AST, memo: &mut HashMap<*const ASTNode, usize>) -> usize {
+        if let Some(index) = memo.get(&Rc::as_ptr(formula)) {
+            return *index;
+        }
+        let index = match &**formula {
+            ASTNode::Variable(ident) => self.node(*ident, FALSE, TRUE),
+            ASTNode::Not(p) => {
+                let p = self.build(p, memo);
+                self.ite(p, FALSE, TRUE)
+            }
+            ASTNode::And(p1, p2) => {
+                let (p1, p2) = (self.build(p1, memo), self.build(p2, memo));
+                self.ite(p1, p2, FALSE)
+            }
+            ASTNode::Or(p1, p2) => {
+                let (p1, p2) = (self.build(p1, memo), self.build(p2, memo));
+                self.ite(p1, TRUE, p2)
+            }
+            ASTNode::Nand(p1, p2) => {
+                let (p1, p2) = (self.build(p1, memo), self.build(p2, memo));
+                let conjunction = self.ite(p1, p2, FALSE);
+                self.ite(conjunction, FALSE, TRUE)
+            }
+            ASTNode::Nor(p1, p2) => {
+                let (p1, p2) = (self.build(p1, memo), self.build(p2, memo));
+                let disjunction = self.ite(p1, TRUE, p2);
+                self.ite(disjunction, FALSE, TRUE)
+            }
+            ASTNode::Ite(p1, p2, p3) => {
+                let condition = self.build(p1, memo);
+                let then = self.build(p2, memo);
+                let otherwise = self.build(p3, memo);
+                self.ite(condition, then, otherwise)
+            }
+        };
+        memo.insert(Rc::as_ptr(formula), index);
+        index
+    }
+
+    fn canonicalize(&self, root: usize) -> Bdd {
+        let mut nodes = self.nodes[..2].to_vec();
+        let mut renumbered = HashMap::from([(FALSE, FALSE), (TRUE, TRUE)]);
+        let mut stack = vec![(root, false)];
+        while let Some((index, expanded)) = stack.pop() {
+            if renumbered.contains_key(&index) {
+                continue;
+            }
+            let node = self.nodes[index];
+            match expanded {
+                false => {
+                    stack.push((index, true));
+                    stack.push((node.high, false));
+                    stack.push((node.low, false));
+                }
+                true => {
+                    nodes.push(Node {
+                        var: node.var,
+                        low: renumbered[&node.low],
+                        high: renumbered[&node.high],
+                    });
+                    renumbered.insert(index, nodes.len() - 1);
+                }
+            }
+        }
+        Bdd {
+            root: renumbered[&root],
+            nodes,
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use std::{
+    fmt, fs, io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ast::AST,
+    budget::dag_size,
+    cdcl::{CDCLSolver, SolverConfig},
+    dimacs::{parse_dimacs, DimacsError},
+    lookahead::Lookahead,
+    preprocess::Preprocessor,
+    random::{random_cnf, random_formula, Rng},
+    rewrite::RewriteRuleset,
+    sat::{Clause, DPLLSolver, IncompleteSolver, Solver},
+    survey::SurveyPropagation,
+    walksat::WalkSat,
+};
+
+#[derive(Debug)]
+pub enum BenchmarkError {
+    Io { path: String, kind: io::ErrorKind },
+    Dimacs { path: String, error: DimacsError },
+}
+
+impl fmt::Display for BenchmarkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BenchmarkError::Io { path, kind } => write!(f, "cannot read {}: {}", path, kind),
+            BenchmarkError::Dimacs { path, error } => write!(f, "{}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for BenchmarkError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub name: String,
+    pub clauses: Vec<Clause>,
+}
+
+impl Instance {
+    pub fn new(name: &str, clauses: Vec<Clause>) -> Self {
+        Self {
+            name: name.to_owned(),
+            clauses,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BenchmarkError> {
+        let display = path.display().to_string();
+        let input = fs::read_to_string(path).map_err(|err| BenchmarkError::Io {
+            path: display.clone(),
+            kind: err.kind(),
+        })?;
+        let clauses = parse_dimacs(&input).map_err(|error| BenchmarkError::Dimacs {
+            path: display.clone(),
+            error,
+        })?;
+        let name = path
+            .file_stem()
+            .map_or(display, |stem| stem.to_string_lossy().into_owned());
+        Ok(Self::new(&name, clauses))
+    }
+}
+
+pub fn load_directory(path: &Path) -> Result<Vec<Instance>, BenchmarkError> {
+    let error = |err: io::Error| BenchmarkError::Io {
+        path: path.display().to_string(),
+        kind: err.kind(),
+    };
+    let mut paths = fs::read_dir(path)
+        .map_err(error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(error)?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "cnf"));
+    paths.sort();
+    paths.iter().map(|path| Instance::load(path)).collect()
+}
+
+pub fn random_suite(
+    seed: u64,
+    count: usize,
+    variables: usize,
+    clauses: usize,
+    width: usize,
+) -> Vec<Instance> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|index| {
+            Instance::new(
+                &format!("random-{}-{}", seed, index),
+                random_cnf(&mut rng, variables, clauses, width),
+            )
+        })
+        .collect()
+}
+
+pub fn random_formulas(seed: u64, count: usize, depth: usize, variables: usize) -> Vec<AST> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| random_formula(&mut rng, depth, variables))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolverKind {
+    Dpll,
+    Cdcl(SolverConfig),
+    Preprocessed(Preprocessor),
+    Lookahead(Lookahead),
+    SurveyPropagation(SurveyPropagation),
+    WalkSat(WalkSat),
+}
+
+impl SolverKind {
+    fn solve(&self, clauses: &[Clause]) -> Outcome {
+        let result = match *self {
+            SolverKind::Dpll => DPLLSolver::from(vec![]).try_solve_clauses(clauses),
+            SolverKind::Cdcl(config) => CDCLSolver::new(config).try_solve_clauses(clauses),
+            SolverKind::Preprocessed(preprocessor) => preprocessor.try_solve_clauses(clauses),
+            SolverKind::Lookahead(lookahead) => lookahead.try_solve_clauses(clauses),
+            SolverKind::SurveyPropagation(survey) => survey.try_solve_clauses(clauses),
+            SolverKind::WalkSat(walksat) => walksat.try_solve_clauses(clauses),
+        };
+        let Ok(model) = result else {
+            return Outcome::Unknown;
+        };
+        match model {
+            Some(model) if clauses.iter().all(|clause| clause.is_satisfied(&model)) => {
+                Outcome::Satisfiable
+            }
+            Some(_) => Outcome::Invalid,
+            None => Outcome::Unsatisfiable,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Configuration {
+    pub name: String,
+    pub solver: SolverKind,
+}
+
+impl Configuration {
+    pub fn new(name: &str, solver: SolverKind) -> Self {
+        Self {
+            name: name.to_owned(),
+            solver,
+        }
+    }
+
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("dpll", SolverKind::Dpll),
+            Self::new("cdcl", SolverKind::Cdcl(SolverConfig::default())),
+            Self::new(
+                "preprocessed",
+                SolverKind::Preprocessed(Preprocessor::default()),
+            ),
+            Self::new("lookahead", SolverKind::Lookahead(Lookahead::default())),
+            Self::new(
+                "survey",
+                SolverKind::SurveyPropagation(SurveyPropagation::default()),
+            ),
+            Self::new("walksat", SolverKind::WalkSat(WalkSat::default())),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Satisfiable,
+    Unsatisfiable,
+    Unknown,
+    Invalid,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Satisfiable => write!(f, "sat"),
+            Outcome::Unsatisfiable => write!(f, "unsat"),
+            Outcome::Unknown => write!(f, "unknown"),
+            Outcome::Invalid => write!(f, "invalid"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub instance: String,
+    pub configuration: String,
+    pub outcome: Outcome,
+    pub time: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    pub name: String,
+    pub runs: usize,
+    pub solved: usize,
+    pub invalid: usize,
+    pub total: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} {:>4}/{:<4} {:>12} {:>12} {:>12} {:>12}",
+            self.name,
+            self.solved,
+            self.runs,
+            milliseconds(self.total),
+            milliseconds(self.mean),
+            milliseconds(self.median),
+            milliseconds(self.max)
+        )?;
+        if self.invalid > 0 {
+            write!(f, " ({} invalid)", self.invalid)?;
+        }
+        Ok(())
+    }
+}
+
+impl Statistics {
+    fn new(name: &str, mut times: Vec<Duration>, solved: usize, invalid: usize) -> Self {
+        times.sort_unstable();
+        let total = times.iter().sum::<Duration>();
+        Self {
+            name: name.to_owned(),
+            runs: times.len(),
+            solved,
+            invalid,
+            total,
+            mean: total / times.len().max(1) as u32,
+            median: times.get(times.len() / 2).copied().unwrap_or_default(),
+            max: times.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    pub runs: Vec<Run>,
+    pub statistics: Vec<Statistics>,
+    pub disagreements: Vec<String>,
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} {:>9} {:>12} {:>12} {:>12} {:>12}",
+            "configuration", "solved", "total", "mean", "median", "max"
+        )?;
+        for statistics in &self.statistics {
+            write!(f, "\n{}", statistics)?;
+        }
+        for instance in &self.disagreements {
+            write!(f, "\nwarning: configurations disagree on {}", instance)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Benchmark {
+    pub configurations: Vec<Configuration>,
+    pub repetitions: usize,
+}
+
+impl Default for Benchmark {
+    fn default() -> Self {
+        Self {
+            configurations: Configuration::defaults(),
+            repetitions: 1,
+        }
+    }
+}
+
+impl Benchmark {
+    pub fn new(configurations: Vec<Configuration>) -> Self {
+        Self {
+            configurations,
+            ..Self::default()
+        }
+    }
+
+    pub fn run(&self, instances: &[Instance]) -> BenchmarkReport {
+        let mut runs = vec![];
+        let mut disagreements = vec![];
+        for instance in instances {
+            let start = runs.len();
+            for configuration in &self.configurations {
+                let (outcome, time) =
+                    self.measure(|| configuration.solver.solve(&instance.clauses));
+                runs.push(Run {
+                    instance: instance.name.clone(),
+                    configuration: configuration.name.clone(),
+                    outcome,
+                    time,
+                });
+            }
+            let outcomes = runs[start..]
+                .iter()
+                .map(|run: &Run| run.outcome)
+                .collect::<Vec<_>>();
+            if outcomes.contains(&Outcome::Satisfiable)
+                && outcomes.contains(&Outcome::Unsatisfiable)
+            {
+                disagreements.push(instance.name.clone());
+            }
+        }
+        let statistics = self
+            .configurations
+            .iter()
+            .map(|configuration| {
+                let runs = runs
+                    .iter()
+                    .filter(|run| run.configuration == configuration.name)
+                    .collect::<Vec<_>>();
+                let count = |predicate: fn(Outcome) -> bool| {
+                    runs.iter().filter(|run| predicate(run.outcome)).count()
+                };
+                Statistics::new(
+                    &configuration.name,
+                    runs.iter().map(|run| run.time).collect(),
+                    count(|outcome| {
+                        matches!(outcome, Outcome::Satisfiable | Outcome::Unsatisfiable)
+                    }),
+                    count(|outcome| outcome == Outcome::Invalid),
+                )
+            })
+            .collect();
+        BenchmarkReport {
+            runs,
+            statistics,
+            disagreements,
+        }
+    }
+
+    pub fn run_rewriting(&self, rulesets: &[RewriteRuleset], formulas: &[AST]) -> RewriteReport {
+        let entries = rulesets
+            .iter()
+            .map(|ruleset| {
+                let mut times = vec![];
+                let mut rewritten = 0;
+                let mut nodes = 0;
+                for formula in formulas {
+                    let (result, time) =
+                        self.measure(|| ruleset.rewrite_recursive_hull(formula.clone()));
+                    if let Ok(result) = result {
+                        rewritten += 1;
+                        nodes += dag_size(&result, usize::MAX);
+                    }
+                    times.push(time);
+                }
+                let statistics = Statistics::new(&ruleset.name, times, rewritten, 0);
+                RewriteStatistics { statistics, nodes }
+            })
+            .collect();
+        RewriteReport { entries }
+    }
+
+    fn measure<T>(&self, mut task: impl FnMut() -> T) -> (T, Duration) {
+        let mut best = None;
+        let mut result = None;
+        for _ in 0..self.repetitions.max(1) {
+            let start = Instant::now();
+            let value = task();
+            let elapsed = start.elapsed();
+            best = Some(best.map_or(elapsed, |best: Duration| best.min(elapsed)));
+            result = Some(value);
+        }
+        (result.unwrap(), best.unwrap())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewriteStatistics {
+    pub statistics: Statistics,
+    pub nodes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewriteReport {
+    pub entries: Vec<RewriteStatistics>,
+}
+
+impl fmt::Display for RewriteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} {:>9} {:>12} {:>12} {:>12} {:>12} {:>8}",
+            "ruleset", "rewritten", "total", "mean", "median", "max", "nodes"
+        )?;
+        for entry in &self.entries {
+            write!(f, "\n{} {:>8}", entry.statistics, entry.nodes)?;
+        }
+        Ok(())
+    }
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
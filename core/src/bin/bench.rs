@@ -0,0 +1,31 @@
+use std::{env, path::Path, process};
+
+use core::{
+    benchmark::{self, Benchmark},
+    rewrite::RewriteRuleset,
+};
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let instances = match args.as_slice() {
+        [] => benchmark::random_suite(0, 20, 50, 213, 3),
+        [directory] => match benchmark::load_directory(Path::new(directory)) {
+            Ok(instances) => instances,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("usage: bench [directory of .cnf files]");
+            process::exit(2);
+        }
+    };
+    let benchmark = Benchmark::default();
+    println!("{} instances", instances.len());
+    println!("{}\n", benchmark.run(&instances));
+    let formulas = benchmark::random_formulas(0, 50, 6, 6);
+    let rulesets = [RewriteRuleset::cnf(), RewriteRuleset::dnf()];
+    println!("{} formulas", formulas.len());
+    println!("{}", benchmark.run_rewriting(&rulesets, &formulas));
+}
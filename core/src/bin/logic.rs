@@ -0,0 +1,29 @@
+use std::{env, process};
+
+use core::{ast::AbstractSyntaxTree, parse, tseitin::Equivalence};
+
+fn main() {
+    let input = env::args().skip(1).collect::<Vec<_>>().join(" ");
+    if input.is_empty() {
+        eprintln!("usage: logic <formula>");
+        process::exit(2);
+    }
+    let formula = match parse::parse(&input) {
+        Ok(formula) => formula,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    println!("formula:     {}", formula);
+    match formula.dnf() {
+        Ok(dnf) => println!("dnf:         {}", dnf),
+        Err(err) => println!("dnf:         {}", err),
+    }
+    let cnf = formula.cnf();
+    match cnf.equivalence {
+        Equivalence::Equivalent => println!("cnf:         {}", cnf.formula),
+        Equivalence::Equisatisfiable => println!("cnf:         {} (equisatisfiable)", cnf.formula),
+    }
+    println!("satisfiable: {}", formula.sat());
+}
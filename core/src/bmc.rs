@@ -0,0 +1,147 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    context::Context,
+    transition::{rename, TransitionSystem},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    pub steps: Vec<Model>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(step, model)| {
+                let mut assignment = model.iter().collect::<Vec<_>>();
+                assignment.sort();
+                let values = assignment
+                    .into_iter()
+                    .map(|(ident, value)| format!("var{}={}", ident, value as u8))
+                    .collect::<Vec<_>>();
+                format!("{}: {}", step, values.join(" "))
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+pub struct Bmc<'a> {
+    system: &'a TransitionSystem,
+    context: Context,
+    layout: Vec<Ident>,
+    unrolled: usize,
+}
+
+impl<'a> Bmc<'a> {
+    pub fn new(system: &'a TransitionSystem) -> Self {
+        let mut bmc = Self::unconstrained(system);
+        bmc.assert_at(&system.init, 0);
+        bmc
+    }
+
+    pub(crate) fn unconstrained(system: &'a TransitionSystem) -> Self {
+        let mut layout = system
+            .state
+            .iter()
+            .map(|(current, _)| *current)
+            .collect::<Vec<_>>();
+        layout.extend(system.inputs());
+        Self {
+            system,
+            context: Context::default(),
+            layout,
+            unrolled: 0,
+        }
+    }
+
+    pub fn check(&mut self, bound: usize) -> Option<Trace> {
+        (0..=bound).find_map(|step| self.check_step(step))
+    }
+
+    pub fn check_step(&mut self, step: usize) -> Option<Trace> {
+        self.unroll(step);
+        match self.reaches_bad(step) {
+            true => Some(self.trace(step)),
+            false => None,
+        }
+    }
+
+    pub(crate) fn reaches_bad(&mut self, step: usize) -> bool {
+        let bad = self.at(&self.system.bad, step);
+        self.context.check_complete(&[bad])
+    }
+
+    pub(crate) fn assert_at(&mut self, formula: &AST, step: usize) {
+        let formula = self.at(formula, step);
+        self.context.assert(&formula);
+    }
+
+    pub(crate) fn assert_distinct(&mut self, first: usize, second: usize) {
+        let width = self.layout.len();
+        let timed = |step: usize, position: usize| {
+            <AST as AbstractSyntaxTree>::variable((step * width + position) as Ident)
+        };
+        let differs = (0..self.system.state.len())
+            .map(|position| {
+                let (lhs, rhs) = (timed(first, position), timed(second, position));
+                lhs.and(rhs.not()).or(lhs.not().and(rhs))
+            })
+            .reduce(|lhs, rhs| lhs.or(rhs));
+        match differs {
+            Some(differs) => self.context.assert(&differs),
+            None => {
+                let var = <AST as AbstractSyntaxTree>::variable(0);
+                self.context.assert(&var.and(var.not()));
+            }
+        }
+    }
+
+    pub(crate) fn unroll(&mut self, steps: usize) {
+        while self.unrolled < steps {
+            let trans = self.at(&self.system.trans, self.unrolled);
+            self.context.assert(&trans);
+            self.unrolled += 1;
+        }
+    }
+
+    pub(crate) fn at(&self, formula: &AST, step: usize) -> AST {
+        let width = self.layout.len();
+        let mut renaming = HashMap::new();
+        for (position, ident) in self.layout.iter().enumerate() {
+            renaming.insert(*ident, (step * width + position) as Ident);
+        }
+        for (position, (_, next)) in self.system.state.iter().enumerate() {
+            renaming.insert(*next, ((step + 1) * width + position) as Ident);
+        }
+        rename(formula, &renaming)
+    }
+
+    fn trace(&self, steps: usize) -> Trace {
+        let model = self.context.model().unwrap_or_default();
+        let width = self.layout.len();
+        Trace {
+            steps: (0..=steps)
+                .map(|step| {
+                    self.layout
+                        .iter()
+                        .enumerate()
+                        .map(|(position, ident)| {
+                            let timed = (step * width + position) as Ident;
+                            (*ident, model.get(timed).unwrap_or(false))
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn bmc(system: &TransitionSystem, bound: usize) -> Option<Trace> {
+    Bmc::new(system).check(bound)
+}
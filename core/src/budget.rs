@@ -0,0 +1,89 @@
+use std::{collections::HashSet, fmt};
+
+use crate::ast::{AbstractSyntaxTree, Node};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub max_nodes: Option<usize>,
+    pub max_clauses: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetError {
+    NodeLimitExceeded { limit: usize, nodes: usize },
+    ClauseLimitExceeded { limit: usize, clauses: usize },
+}
+
+impl fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetError::NodeLimitExceeded { limit, nodes } => {
+                write!(f, "{} nodes exceed the limit of {}", nodes, limit)
+            }
+            BudgetError::ClauseLimitExceeded { limit, clauses } => {
+                write!(f, "{} clauses exceed the limit of {}", clauses, limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BudgetError {}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn check_nodes<T: AbstractSyntaxTree>(&self, formula: &T) -> Result<(), BudgetError> {
+        let Some(limit) = self.max_nodes else {
+            return Ok(());
+        };
+        match dag_size(formula, limit.saturating_add(1)) {
+            nodes if nodes > limit => Err(BudgetError::NodeLimitExceeded { limit, nodes }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_node_count(&self, nodes: usize) -> Result<(), BudgetError> {
+        match self.max_nodes {
+            Some(limit) if nodes > limit => Err(BudgetError::NodeLimitExceeded { limit, nodes }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_clauses(&self, clauses: usize) -> Result<(), BudgetError> {
+        match self.max_clauses {
+            Some(limit) if clauses > limit => {
+                Err(BudgetError::ClauseLimitExceeded { limit, clauses })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn dag_size<T: AbstractSyntaxTree>(formula: &T, cutoff: usize) -> usize {
+    let mut visited = HashSet::new();
+    let mut subtrees = vec![formula.clone()];
+    while let Some(subtree) = subtrees.pop() {
+        if visited.len() >= cutoff {
+            break;
+        }
+        if !visited.insert(subtree.key()) {
+            continue;
+        }
+        match subtree.node() {
+            Node::Variable(_) => (),
+            Node::Not(p) => subtrees.push(p),
+            Node::And(p1, p2) | Node::Or(p1, p2) | Node::Nand(p1, p2) | Node::Nor(p1, p2) => {
+                subtrees.push(p1);
+                subtrees.push(p2);
+            }
+            Node::Ite(p1, p2, p3) => {
+                subtrees.push(p1);
+                subtrees.push(p2);
+                subtrees.push(p3);
+            }
+        }
+    }
+    visited.len()
+}
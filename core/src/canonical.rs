@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, AST},
+    bdd::Bdd,
+};
+
+pub type Term = BTreeMap<Ident, bool>;
+
+pub fn prime_implicates(formula: &AST) -> BTreeSet<Term> {
+    consensus(
+        Bdd::from(formula)
+            .paths(false)
+            .into_iter()
+            .map(|path| {
+                path.into_iter()
+                    .map(|(ident, value)| (ident, !value))
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+pub fn prime_implicants(formula: &AST) -> BTreeSet<Term> {
+    consensus(Bdd::from(formula).paths(true).into_iter().collect())
+}
+
+pub fn canonical_cnf(formula: &AST) -> AST {
+    let var = <AST as AbstractSyntaxTree>::variable(0);
+    prime_implicates(formula)
+        .iter()
+        .map(|clause| join(clause, |lhs, rhs| lhs.or(rhs)).unwrap_or(var.and(var.not())))
+        .reduce(|cnf, clause| cnf.and(clause))
+        .unwrap_or(var.or(var.not()))
+}
+
+pub fn canonical_dnf(formula: &AST) -> AST {
+    let var = <AST as AbstractSyntaxTree>::variable(0);
+    prime_implicants(formula)
+        .iter()
+        .map(|term| join(term, |lhs, rhs| lhs.and(rhs)).unwrap_or(var.or(var.not())))
+        .reduce(|dnf, term| dnf.or(term))
+        .unwrap_or(var.and(var.not()))
+}
+
+fn consensus(terms: BTreeSet<Term>) -> BTreeSet<Term> {
+    let variables = terms
+        .iter()
+        .flat_map(|term| term.keys().copied())
+        .collect::<BTreeSet<_>>();
+    let mut terms = reduce(terms);
+    for ident in variables {
+        let resolvents = terms
+            .iter()
+            .filter(|term| term.get(&ident) == Some(&true))
+            .flat_map(|positive| {
+                terms
+                    .iter()
+                    .filter(|term| term.get(&ident) == Some(&false))
+                    .filter_map(move |negative| resolve(positive, negative, ident))
+            })
+            .collect::<Vec<_>>();
+        terms.extend(resolvents);
+        terms = reduce(terms);
+    }
+    terms
+}
+
+fn resolve(positive: &Term, negative: &Term, ident: Ident) -> Option<Term> {
+    let mut resolvent = positive.clone();
+    resolvent.remove(&ident);
+    for (var, value) in negative {
+        if *var != ident && *resolvent.entry(*var).or_insert(*value) != *value {
+            return None;
+        }
+    }
+    Some(resolvent)
+}
+
+fn reduce(clauses: BTreeSet<Term>) -> BTreeSet<Term> {
+    let mut clauses = clauses.into_iter().collect::<Vec<_>>();
+    clauses.sort_by_key(|clause| clause.len());
+    let mut reduced: Vec<Term> = vec![];
+    for clause in clauses {
+        let subsumed = reduced.iter().any(|kept| {
+            kept.iter()
+                .all(|(ident, value)| clause.get(ident) == Some(value))
+        });
+        if !subsumed {
+            reduced.push(clause);
+        }
+    }
+    reduced.into_iter().collect()
+}
+
+fn join(term: &Term, operator: impl Fn(AST, AST) -> AST) -> Option<AST> {
+    term.iter()
+        .map(|(ident, value)| {
+            let var = <AST as AbstractSyntaxTree>::variable(*ident);
+            match value {
+                true => var,
+                false => var.not(),
+            }
+        })
+        .reduce(operator)
+}
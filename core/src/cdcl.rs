@@ -0,0 +1,1050 @@
+use std::fmt;
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    error::Error,
+    random::Rng,
+    sat::{stream_clauses_from_tree, Clause, Literal, Polarity, Solver},
+    trace::{trace_event, trace_span},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Lit(u32);
+
+impl Lit {
+    fn new(var: usize, negative: bool) -> Self {
+        Lit((var as u32) << 1 | negative as u32)
+    }
+
+    fn var(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    fn is_negative(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    fn negate(self) -> Self {
+        Lit(self.0 ^ 1)
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl From<&Literal> for Lit {
+    fn from(value: &Literal) -> Self {
+        Lit::new(
+            value.identifier() as usize,
+            *value.polarity() == Polarity::Negative,
+        )
+    }
+}
+
+impl From<Lit> for Literal {
+    fn from(value: Lit) -> Self {
+        Literal::new(
+            value.var() as Ident,
+            match value.is_negative() {
+                true => Polarity::Negative,
+                false => Polarity::Positive,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestartPolicy {
+    Never,
+    Fixed(u64),
+    Geometric { initial: u64, factor: f64 },
+    Luby(u64),
+    Ema { fast: f64, slow: f64, margin: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    ConflictLimitExceeded {
+        conflicts: u64,
+        decision_level: usize,
+        learnts: usize,
+    },
+    FlipLimitExceeded {
+        tries: usize,
+        flips: usize,
+    },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::ConflictLimitExceeded {
+                conflicts,
+                decision_level,
+                learnts,
+            } => write!(
+                f,
+                "conflict limit exceeded after {} conflicts at decision level {} with {} learnt clauses",
+                conflicts, decision_level, learnts
+            ),
+            SolveError::FlipLimitExceeded { tries, flips } => {
+                write!(f, "flip limit exceeded after {} tries of {} flips", tries, flips)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverConfig {
+    pub decay: f64,
+    pub restarts: RestartPolicy,
+    pub learnt_limit: usize,
+    pub phase_saving: bool,
+    pub target_phases: bool,
+    pub rephase_interval: Option<u64>,
+    pub chronological_backtracking: Option<usize>,
+    pub vivification: bool,
+    pub random_decisions: f64,
+    pub seed: u64,
+    pub conflict_limit: Option<u64>,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            decay: 0.95,
+            restarts: RestartPolicy::Luby(100),
+            learnt_limit: 2000,
+            phase_saving: true,
+            target_phases: true,
+            rephase_interval: Some(1000),
+            chronological_backtracking: None,
+            vivification: true,
+            random_decisions: 0.0,
+            seed: 0,
+            conflict_limit: None,
+        }
+    }
+}
+
+impl Solver for CDCLSolver {
+    fn solve(&self, clauses: &[Clause]) -> Option<Model> {
+        self.try_solve_clauses(clauses).ok().flatten()
+    }
+
+    fn try_solve_clauses(&self, clauses: &[Clause]) -> Result<Option<Model>, SolveError> {
+        let mut solver = self.clone();
+        for clause in clauses {
+            solver.add_clause(clause);
+        }
+        match solver.try_solve()? {
+            true => Ok(solver.model().cloned()),
+            false => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct RestartState {
+    conflicts: u64,
+    restarts: u64,
+    total: u64,
+    fast: f64,
+    slow: f64,
+}
+
+impl RestartState {
+    const WARMUP: u64 = 50;
+
+    fn conflict(&mut self, policy: RestartPolicy, block_distance: usize) -> bool {
+        self.conflicts += 1;
+        self.total += 1;
+        let restart = match policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::Fixed(interval) => self.conflicts >= interval,
+            RestartPolicy::Geometric { initial, factor } => {
+                self.conflicts as f64 >= initial as f64 * factor.powi(self.restarts as i32)
+            }
+            RestartPolicy::Luby(unit) => self.conflicts >= unit * luby(self.restarts),
+            RestartPolicy::Ema { fast, slow, margin } => {
+                let distance = block_distance as f64;
+                match self.total {
+                    1 => (self.fast, self.slow) = (distance, distance),
+                    _ => {
+                        self.fast += fast * (distance - self.fast);
+                        self.slow += slow * (distance - self.slow);
+                    }
+                }
+                self.total >= Self::WARMUP
+                    && self.conflicts >= Self::WARMUP
+                    && self.fast > margin * self.slow
+            }
+        };
+        if restart {
+            self.conflicts = 0;
+            self.restarts += 1;
+        }
+        restart
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PhaseState {
+    saved: Vec<bool>,
+    target: Vec<Option<bool>>,
+    target_size: usize,
+    rephases: u64,
+}
+
+impl PhaseState {
+    fn reserve(&mut self, variables: usize) {
+        self.saved.resize(variables, false);
+        self.target.resize(variables, None);
+    }
+
+    fn update_target(&mut self, trail: &[Lit]) {
+        if trail.len() <= self.target_size {
+            return;
+        }
+        self.target_size = trail.len();
+        self.target.fill(None);
+        for lit in trail {
+            self.target[lit.var()] = Some(!lit.is_negative());
+        }
+    }
+
+    fn rephase(&mut self) {
+        match self.rephases % 3 {
+            0 => self.saved.fill(false),
+            1 => self.saved.fill(true),
+            _ => {
+                for (saved, target) in self.saved.iter_mut().zip(&self.target) {
+                    *saved = target.unwrap_or(*saved);
+                }
+            }
+        }
+        self.target.fill(None);
+        self.target_size = 0;
+        self.rephases += 1;
+    }
+}
+
+fn luby(mut index: u64) -> u64 {
+    let mut size = 1;
+    let mut sequence = 0;
+    while size < index + 1 {
+        sequence += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != index {
+        size = (size - 1) / 2;
+        sequence -= 1;
+        index %= size;
+    }
+    1 << sequence
+}
+
+#[derive(Debug, Clone)]
+struct VariableOrder {
+    heap: Vec<usize>,
+    positions: Vec<Option<usize>>,
+    activity: Vec<f64>,
+    increment: f64,
+}
+
+impl Default for VariableOrder {
+    fn default() -> Self {
+        Self {
+            heap: vec![],
+            positions: vec![],
+            activity: vec![],
+            increment: 1.0,
+        }
+    }
+}
+
+impl VariableOrder {
+    fn reserve(&mut self, variables: usize) {
+        while self.activity.len() < variables {
+            self.activity.push(0.0);
+            self.positions.push(None);
+            self.insert(self.activity.len() - 1);
+        }
+    }
+
+    fn insert(&mut self, var: usize) {
+        if self.positions[var].is_none() {
+            self.positions[var] = Some(self.heap.len());
+            self.heap.push(var);
+            self.sift_up(self.heap.len() - 1);
+        }
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let last = self.heap.pop()?;
+        self.positions[last] = None;
+        if self.heap.is_empty() {
+            return Some(last);
+        }
+        let top = std::mem::replace(&mut self.heap[0], last);
+        self.positions[top] = None;
+        self.positions[last] = Some(0);
+        self.sift_down(0);
+        Some(top)
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.activity[var] += self.increment;
+        if self.activity[var] > 1e100 {
+            for activity in &mut self.activity {
+                *activity *= 1e-100;
+            }
+            self.increment *= 1e-100;
+        }
+        if let Some(position) = self.positions[var] {
+            self.sift_up(position);
+        }
+    }
+
+    fn decay(&mut self, factor: f64) {
+        self.increment /= factor;
+    }
+
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.activity[self.heap[parent]] >= self.activity[self.heap[position]] {
+                break;
+            }
+            self.swap(parent, position);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let mut largest = position;
+            for child in [2 * position + 1, 2 * position + 2] {
+                if child < self.heap.len()
+                    && self.activity[self.heap[child]] > self.activity[self.heap[largest]]
+                {
+                    largest = child;
+                }
+            }
+            if largest == position {
+                break;
+            }
+            self.swap(largest, position);
+            position = largest;
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a]] = Some(a);
+        self.positions[self.heap[b]] = Some(b);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CDCLSolver {
+    config: SolverConfig,
+    order: VariableOrder,
+    restarts: RestartState,
+    phases: PhaseState,
+    rng: Rng,
+    clauses: Vec<Vec<Lit>>,
+    block_distances: Vec<Option<usize>>,
+    learnts: usize,
+    reductions: i32,
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    trail_limits: Vec<usize>,
+    propagated: usize,
+    inconsistent: bool,
+    model: Option<Model>,
+    core: Vec<Literal>,
+}
+
+impl fmt::Display for CDCLSolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{{}}}",
+            self.clauses
+                .iter()
+                .map(|clause| Clause::new(clause.iter().map(|lit| Literal::from(*lit))).to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl TryFrom<&AST> for CDCLSolver {
+    type Error = Error;
+
+    fn try_from(value: &AST) -> Result<Self, Self::Error> {
+        let mut solver = Self::default();
+        for clause in stream_clauses_from_tree(value.cnf().formula) {
+            solver.add_clause(&clause?);
+        }
+        Ok(solver)
+    }
+}
+
+impl Extend<Clause> for CDCLSolver {
+    fn extend<I: IntoIterator<Item = Clause>>(&mut self, iter: I) {
+        for clause in iter {
+            self.add_clause(&clause);
+        }
+    }
+}
+
+impl From<Vec<Clause>> for CDCLSolver {
+    fn from(input: Vec<Clause>) -> Self {
+        let mut solver = Self::default();
+        for clause in &input {
+            solver.add_clause(clause);
+        }
+        solver
+    }
+}
+
+impl CDCLSolver {
+    const LEARNT_GROWTH: f64 = 1.1;
+
+    pub fn new(config: SolverConfig) -> Self {
+        Self {
+            config,
+            rng: Rng::new(config.seed),
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    pub fn variables(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn new_variable(&mut self) -> Ident {
+        let ident = self.variables();
+        self.reserve(ident);
+        ident as Ident
+    }
+
+    fn reserve(&mut self, var: usize) {
+        if var >= self.values.len() {
+            self.values.resize(var + 1, None);
+            self.levels.resize(var + 1, 0);
+            self.reasons.resize(var + 1, None);
+            self.watches.resize(2 * (var + 1), vec![]);
+            self.order.reserve(var + 1);
+            self.phases.reserve(var + 1);
+        }
+    }
+
+    pub fn add_clause(&mut self, clause: &Clause) {
+        self.backtrack(0);
+        for literal in clause.literals() {
+            self.reserve(literal.identifier() as usize);
+        }
+        let mut literals = vec![];
+        for literal in clause.literals() {
+            let lit = Lit::from(literal);
+            match self.value(lit) {
+                Some(true) => return,
+                Some(false) => (),
+                None if clause.contains(&literal.not()) => return,
+                None => literals.push(lit),
+            }
+        }
+        match literals.len() {
+            0 => self.inconsistent = true,
+            1 => {
+                self.enqueue(literals[0], None);
+                if self.propagate().is_some() {
+                    self.inconsistent = true;
+                }
+            }
+            _ => {
+                self.attach(literals, None);
+            }
+        }
+    }
+
+    fn attach(&mut self, literals: Vec<Lit>, block_distance: Option<usize>) -> usize {
+        let index = self.clauses.len();
+        self.watches[literals[0].index()].push(index);
+        self.watches[literals[1].index()].push(index);
+        self.clauses.push(literals);
+        self.block_distances.push(block_distance);
+        if block_distance.is_some() {
+            self.learnts += 1;
+        }
+        index
+    }
+
+    fn learnt_limit(&self) -> usize {
+        (self.config.learnt_limit as f64 * Self::LEARNT_GROWTH.powi(self.reductions)) as usize
+    }
+
+    fn reduce(&mut self) {
+        trace_span!("cdcl.reduce");
+        if self.propagate().is_some() {
+            self.inconsistent = true;
+            return;
+        }
+        let mut candidates = self
+            .block_distances
+            .iter()
+            .enumerate()
+            .filter_map(|(index, distance)| {
+                distance
+                    .filter(|distance| *distance > 2)
+                    .map(|distance| (distance, index))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|(distance, index)| {
+            std::cmp::Reverse((*distance, self.clauses[*index].len()))
+        });
+        let mut removed = vec![false; self.clauses.len()];
+        for (_, index) in candidates.iter().take(self.learnts / 2) {
+            removed[*index] = true;
+        }
+        let mut shortened = vec![None; self.clauses.len()];
+        if self.config.vivification {
+            let saved = self.phases.saved.clone();
+            for index in 0..self.clauses.len() {
+                if self.block_distances[index].is_some() && !removed[index] {
+                    shortened[index] = self.vivify(index);
+                }
+            }
+            self.phases.saved = saved;
+        }
+        let clauses = std::mem::take(&mut self.clauses);
+        let block_distances = std::mem::take(&mut self.block_distances);
+        for watching in &mut self.watches {
+            watching.clear();
+        }
+        self.learnts = 0;
+        let mut units = vec![];
+        for (((literals, block_distance), removed), shortened) in clauses
+            .into_iter()
+            .zip(block_distances)
+            .zip(removed)
+            .zip(shortened)
+        {
+            match shortened.unwrap_or(literals) {
+                _ if removed => (),
+                literals if literals.len() == 1 => units.push(literals[0]),
+                literals => {
+                    self.attach(literals, block_distance);
+                }
+            }
+        }
+        for reason in &mut self.reasons {
+            *reason = None;
+        }
+        for lit in units {
+            match self.value(lit) {
+                Some(true) => (),
+                Some(false) => self.inconsistent = true,
+                None => self.enqueue(lit, None),
+            }
+        }
+        if self.propagate().is_some() {
+            self.inconsistent = true;
+        }
+        self.reductions += 1;
+    }
+
+    fn vivify(&mut self, index: usize) -> Option<Vec<Lit>> {
+        let literals = self.clauses[index].clone();
+        let mut kept = vec![];
+        for &lit in &literals {
+            match self.value(lit) {
+                Some(true) => {
+                    kept.push(lit);
+                    break;
+                }
+                Some(false) => (),
+                None => {
+                    kept.push(lit);
+                    self.decide(lit.negate());
+                    if self.propagate().is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+        self.backtrack(0);
+        (kept.len() < literals.len()).then_some(kept)
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.values[lit.var()].map(|value| value != lit.is_negative())
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_limits.len()
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        self.enqueue_at(lit, reason, self.decision_level());
+    }
+
+    fn enqueue_at(&mut self, lit: Lit, reason: Option<usize>, level: usize) {
+        let var = lit.var();
+        self.values[var] = Some(!lit.is_negative());
+        self.levels[var] = level;
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        let start = self.trail_limits[level];
+        for lit in self.trail.split_off(start) {
+            if self.levels[lit.var()] <= level {
+                self.trail.push(lit);
+                continue;
+            }
+            self.phases.saved[lit.var()] = !lit.is_negative();
+            self.values[lit.var()] = None;
+            self.reasons[lit.var()] = None;
+            self.order.insert(lit.var());
+        }
+        self.trail_limits.truncate(level);
+        self.propagated = self.propagated.min(start);
+    }
+
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let false_lit = self.trail[self.propagated].negate();
+            self.propagated += 1;
+            let watching = std::mem::take(&mut self.watches[false_lit.index()]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut conflict = None;
+            for (position, &index) in watching.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend(&watching[position..]);
+                    break;
+                }
+                let clause = &mut self.clauses[index];
+                if clause[0] == false_lit {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.values[first.var()].map(|value| value != first.is_negative()) == Some(true)
+                {
+                    kept.push(index);
+                    continue;
+                }
+                let replacement = (2..clause.len()).find(|k| {
+                    let lit = clause[*k];
+                    self.values[lit.var()].map(|value| value != lit.is_negative()) != Some(false)
+                });
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    let watch = clause[1];
+                    self.watches[watch.index()].push(index);
+                    continue;
+                }
+                kept.push(index);
+                match self.value(first) {
+                    Some(false) => conflict = Some(index),
+                    _ => {
+                        let level = match self.config.chronological_backtracking {
+                            Some(_) => self.clause_level(&self.clauses[index][1..]),
+                            None => self.decision_level(),
+                        };
+                        trace_event!("cdcl.propagation", variable = first.var(), level = level);
+                        self.enqueue_at(first, Some(index), level)
+                    }
+                }
+            }
+            self.watches[false_lit.index()] = kept;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let mut seen = vec![false; self.variables()];
+        let mut learnt = vec![Lit(0)];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+        let mut implied = None;
+        loop {
+            let skip = usize::from(implied.is_some());
+            for position in skip..self.clauses[clause].len() {
+                let lit = self.clauses[clause][position];
+                let var = lit.var();
+                if !seen[var] && self.levels[var] > 0 {
+                    seen[var] = true;
+                    self.order.bump(var);
+                    match self.levels[var] == self.decision_level() {
+                        true => pending += 1,
+                        false => learnt.push(lit),
+                    }
+                }
+            }
+            loop {
+                index -= 1;
+                let var = self.trail[index].var();
+                if seen[var] && self.levels[var] == self.decision_level() {
+                    break;
+                }
+            }
+            let lit = self.trail[index];
+            seen[lit.var()] = false;
+            pending -= 1;
+            implied = Some(lit);
+            if pending == 0 {
+                break;
+            }
+            clause = self.reasons[lit.var()].unwrap();
+        }
+        learnt[0] = implied.unwrap().negate();
+        let mut position = 1;
+        while position < learnt.len() {
+            let lit = learnt[position];
+            match self.reasons[lit.var()].is_some() && self.redundant(lit, &mut seen) {
+                true => {
+                    learnt.swap_remove(position);
+                }
+                false => position += 1,
+            }
+        }
+        let mut level = 0;
+        if let Some((position, _)) = learnt
+            .iter()
+            .enumerate()
+            .skip(1)
+            .max_by_key(|(_, lit)| self.levels[lit.var()])
+        {
+            learnt.swap(1, position);
+            level = self.levels[learnt[1].var()];
+        }
+        (learnt, level)
+    }
+
+    fn redundant(&self, lit: Lit, seen: &mut [bool]) -> bool {
+        let mut stack = vec![lit];
+        let mut marked = vec![];
+        while let Some(lit) = stack.pop() {
+            let reason = self.reasons[lit.var()].unwrap();
+            for &other in &self.clauses[reason][1..] {
+                let var = other.var();
+                if seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                if self.reasons[var].is_none() {
+                    for var in marked {
+                        seen[var] = false;
+                    }
+                    return false;
+                }
+                seen[var] = true;
+                marked.push(var);
+                stack.push(other);
+            }
+        }
+        true
+    }
+
+    fn block_distance(&self, literals: &[Lit]) -> usize {
+        let mut levels = literals
+            .iter()
+            .map(|lit| self.levels[lit.var()])
+            .collect::<Vec<_>>();
+        levels.sort_unstable();
+        levels.dedup();
+        levels.len()
+    }
+
+    fn analyze_final(&self, assumption: Lit) -> Vec<Literal> {
+        let mut core = vec![Literal::from(assumption)];
+        if self.levels[assumption.var()] == 0 {
+            return core;
+        }
+        let mut seen = vec![false; self.variables()];
+        seen[assumption.var()] = true;
+        for &lit in self.trail[self.trail_limits[0]..].iter().rev() {
+            let var = lit.var();
+            if !seen[var] {
+                continue;
+            }
+            match self.reasons[var] {
+                None => core.push(Literal::from(lit)),
+                Some(reason) => {
+                    for other in &self.clauses[reason][1..] {
+                        if self.levels[other.var()] > 0 {
+                            seen[other.var()] = true;
+                        }
+                    }
+                }
+            }
+            seen[var] = false;
+        }
+        core
+    }
+
+    fn clause_level(&self, literals: &[Lit]) -> usize {
+        literals
+            .iter()
+            .map(|lit| self.levels[lit.var()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn learn(&mut self, learnt: Vec<Lit>, block_distance: usize, level: usize) {
+        match learnt.len() {
+            1 => self.enqueue_at(learnt[0], None, 0),
+            _ => {
+                let asserting = learnt[0];
+                let index = self.attach(learnt, Some(block_distance));
+                self.enqueue_at(asserting, Some(index), level);
+            }
+        }
+    }
+
+    fn decide(&mut self, lit: Lit) {
+        trace_event!(
+            "cdcl.decision",
+            variable = lit.var(),
+            level = self.decision_level() + 1
+        );
+        self.trail_limits.push(self.trail.len());
+        self.enqueue(lit, None);
+    }
+
+    fn phase(&self, var: usize) -> bool {
+        match self.phases.target[var] {
+            Some(phase) if self.config.target_phases => phase,
+            _ => self.config.phase_saving && self.phases.saved[var],
+        }
+    }
+
+    fn next_variable(&mut self) -> Option<usize> {
+        if self.config.random_decisions > 0.0
+            && self.variables() > 0
+            && self.rng.next_f64() < self.config.random_decisions
+        {
+            let var = self.rng.below(self.variables());
+            if self.values[var].is_none() {
+                return Some(var);
+            }
+        }
+        while let Some(var) = self.order.pop() {
+            if self.values[var].is_none() {
+                return Some(var);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn solve_complete(&mut self, assumptions: &[Literal]) -> bool {
+        let limit = self.config.conflict_limit.take();
+        let result = self.try_solve_with_assumptions(assumptions);
+        self.config.conflict_limit = limit;
+        let Ok(satisfiable) = result else {
+            unreachable!("a solver without a conflict limit always decides")
+        };
+        satisfiable
+    }
+
+    pub fn try_solve(&mut self) -> Result<bool, SolveError> {
+        self.try_solve_with_assumptions(&[])
+    }
+
+    pub fn try_solve_with_assumptions(
+        &mut self,
+        assumptions: &[Literal],
+    ) -> Result<bool, SolveError> {
+        trace_span!("cdcl.solve");
+        self.model = None;
+        self.core.clear();
+        let assumptions = assumptions.iter().map(Lit::from).collect::<Vec<_>>();
+        for lit in &assumptions {
+            self.reserve(lit.var());
+        }
+        if self.inconsistent {
+            return Ok(false);
+        }
+        self.backtrack(0);
+        let start = self.restarts.total;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                let conflict_level = self.clause_level(&self.clauses[conflict]);
+                if conflict_level == 0 {
+                    self.inconsistent = true;
+                    return Ok(false);
+                }
+                if let Some(limit) = self.config.conflict_limit {
+                    if self.restarts.total - start >= limit {
+                        let decision_level = self.decision_level();
+                        self.backtrack(0);
+                        return Err(SolveError::ConflictLimitExceeded {
+                            conflicts: limit,
+                            decision_level,
+                            learnts: self.learnts,
+                        });
+                    }
+                }
+                self.backtrack(conflict_level);
+                if self.config.target_phases {
+                    self.phases.update_target(&self.trail);
+                }
+                let (learnt, level) = self.analyze(conflict);
+                let block_distance = self.block_distance(&learnt);
+                trace_event!(
+                    "cdcl.conflict",
+                    level = conflict_level,
+                    backjump = level,
+                    length = learnt.len(),
+                    block_distance = block_distance
+                );
+                match self.config.chronological_backtracking {
+                    Some(threshold) if self.decision_level() - level > threshold => {
+                        self.backtrack(self.decision_level() - 1)
+                    }
+                    _ => self.backtrack(level),
+                }
+                self.learn(learnt, block_distance, level);
+                self.order.decay(self.config.decay);
+                let restart = self.restarts.conflict(self.config.restarts, block_distance);
+                if restart || self.learnts > self.learnt_limit() {
+                    trace_event!("cdcl.restart", conflicts = self.restarts.total);
+                    self.backtrack(0);
+                }
+                if self.learnts > self.learnt_limit() {
+                    self.reduce();
+                    if self.inconsistent {
+                        return Ok(false);
+                    }
+                }
+                if let Some(interval) = self.config.rephase_interval {
+                    if self.restarts.total.is_multiple_of(interval) {
+                        self.phases.rephase();
+                    }
+                }
+                continue;
+            }
+            if let Some(&assumption) = assumptions.get(self.decision_level()) {
+                match self.value(assumption) {
+                    Some(true) => self.trail_limits.push(self.trail.len()),
+                    Some(false) => {
+                        self.core = self.analyze_final(assumption);
+                        self.backtrack(0);
+                        return Ok(false);
+                    }
+                    None => self.decide(assumption),
+                }
+                continue;
+            }
+            match self.next_variable() {
+                Some(var) => {
+                    let phase = self.phase(var);
+                    self.decide(Lit::new(var, !phase))
+                }
+                None => {
+                    self.model = Some(
+                        self.values
+                            .iter()
+                            .enumerate()
+                            .map(|(var, value)| (var as Ident, value.unwrap()))
+                            .collect(),
+                    );
+                    self.backtrack(0);
+                    debug_assert!(self
+                        .model
+                        .as_ref()
+                        .is_some_and(|model| self.verify_model(model)));
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn assume(&mut self, assumptions: &[Literal]) -> bool {
+        self.backtrack(0);
+        if self.inconsistent || self.propagate().is_some() {
+            return false;
+        }
+        for lit in assumptions.iter().map(Lit::from) {
+            self.reserve(lit.var());
+            match self.value(lit) {
+                Some(true) => (),
+                Some(false) => return false,
+                None => {
+                    self.decide(lit);
+                    if self.propagate().is_some() {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    pub(crate) fn probe(&mut self, literal: &Literal) -> Option<usize> {
+        let lit = Lit::from(literal);
+        match self.value(lit) {
+            Some(true) => return Some(0),
+            Some(false) => return None,
+            None => (),
+        }
+        let level = self.decision_level();
+        let size = self.trail.len();
+        self.decide(lit);
+        let implied = match self.propagate() {
+            Some(_) => None,
+            None => Some(self.trail.len() - size),
+        };
+        self.backtrack(level);
+        implied
+    }
+
+    pub(crate) fn is_assigned(&self, ident: Ident) -> bool {
+        self.values
+            .get(ident as usize)
+            .is_some_and(|value| value.is_some())
+    }
+
+    pub fn verify_model(&self, model: &Model) -> bool {
+        let satisfied = |lit: &Lit| model.get(lit.var() as Ident) == Some(!lit.is_negative());
+        !self.inconsistent
+            && self
+                .trail
+                .iter()
+                .filter(|lit| self.levels[lit.var()] == 0)
+                .all(satisfied)
+            && self
+                .clauses
+                .iter()
+                .all(|clause| clause.iter().any(satisfied))
+    }
+
+    pub fn model(&self) -> Option<&Model> {
+        self.model.as_ref()
+    }
+
+    pub fn unsat_core(&self) -> &[Literal] {
+        &self.core
+    }
+}
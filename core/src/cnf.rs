@@ -0,0 +1,268 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    cdcl::CDCLSolver,
+    error::Error,
+    gates::{self, detect_gates, Gate},
+    sat::{try_generate_clauses_from_tree, Clause, Literal, Polarity},
+    tseitin::{CnfConversion, Equivalence},
+};
+
+pub type GroupId = usize;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CnfFormula {
+    clauses: Vec<(Option<GroupId>, Clause)>,
+    groups: BTreeSet<GroupId>,
+}
+
+impl fmt::Display for CnfFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{{}}}",
+            self.clauses
+                .iter()
+                .map(|(group, clause)| match group {
+                    Some(group) => format!("g{}: {}", group, clause),
+                    None => clause.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl From<Vec<Clause>> for CnfFormula {
+    fn from(value: Vec<Clause>) -> Self {
+        Self {
+            clauses: value.into_iter().map(|clause| (None, clause)).collect(),
+            groups: BTreeSet::new(),
+        }
+    }
+}
+
+impl TryFrom<&AST> for CnfFormula {
+    type Error = Error;
+
+    fn try_from(value: &AST) -> Result<Self, Self::Error> {
+        Ok(Self::from(try_generate_clauses_from_tree(
+            value.cnf().formula,
+        )?))
+    }
+}
+
+impl FromIterator<Clause> for CnfFormula {
+    fn from_iter<I: IntoIterator<Item = Clause>>(iter: I) -> Self {
+        let mut formula = Self::new();
+        formula.extend(iter);
+        formula
+    }
+}
+
+impl Extend<Clause> for CnfFormula {
+    fn extend<I: IntoIterator<Item = Clause>>(&mut self, iter: I) {
+        self.clauses
+            .extend(iter.into_iter().map(|clause| (None, clause)));
+    }
+}
+
+impl<'a> IntoIterator for &'a CnfFormula {
+    type Item = &'a Clause;
+    type IntoIter = Box<dyn Iterator<Item = &'a Clause> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.clauses())
+    }
+}
+
+impl CnfFormula {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_clause(&mut self, clause: Clause) {
+        self.clauses.push((None, clause));
+    }
+
+    pub fn add_group(&mut self, group: GroupId) {
+        self.groups.insert(group);
+    }
+
+    pub fn add_group_clause(&mut self, group: GroupId, clause: Clause) {
+        self.groups.insert(group);
+        self.clauses.push((Some(group), clause));
+    }
+
+    pub fn clauses(&self) -> impl Iterator<Item = &Clause> {
+        self.clauses.iter().map(|(_, clause)| clause)
+    }
+
+    pub fn group(&self, group: GroupId) -> impl Iterator<Item = &Clause> {
+        self.clauses
+            .iter()
+            .filter(move |(tag, _)| *tag == Some(group))
+            .map(|(_, clause)| clause)
+    }
+
+    pub fn groups(&self) -> BTreeSet<GroupId> {
+        self.groups.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.clauses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    pub fn variables(&self) -> BTreeSet<Ident> {
+        self.clauses()
+            .flat_map(|clause| clause.literals())
+            .map(|literal| literal.identifier())
+            .collect()
+    }
+
+    pub fn to_ast<T: AbstractSyntaxTree>(&self) -> Option<T> {
+        self.clauses()
+            .map(|clause| clause.to_ast::<T>())
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .reduce(|lhs, rhs| lhs.and(rhs))
+    }
+
+    pub fn gates(&self) -> Vec<Gate> {
+        detect_gates(&self.clauses().cloned().collect::<Vec<_>>())
+    }
+
+    pub fn to_ast_with_gates<T: AbstractSyntaxTree>(&self) -> CnfConversion<T> {
+        let clauses = self.clauses().cloned().collect::<Vec<_>>();
+        let gates = detect_gates(&clauses);
+        let formula = gates::substitute::<T>(&gates, &clauses)
+            .into_iter()
+            .reduce(|lhs, rhs| lhs.and(rhs))
+            .unwrap_or_else(|| {
+                let var = T::variable(0);
+                var.or(var.not())
+            });
+        let auxiliary = gates
+            .iter()
+            .map(|gate| gate.output().identifier())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        CnfConversion {
+            formula,
+            equivalence: match auxiliary.is_empty() {
+                true => Equivalence::Equivalent,
+                false => Equivalence::Equisatisfiable,
+            },
+            auxiliary,
+        }
+    }
+
+    pub fn verify_model(&self, model: &Model) -> bool {
+        self.clauses().all(|clause| clause.is_satisfied(model))
+    }
+
+    pub fn solver(&self) -> GroupSolver {
+        GroupSolver::new(self)
+    }
+}
+
+pub struct GroupSolver {
+    solver: CDCLSolver,
+    selectors: BTreeMap<GroupId, Literal>,
+    disabled: BTreeSet<GroupId>,
+    variables: usize,
+}
+
+impl GroupSolver {
+    fn new(formula: &CnfFormula) -> Self {
+        let variables = formula
+            .clauses()
+            .flat_map(|clause| clause.literals())
+            .map(|literal| literal.identifier() as usize + 1)
+            .max()
+            .unwrap_or(0);
+        let selectors = formula
+            .groups()
+            .into_iter()
+            .enumerate()
+            .map(|(index, group)| {
+                (
+                    group,
+                    Literal::new((variables + index) as Ident, Polarity::Positive),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        let mut solver = CDCLSolver::default();
+        for (group, clause) in &formula.clauses {
+            match group {
+                Some(group) => solver.add_clause(&Clause::new(
+                    clause.literals().cloned().chain([selectors[group].not()]),
+                )),
+                None => solver.add_clause(clause),
+            }
+        }
+        Self {
+            solver,
+            selectors,
+            disabled: BTreeSet::new(),
+            variables,
+        }
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = &GroupId> {
+        self.selectors.keys()
+    }
+
+    pub fn enable(&mut self, group: GroupId) {
+        self.disabled.remove(&group);
+    }
+
+    pub fn disable(&mut self, group: GroupId) {
+        self.disabled.insert(group);
+    }
+
+    pub fn solve(&mut self) -> bool {
+        let enabled = self
+            .selectors
+            .keys()
+            .filter(|group| !self.disabled.contains(group))
+            .copied()
+            .collect::<Vec<_>>();
+        self.solve_groups(&enabled)
+    }
+
+    pub fn solve_groups(&mut self, groups: &[GroupId]) -> bool {
+        let assumptions = groups
+            .iter()
+            .filter_map(|group| self.selectors.get(group).cloned())
+            .collect::<Vec<_>>();
+        self.solver.solve_complete(&assumptions)
+    }
+
+    pub fn model(&self) -> Option<Model> {
+        self.solver.model().map(|model| {
+            model
+                .iter()
+                .filter(|(ident, _)| (*ident as usize) < self.variables)
+                .collect()
+        })
+    }
+
+    pub fn core(&self) -> Vec<GroupId> {
+        let core = self.solver.unsat_core();
+        self.selectors
+            .iter()
+            .filter(|(_, selector)| core.contains(selector))
+            .map(|(group, _)| *group)
+            .collect()
+    }
+}
@@ -0,0 +1,324 @@
+use std::{
+    fmt::{self, Write},
+    iter::Peekable,
+    str::Chars,
+};
+
+use crate::ast::ParseError;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Syntax { line: usize, message: String },
+    MissingField(String),
+    InvalidType(String),
+    InvalidPattern(String, ParseError),
+    UnboundVariable(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ConfigError::MissingField(key) => write!(f, "missing field '{}'", key),
+            ConfigError::InvalidType(key) => write!(f, "field '{}' has the wrong type", key),
+            ConfigError::InvalidPattern(pattern, _) => write!(f, "invalid pattern '{}'", pattern),
+            ConfigError::UnboundVariable(name) => {
+                write!(
+                    f,
+                    "variable '{}' is not bound by the rule's left side",
+                    name
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::InvalidPattern(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Array(Vec<Value>),
+    Table(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Table(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn field(&self, key: &str) -> Result<&Value, ConfigError> {
+        self.get(key)
+            .ok_or_else(|| ConfigError::MissingField(key.to_owned()))
+    }
+
+    pub fn as_str(&self, key: &str) -> Result<&str, ConfigError> {
+        match self.field(key)? {
+            Value::String(s) => Ok(s),
+            _ => Err(ConfigError::InvalidType(key.to_owned())),
+        }
+    }
+
+    pub fn as_array(&self, key: &str) -> Result<&[Value], ConfigError> {
+        match self.field(key)? {
+            Value::Array(items) => Ok(items),
+            _ => Err(ConfigError::InvalidType(key.to_owned())),
+        }
+    }
+}
+
+struct JsonReader<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl JsonReader<'_> {
+    fn error<T>(&self, message: &str) -> Result<T, ConfigError> {
+        Err(ConfigError::Syntax {
+            line: self.line,
+            message: message.to_owned(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ConfigError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => self.error(&format!("expected '{}'", expected)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ConfigError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some('[') => {
+                self.chars.next();
+                let mut items = vec![];
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&']').is_some() {
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.chars.next() {
+                        Some(',') => continue,
+                        Some(']') => return Ok(Value::Array(items)),
+                        _ => return self.error("expected ',' or ']'"),
+                    }
+                }
+            }
+            Some('{') => {
+                self.chars.next();
+                let mut entries = vec![];
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&'}').is_some() {
+                    return Ok(Value::Table(entries));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    entries.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.chars.next() {
+                        Some(',') => continue,
+                        Some('}') => return Ok(Value::Table(entries)),
+                        _ => return self.error("expected ',' or '}'"),
+                    }
+                }
+            }
+            Some(_) => self.error("unsupported value"),
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ConfigError> {
+        if self.chars.next() != Some('"') {
+            return self.error("expected string");
+        }
+        let mut s = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some(c @ ('"' | '\\' | '/')) => s.push(c),
+                    _ => return self.error("invalid escape sequence"),
+                },
+                Some('\n') | None => return self.error("unterminated string"),
+                Some(c) => s.push(c),
+            }
+        }
+    }
+}
+
+pub fn parse_json(input: &str) -> Result<Value, ConfigError> {
+    let mut reader = JsonReader {
+        chars: input.chars().peekable(),
+        line: 1,
+    };
+    let value = reader.value()?;
+    reader.skip_whitespace();
+    match reader.chars.next() {
+        Some(_) => reader.error("trailing characters"),
+        None => Ok(value),
+    }
+}
+
+pub fn parse_toml(input: &str) -> Result<Value, ConfigError> {
+    let mut root = vec![];
+    let mut current: Option<(String, Vec<(String, Value)>)> = None;
+    for (index, raw) in input.lines().enumerate() {
+        let error = |message: &str| ConfigError::Syntax {
+            line: index + 1,
+            message: message.to_owned(),
+        };
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(key) = line
+            .strip_prefix("[[")
+            .and_then(|rest| rest.strip_suffix("]]"))
+        {
+            if let Some((key, table)) = current.take() {
+                push_array_table(&mut root, key, table);
+            }
+            current = Some((key.trim().to_owned(), vec![]));
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| error("expected '='"))?;
+        let mut reader = JsonReader {
+            chars: value.trim().chars().peekable(),
+            line: index + 1,
+        };
+        let value = reader.string()?;
+        if reader.chars.next().is_some() {
+            return Err(error("trailing characters"));
+        }
+        let entry = (key.trim().to_owned(), Value::String(value));
+        match &mut current {
+            Some((_, table)) => table.push(entry),
+            None => root.push(entry),
+        }
+    }
+    if let Some((key, table)) = current {
+        push_array_table(&mut root, key, table);
+    }
+    Ok(Value::Table(root))
+}
+
+fn push_array_table(root: &mut Vec<(String, Value)>, key: String, table: Vec<(String, Value)>) {
+    match root.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, Value::Array(items))) => items.push(Value::Table(table)),
+        _ => root.push((key, Value::Array(vec![Value::Table(table)]))),
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub fn write_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_json_value(value, 0, &mut out);
+    out.push('\n');
+    out
+}
+
+fn write_json_value(value: &Value, indent: usize, out: &mut String) {
+    let pad = "  ".repeat(indent + 1);
+    match value {
+        Value::String(s) => out.push_str(&quote(s)),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                out.push_str(&pad);
+                write_json_value(item, indent + 1, out);
+            }
+            if !items.is_empty() {
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent));
+            }
+            out.push(']');
+        }
+        Value::Table(entries) => {
+            out.push('{');
+            for (i, (key, item)) in entries.iter().enumerate() {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                let _ = write!(out, "{}{}: ", pad, quote(key));
+                write_json_value(item, indent + 1, out);
+            }
+            if !entries.is_empty() {
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent));
+            }
+            out.push('}');
+        }
+    }
+}
+
+pub fn write_toml(value: &Value) -> String {
+    let mut out = String::new();
+    let entries = match value {
+        Value::Table(entries) => entries,
+        _ => return out,
+    };
+    for (key, item) in entries {
+        if let Value::String(s) = item {
+            let _ = writeln!(out, "{} = {}", key, quote(s));
+        }
+    }
+    for (key, item) in entries {
+        if let Value::Array(tables) = item {
+            for table in tables {
+                let _ = writeln!(out, "\n[[{}]]", key);
+                if let Value::Table(fields) = table {
+                    for (field, v) in fields {
+                        if let Value::String(s) = v {
+                            let _ = writeln!(out, "{} = {}", field, quote(s));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,224 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    ast::{ASTNode, AbstractSyntaxTree, Ident, AST},
+    rewrite::{RewriteRule, RewriteRuleset},
+    transition::{rename, substitute},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CriticalPair {
+    pub outer: String,
+    pub inner: String,
+    pub overlap: AST,
+    pub left: AST,
+    pub right: AST,
+    pub joinable: bool,
+}
+
+impl fmt::Display for CriticalPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ⇝ ({}, {}) from '{}' and '{}'{}",
+            self.overlap,
+            self.left,
+            self.right,
+            self.outer,
+            self.inner,
+            match self.joinable {
+                true => "",
+                false => " (not joinable)",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfluenceReport {
+    pub ruleset: String,
+    pub pairs: Vec<CriticalPair>,
+}
+
+impl fmt::Display for ConfluenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} critical pairs, {} not joinable",
+            self.ruleset,
+            self.pairs.len(),
+            self.non_joinable().count()
+        )?;
+        for pair in self.non_joinable() {
+            write!(f, "\n  {}", pair)?;
+        }
+        Ok(())
+    }
+}
+
+impl ConfluenceReport {
+    pub fn is_locally_confluent(&self) -> bool {
+        self.pairs.iter().all(|pair| pair.joinable)
+    }
+
+    pub fn non_joinable(&self) -> impl Iterator<Item = &CriticalPair> {
+        self.pairs.iter().filter(|pair| !pair.joinable)
+    }
+}
+
+pub fn critical_pairs(ruleset: &RewriteRuleset) -> ConfluenceReport {
+    let mut pairs = vec![];
+    for (i, outer) in ruleset.rules.iter().enumerate() {
+        for (j, inner) in ruleset.rules.iter().enumerate() {
+            let inner = rename_apart(inner, outer);
+            for (position, subterm) in positions(&outer.top) {
+                if position.is_empty() && i == j {
+                    continue;
+                }
+                let mut substitution = HashMap::new();
+                if !unify(&subterm, &inner.top, &mut substitution) {
+                    continue;
+                }
+                let instance = |formula: &AST| resolve(formula, &substitution);
+                let replaced = replace(&outer.top, &position, &inner.bot);
+                let (left, right) = (instance(&outer.bot), instance(&replaced));
+                let joinable = joinable(ruleset, &left, &right);
+                pairs.push(CriticalPair {
+                    outer: outer.name.clone(),
+                    inner: inner.name.clone(),
+                    overlap: instance(&outer.top),
+                    left,
+                    right,
+                    joinable,
+                });
+            }
+        }
+    }
+    ConfluenceReport {
+        ruleset: ruleset.name.clone(),
+        pairs,
+    }
+}
+
+fn rename_apart(rule: &RewriteRule, other: &RewriteRule) -> RewriteRule {
+    let offset = other
+        .top
+        .variables()
+        .into_iter()
+        .chain(other.bot.variables())
+        .max()
+        .map_or(0, |ident| ident + 1);
+    let renaming = rule
+        .top
+        .variables()
+        .into_iter()
+        .map(|ident| (ident, ident + offset))
+        .collect::<HashMap<_, _>>();
+    RewriteRule {
+        name: rule.name.clone(),
+        top: rename(&rule.top, &renaming),
+        bot: rename(&rule.bot, &renaming),
+    }
+}
+
+fn joinable(ruleset: &RewriteRuleset, left: &AST, right: &AST) -> bool {
+    match (
+        ruleset.rewrite_recursive_hull(left.clone()),
+        ruleset.rewrite_recursive_hull(right.clone()),
+    ) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => false,
+    }
+}
+
+fn children(formula: &AST) -> Vec<AST> {
+    match &**formula {
+        ASTNode::Variable(_) => vec![],
+        ASTNode::Not(p) => vec![p.clone()],
+        ASTNode::And(p1, p2)
+        | ASTNode::Or(p1, p2)
+        | ASTNode::Nand(p1, p2)
+        | ASTNode::Nor(p1, p2) => vec![p1.clone(), p2.clone()],
+        ASTNode::Ite(p1, p2, p3) => vec![p1.clone(), p2.clone(), p3.clone()],
+    }
+}
+
+fn positions(formula: &AST) -> Vec<(Vec<usize>, AST)> {
+    if let ASTNode::Variable(_) = &**formula {
+        return vec![];
+    }
+    let mut positions = vec![(vec![], formula.clone())];
+    for (index, child) in children(formula).iter().enumerate() {
+        for (mut position, subterm) in self::positions(child) {
+            position.insert(0, index);
+            positions.push((position, subterm));
+        }
+    }
+    positions
+}
+
+fn replace(formula: &AST, position: &[usize], replacement: &AST) -> AST {
+    let Some((index, rest)) = position.split_first() else {
+        return replacement.clone();
+    };
+    let mut operands = children(formula);
+    operands[*index] = replace(&operands[*index], rest, replacement);
+    match (&**formula, &operands[..]) {
+        (ASTNode::Not(_), [p]) => p.not(),
+        (ASTNode::And(..), [p1, p2]) => p1.and(p2.clone()),
+        (ASTNode::Or(..), [p1, p2]) => p1.or(p2.clone()),
+        (ASTNode::Nand(..), [p1, p2]) => p1.nand(p2.clone()),
+        (ASTNode::Nor(..), [p1, p2]) => p1.nor(p2.clone()),
+        (ASTNode::Ite(..), [p1, p2, p3]) => p1.ite(p2.clone(), p3.clone()),
+        _ => unreachable!(),
+    }
+}
+
+fn resolve(formula: &AST, substitution: &HashMap<Ident, AST>) -> AST {
+    let mut current = formula.clone();
+    loop {
+        let next = substitute(&current, substitution);
+        if next == current {
+            return current;
+        }
+        current = next;
+    }
+}
+
+fn unify(lhs: &AST, rhs: &AST, substitution: &mut HashMap<Ident, AST>) -> bool {
+    let (lhs, rhs) = (walk(lhs, substitution), walk(rhs, substitution));
+    match (&*lhs, &*rhs) {
+        (ASTNode::Variable(left), ASTNode::Variable(right)) if left == right => true,
+        (ASTNode::Variable(ident), _) => bind(*ident, &rhs, substitution),
+        (_, ASTNode::Variable(ident)) => bind(*ident, &lhs, substitution),
+        (ASTNode::Not(_), ASTNode::Not(_))
+        | (ASTNode::And(..), ASTNode::And(..))
+        | (ASTNode::Or(..), ASTNode::Or(..))
+        | (ASTNode::Nand(..), ASTNode::Nand(..))
+        | (ASTNode::Nor(..), ASTNode::Nor(..))
+        | (ASTNode::Ite(..), ASTNode::Ite(..)) => children(&lhs)
+            .iter()
+            .zip(children(&rhs).iter())
+            .all(|(lhs, rhs)| unify(lhs, rhs, substitution)),
+        _ => false,
+    }
+}
+
+fn walk(formula: &AST, substitution: &HashMap<Ident, AST>) -> AST {
+    let mut current = formula.clone();
+    while let ASTNode::Variable(ident) = &*current {
+        match substitution.get(ident) {
+            Some(bound) => current = bound.clone(),
+            None => break,
+        }
+    }
+    current
+}
+
+fn bind(ident: Ident, formula: &AST, substitution: &mut HashMap<Ident, AST>) -> bool {
+    if resolve(formula, substitution).variables().contains(&ident) {
+        return false;
+    }
+    substitution.insert(ident, formula.clone());
+    true
+}
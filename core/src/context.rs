@@ -0,0 +1,161 @@
+use crate::{
+    ast::{AbstractSyntaxTree, Model, AST},
+    cdcl::{CDCLSolver, SolveError, SolverConfig},
+    sat::{Clause, Literal},
+    tseitin::Encoder,
+};
+
+#[derive(Debug, Clone)]
+struct Scope {
+    activation: Literal,
+    assertions: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Context {
+    solver: CDCLSolver,
+    encoder: Encoder,
+    assertions: Vec<AST>,
+    scopes: Vec<Scope>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new(SolverConfig::default())
+    }
+}
+
+impl Context {
+    pub fn new(config: SolverConfig) -> Self {
+        Self {
+            solver: CDCLSolver::new(config),
+            encoder: Encoder::renaming(),
+            assertions: vec![],
+            scopes: vec![],
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    pub fn assertions(&self) -> impl Iterator<Item = &AST> {
+        self.assertions.iter()
+    }
+
+    pub fn push(&mut self) {
+        let activation = self.fresh();
+        self.scopes.push(Scope {
+            activation,
+            assertions: self.assertions.len(),
+        });
+    }
+
+    pub fn pop(&mut self) -> bool {
+        let Some(scope) = self.scopes.pop() else {
+            return false;
+        };
+        self.solver
+            .add_clause(&Clause::new([scope.activation.not()]));
+        self.assertions.truncate(scope.assertions);
+        true
+    }
+
+    pub fn assert<T: AbstractSyntaxTree>(&mut self, formula: &T) {
+        let formula = formula.to_ast();
+        let root = self.encode(&formula);
+        let clause = match self.scopes.last() {
+            Some(scope) => Clause::new([root, scope.activation.not()]),
+            None => Clause::new([root]),
+        };
+        self.solver.add_clause(&clause);
+        self.assertions.push(formula);
+    }
+
+    pub fn check(&mut self) -> Result<bool, SolveError> {
+        self.check_assuming::<AST>(&[])
+    }
+
+    pub fn check_assuming<T: AbstractSyntaxTree>(
+        &mut self,
+        assumptions: &[T],
+    ) -> Result<bool, SolveError> {
+        let literals = self.assumptions(assumptions);
+        self.solver.try_solve_with_assumptions(&literals)
+    }
+
+    pub fn model(&self) -> Option<Model> {
+        let model = self.solver.model()?;
+        Some(
+            self.encoder
+                .renamed()
+                .map(|(ident, renamed)| (ident, model.get(renamed).unwrap_or(false)))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn guard<T: AbstractSyntaxTree>(&mut self, formula: &T) -> Literal {
+        let root = self.literal(formula);
+        let activation = self.fresh();
+        self.solver
+            .add_clause(&Clause::new([root, activation.not()]));
+        activation
+    }
+
+    pub(crate) fn retire(&mut self, activation: &Literal) {
+        self.solver.add_clause(&Clause::new([activation.not()]));
+    }
+
+    pub(crate) fn literal<T: AbstractSyntaxTree>(&mut self, formula: &T) -> Literal {
+        self.encode(&formula.to_ast())
+    }
+
+    pub(crate) fn check_complete<T: AbstractSyntaxTree>(&mut self, assumptions: &[T]) -> bool {
+        let literals = self.assumptions(assumptions);
+        self.solver.solve_complete(&literals)
+    }
+
+    pub(crate) fn check_literals(&mut self, assumptions: &[Literal]) -> bool {
+        self.solver.solve_complete(assumptions)
+    }
+
+    pub(crate) fn unsat_core(&self) -> &[Literal] {
+        self.solver.unsat_core()
+    }
+
+    pub(crate) fn add_clause(&mut self, literals: impl IntoIterator<Item = Literal>) {
+        self.solver.add_clause(&Clause::new(literals));
+    }
+
+    pub(crate) fn holds(&self, literal: &Literal) -> bool {
+        self.solver
+            .model()
+            .and_then(|model| model.get(literal.identifier()))
+            .is_some_and(|value| value == literal.is_positive())
+    }
+
+    fn assumptions<T: AbstractSyntaxTree>(&mut self, assumptions: &[T]) -> Vec<Literal> {
+        let mut literals = self
+            .scopes
+            .iter()
+            .map(|scope| scope.activation.clone())
+            .collect::<Vec<_>>();
+        for assumption in assumptions {
+            let literal = self.encode(&assumption.to_ast());
+            literals.push(literal);
+        }
+        literals
+    }
+
+    fn fresh(&mut self) -> Literal {
+        self.encoder.fresh_literal()
+    }
+
+    fn encode(&mut self, formula: &AST) -> Literal {
+        let root = self.encoder.root(formula);
+        for clause in self.encoder.take_clauses() {
+            self.solver.add_clause(&clause);
+        }
+        root
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    cdcl::CDCLSolver,
+    random::Rng,
+    sat::{Clause, DPLLSolver, Literal, Polarity, XorConstraint},
+    tseitin,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApproxMC {
+    pub epsilon: f64,
+    pub delta: f64,
+}
+
+impl Default for ApproxMC {
+    fn default() -> Self {
+        Self::new(0.8, 0.2)
+    }
+}
+
+impl ApproxMC {
+    pub fn new(epsilon: f64, delta: f64) -> Self {
+        Self { epsilon, delta }
+    }
+
+    pub(crate) fn threshold(&self) -> usize {
+        let epsilon = self.epsilon;
+        (1.0 + 9.84 * (1.0 + epsilon / (1.0 + epsilon)) * (1.0 + 1.0 / epsilon).powi(2)).ceil()
+            as usize
+    }
+
+    fn iterations(&self) -> usize {
+        (17.0 * (3.0 / self.delta).log2()).ceil() as usize
+    }
+
+    pub fn count(&self, formula: &AST, rng: &mut Rng) -> u128 {
+        self.count_clauses(&tseitin::cnf_clauses(formula), &formula.variables(), rng)
+    }
+
+    pub fn count_clauses(
+        &self,
+        clauses: &[Clause],
+        variables: &BTreeSet<Ident>,
+        rng: &mut Rng,
+    ) -> u128 {
+        let threshold = self.threshold();
+        let exact = bounded_count(clauses.to_vec(), &[], variables, threshold);
+        if exact < threshold as u128 {
+            return exact;
+        }
+        let mut estimates = (0..self.iterations())
+            .filter_map(|_| self.estimate(clauses, variables, threshold, rng))
+            .collect::<Vec<_>>();
+        estimates.sort();
+        estimates.get(estimates.len() / 2).copied().unwrap_or(0)
+    }
+
+    fn estimate(
+        &self,
+        clauses: &[Clause],
+        variables: &BTreeSet<Ident>,
+        threshold: usize,
+        rng: &mut Rng,
+    ) -> Option<u128> {
+        for hashes in 1..=variables.len() {
+            let xors = (0..hashes)
+                .map(|_| {
+                    let parity = rng.next_bool();
+                    XorConstraint::new(
+                        variables.iter().copied().filter(|_| rng.next_bool()),
+                        parity,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let size = bounded_count(clauses.to_vec(), &xors, variables, threshold);
+            if size < threshold as u128 {
+                return match size {
+                    0 => None,
+                    _ => Some(
+                        1u128
+                            .checked_shl(hashes as u32)
+                            .and_then(|scale| size.checked_mul(scale))
+                            .unwrap_or(u128::MAX),
+                    ),
+                };
+            }
+        }
+        None
+    }
+}
+
+pub fn count_projected_models(clauses: &[Clause], variables: &BTreeSet<Ident>) -> u128 {
+    bounded_count(clauses.to_vec(), &[], variables, usize::MAX)
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectedModels {
+    solver: CDCLSolver,
+    variables: BTreeSet<Ident>,
+}
+
+impl ProjectedModels {
+    pub fn new(clauses: &[Clause], variables: BTreeSet<Ident>) -> Self {
+        Self {
+            solver: CDCLSolver::from(clauses.to_vec()),
+            variables,
+        }
+    }
+
+    pub fn from_formula(formula: &AST, variables: BTreeSet<Ident>) -> Self {
+        Self::new(&tseitin::cnf_clauses(formula), variables)
+    }
+}
+
+impl Iterator for ProjectedModels {
+    type Item = Model;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.solver.solve_complete(&[]) {
+            return None;
+        }
+        let model = self.solver.model()?;
+        let projected = self
+            .variables
+            .iter()
+            .map(|ident| (*ident, model.get(*ident).unwrap_or(false)))
+            .collect::<Model>();
+        self.solver
+            .add_clause(&Clause::new(projected.iter().map(|(ident, value)| {
+                Literal::new(
+                    ident,
+                    match value {
+                        true => Polarity::Negative,
+                        false => Polarity::Positive,
+                    },
+                )
+            })));
+        Some(projected)
+    }
+}
+
+pub(crate) fn bounded_count(
+    clauses: Vec<Clause>,
+    xors: &[XorConstraint],
+    variables: &BTreeSet<Ident>,
+    limit: usize,
+) -> u128 {
+    enumerate_cubes(clauses, xors, variables, limit)
+        .iter()
+        .map(|cube| cube_size(cube, variables))
+        .fold(0, u128::saturating_add)
+}
+
+pub(crate) fn cube_size(cube: &Model, variables: &BTreeSet<Ident>) -> u128 {
+    1u128
+        .checked_shl((variables.len() - cube.len()) as u32)
+        .unwrap_or(u128::MAX)
+}
+
+pub(crate) fn enumerate_cubes(
+    mut clauses: Vec<Clause>,
+    xors: &[XorConstraint],
+    variables: &BTreeSet<Ident>,
+    limit: usize,
+) -> Vec<Model> {
+    let mut cubes = vec![];
+    let mut count = 0u128;
+    while count < limit as u128 {
+        let mut solver = DPLLSolver::from(clauses.clone());
+        for xor in xors {
+            solver.add_xor(xor.clone());
+        }
+        let Some(model) = solver.model() else {
+            break;
+        };
+        let cube = variables
+            .iter()
+            .filter_map(|ident| model.get(*ident).map(|value| (*ident, value)))
+            .collect::<Model>();
+        count = count.saturating_add(cube_size(&cube, variables));
+        if cube.is_empty() {
+            cubes.push(cube);
+            break;
+        }
+        clauses.push(Clause::new(cube.iter().map(|(ident, value)| {
+            Literal::new(
+                ident,
+                match value {
+                    true => Polarity::Negative,
+                    false => Polarity::Positive,
+                },
+            )
+        })));
+        cubes.push(cube);
+    }
+    cubes
+}
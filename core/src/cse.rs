@@ -0,0 +1,171 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    rc::Rc,
+};
+
+use crate::ast::{ASTNode, AbstractSyntaxTree, Ident, AST};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definitions {
+    pub definitions: Vec<(Ident, AST)>,
+    pub body: AST,
+}
+
+impl fmt::Display for Definitions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ident, definition) in &self.definitions {
+            writeln!(f, "var{} := {}", ident, definition)?;
+        }
+        write!(f, "{}", self.body)
+    }
+}
+
+impl Definitions {
+    pub fn expand(&self) -> AST {
+        let mut expansions = HashMap::new();
+        for (ident, definition) in &self.definitions {
+            let expansion = expand(definition, &expansions, &mut HashMap::new());
+            expansions.insert(*ident, expansion);
+        }
+        expand(&self.body, &expansions, &mut HashMap::new())
+    }
+}
+
+pub fn extract_common_subexpressions(formula: &AST) -> Definitions {
+    let mut classes = Classes::default();
+    let root = classes.classify(formula);
+    let mut references = vec![0; classes.nodes.len()];
+    references[root] += 1;
+    for (key, _) in &classes.nodes {
+        for child in key.children() {
+            references[child] += 1;
+        }
+    }
+    let fresh = formula
+        .variables()
+        .iter()
+        .max()
+        .map_or(0, |ident| ident + 1);
+    let mut names = BTreeMap::new();
+    for (class, (key, _)) in classes.nodes.iter().enumerate() {
+        if class != root && references[class] > 1 && !key.is_literal(&classes) {
+            names.insert(class, fresh + names.len() as Ident);
+        }
+    }
+    let mut rebuilt: Vec<AST> = vec![];
+    for (_, node) in &classes.nodes {
+        let child = |p: &AST| {
+            let class = classes.memo[&Rc::as_ptr(p)];
+            match names.get(&class) {
+                Some(ident) => <AST as AbstractSyntaxTree>::variable(*ident),
+                None => rebuilt[class].clone(),
+            }
+        };
+        let formula = match &**node {
+            ASTNode::Variable(_) => node.clone(),
+            ASTNode::Not(p) => child(p).not(),
+            ASTNode::And(p1, p2) => child(p1).and(child(p2)),
+            ASTNode::Or(p1, p2) => child(p1).or(child(p2)),
+            ASTNode::Nand(p1, p2) => child(p1).nand(child(p2)),
+            ASTNode::Nor(p1, p2) => child(p1).nor(child(p2)),
+            ASTNode::Ite(p1, p2, p3) => child(p1).ite(child(p2), child(p3)),
+        };
+        rebuilt.push(formula);
+    }
+    Definitions {
+        definitions: names
+            .iter()
+            .map(|(class, ident)| (*ident, rebuilt[*class].clone()))
+            .collect(),
+        body: rebuilt[root].clone(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Variable(Ident),
+    Unary(usize),
+    Binary(u8, usize, usize),
+    Ternary(usize, usize, usize),
+}
+
+impl Key {
+    fn children(&self) -> Vec<usize> {
+        match self {
+            Key::Variable(_) => vec![],
+            Key::Unary(p) => vec![*p],
+            Key::Binary(_, p1, p2) => vec![*p1, *p2],
+            Key::Ternary(p1, p2, p3) => vec![*p1, *p2, *p3],
+        }
+    }
+
+    fn is_literal(&self, classes: &Classes) -> bool {
+        match self {
+            Key::Variable(_) => true,
+            Key::Unary(p) => matches!(classes.nodes[*p].0, Key::Variable(_)),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Classes {
+    nodes: Vec<(Key, AST)>,
+    keys: HashMap<Key, usize>,
+    memo: HashMap<*const ASTNode, usize>,
+}
+
+impl Classes {
+    fn classify(&mut self, formula: &AST) -> usize {
+        if let Some(class) = self.memo.get(&Rc::as_ptr(formula)) {
+            return *class;
+        }
+        let key = match &**formula {
+            ASTNode::Variable(ident) => Key::Variable(*ident),
+            ASTNode::Not(p) => Key::Unary(self.classify(p)),
+            ASTNode::And(p1, p2) => Key::Binary(0, self.classify(p1), self.classify(p2)),
+            ASTNode::Or(p1, p2) => Key::Binary(1, self.classify(p1), self.classify(p2)),
+            ASTNode::Nand(p1, p2) => Key::Binary(2, self.classify(p1), self.classify(p2)),
+            ASTNode::Nor(p1, p2) => Key::Binary(3, self.classify(p1), self.classify(p2)),
+            ASTNode::Ite(p1, p2, p3) => {
+                Key::Ternary(self.classify(p1), self.classify(p2), self.classify(p3))
+            }
+        };
+        let class = match self.keys.get(&key) {
+            Some(class) => *class,
+            None => {
+                self.nodes.push((key.clone(), formula.clone()));
+                self.keys.insert(key, self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        };
+        self.memo.insert(Rc::as_ptr(formula), class);
+        class
+    }
+}
+
+fn expand(
+    formula: &AST,
+    expansions: &HashMap<Ident, AST>,
+    memo: &mut HashMap<*const ASTNode, AST>,
+) -> AST {
+    if let Some(expansion) = memo.get(&Rc::as_ptr(formula)) {
+        return expansion.clone();
+    }
+    let mut recurse = |p: &AST| expand(p, expansions, memo);
+    let expansion = match &**formula {
+        ASTNode::Variable(ident) => match expansions.get(ident) {
+            Some(expansion) => expansion.clone(),
+            None => formula.clone(),
+        },
+        ASTNode::Not(p) => recurse(p).not(),
+        ASTNode::And(p1, p2) => recurse(p1).and(recurse(p2)),
+        ASTNode::Or(p1, p2) => recurse(p1).or(recurse(p2)),
+        ASTNode::Nand(p1, p2) => recurse(p1).nand(recurse(p2)),
+        ASTNode::Nor(p1, p2) => recurse(p1).nor(recurse(p2)),
+        ASTNode::Ite(p1, p2, p3) => recurse(p1).ite(recurse(p2), recurse(p3)),
+    };
+    memo.insert(Rc::as_ptr(formula), expansion.clone());
+    expansion
+}
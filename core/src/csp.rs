@@ -0,0 +1,339 @@
+use std::fmt;
+
+use crate::{
+    ast::{Ident, Model},
+    encodings::{
+        at_least_one, at_most_one, flatten, negative, positive, Constraint, Decode, Encode, Explain,
+    },
+    sat::{Clause, Literal},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DomainEncoding {
+    #[default]
+    Direct,
+    Log,
+}
+
+impl fmt::Display for DomainEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainEncoding::Direct => write!(f, "direct"),
+            DomainEncoding::Log => write!(f, "log"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CspVariable(pub usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiniteDomain {
+    pub name: String,
+    pub values: Vec<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CspConstraint {
+    Equal(CspVariable, CspVariable),
+    NotEqual(CspVariable, CspVariable),
+    Assign(CspVariable, i64),
+    Forbid(CspVariable, i64),
+    Table {
+        variables: Vec<CspVariable>,
+        tuples: Vec<Vec<i64>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Csp {
+    pub encoding: DomainEncoding,
+    pub variables: Vec<FiniteDomain>,
+    pub constraints: Vec<CspConstraint>,
+}
+
+impl Csp {
+    pub fn new(encoding: DomainEncoding) -> Self {
+        Self {
+            encoding,
+            ..Self::default()
+        }
+    }
+
+    pub fn variable(&mut self, name: &str, values: impl IntoIterator<Item = i64>) -> CspVariable {
+        let mut domain = vec![];
+        for value in values {
+            if !domain.contains(&value) {
+                domain.push(value);
+            }
+        }
+        self.variables.push(FiniteDomain {
+            name: name.to_owned(),
+            values: domain,
+        });
+        CspVariable(self.variables.len() - 1)
+    }
+
+    pub fn equal(&mut self, lhs: CspVariable, rhs: CspVariable) {
+        self.constraints.push(CspConstraint::Equal(lhs, rhs));
+    }
+
+    pub fn not_equal(&mut self, lhs: CspVariable, rhs: CspVariable) {
+        self.constraints.push(CspConstraint::NotEqual(lhs, rhs));
+    }
+
+    pub fn assign(&mut self, variable: CspVariable, value: i64) {
+        self.constraints
+            .push(CspConstraint::Assign(variable, value));
+    }
+
+    pub fn forbid(&mut self, variable: CspVariable, value: i64) {
+        self.constraints
+            .push(CspConstraint::Forbid(variable, value));
+    }
+
+    pub fn table(&mut self, variables: Vec<CspVariable>, tuples: Vec<Vec<i64>>) {
+        self.constraints
+            .push(CspConstraint::Table { variables, tuples });
+    }
+
+    pub fn name(&self, variable: CspVariable) -> &str {
+        &self.variables[variable.0].name
+    }
+
+    fn width(&self, variable: usize) -> usize {
+        let size = self.variables[variable].values.len();
+        match self.encoding {
+            DomainEncoding::Direct => size,
+            DomainEncoding::Log => bits(size),
+        }
+    }
+
+    fn offsets(&self) -> Vec<usize> {
+        let mut offsets = vec![0];
+        for variable in 0..self.variables.len() {
+            offsets.push(offsets[variable] + self.width(variable));
+        }
+        offsets
+    }
+
+    fn cube(&self, offsets: &[usize], variable: CspVariable, index: usize) -> Vec<Literal> {
+        let offset = offsets[variable.0];
+        match self.encoding {
+            DomainEncoding::Direct => vec![positive((offset + index) as Ident)],
+            DomainEncoding::Log => (0..self.width(variable.0))
+                .map(|bit| {
+                    let ident = (offset + bit) as Ident;
+                    match index >> bit & 1 {
+                        1 => positive(ident),
+                        _ => negative(ident),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn excluding(&self, offsets: &[usize], variable: CspVariable, index: usize) -> Clause {
+        Clause::new(self.cube(offsets, variable, index).iter().map(Literal::not))
+    }
+
+    fn index_of(&self, variable: CspVariable, value: i64) -> Option<usize> {
+        self.variables[variable.0]
+            .values
+            .iter()
+            .position(|candidate| *candidate == value)
+    }
+
+    fn domain_constraint(&self, offsets: &[usize], variable: usize) -> Constraint {
+        let size = self.variables[variable].values.len();
+        let idents = (offsets[variable]..offsets[variable + 1]).map(|ident| ident as Ident);
+        let mut clauses = vec![];
+        match (self.encoding, size) {
+            (_, 0) => clauses.push(Clause::new([])),
+            (DomainEncoding::Direct, _) => {
+                clauses.push(at_least_one(idents.clone()));
+                clauses.extend(at_most_one(idents));
+            }
+            (DomainEncoding::Log, _) => {
+                clauses.extend(idents.map(|ident| Clause::new([positive(ident), negative(ident)])));
+                for index in size..1 << self.width(variable) {
+                    clauses.push(self.excluding(offsets, CspVariable(variable), index));
+                }
+            }
+        }
+        Constraint::new(
+            format!("{} takes exactly one value", self.variables[variable].name),
+            clauses,
+        )
+    }
+
+    fn constraint_clauses(
+        &self,
+        offsets: &[usize],
+        next: &mut usize,
+        constraint: &CspConstraint,
+    ) -> Vec<Clause> {
+        let mut clauses = vec![];
+        match constraint {
+            CspConstraint::Equal(lhs, rhs) => {
+                for (from, to) in [(*lhs, *rhs), (*rhs, *lhs)] {
+                    for (index, value) in self.variables[from.0].values.iter().enumerate() {
+                        let premise = self.cube(offsets, from, index);
+                        match self.index_of(to, *value) {
+                            None => clauses.push(self.excluding(offsets, from, index)),
+                            Some(other) => {
+                                for literal in self.cube(offsets, to, other) {
+                                    clauses.push(Clause::new(
+                                        premise.iter().map(Literal::not).chain([literal]),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            CspConstraint::NotEqual(lhs, rhs) => {
+                for (index, value) in self.variables[lhs.0].values.iter().enumerate() {
+                    if let Some(other) = self.index_of(*rhs, *value) {
+                        clauses.push(Clause::new(
+                            self.cube(offsets, *lhs, index)
+                                .into_iter()
+                                .chain(self.cube(offsets, *rhs, other))
+                                .map(|literal| literal.not()),
+                        ));
+                    }
+                }
+            }
+            CspConstraint::Assign(variable, value) => match self.index_of(*variable, *value) {
+                Some(index) => clauses.extend(
+                    self.cube(offsets, *variable, index)
+                        .into_iter()
+                        .map(|literal| Clause::new([literal])),
+                ),
+                None => clauses.push(Clause::new([])),
+            },
+            CspConstraint::Forbid(variable, value) => {
+                if let Some(index) = self.index_of(*variable, *value) {
+                    clauses.push(self.excluding(offsets, *variable, index));
+                }
+            }
+            CspConstraint::Table { variables, tuples } => {
+                let mut rows = vec![];
+                for tuple in tuples {
+                    let indices = variables
+                        .iter()
+                        .zip(tuple)
+                        .map(|(variable, value)| self.index_of(*variable, *value))
+                        .collect::<Option<Vec<_>>>();
+                    if let Some(indices) = indices.filter(|_| tuple.len() == variables.len()) {
+                        if !rows.contains(&indices) {
+                            rows.push(indices);
+                        }
+                    }
+                }
+                let selectors = (*next..*next + rows.len()).map(|ident| ident as Ident);
+                *next += rows.len();
+                clauses.push(at_least_one(selectors.clone()));
+                clauses.extend(at_most_one(selectors.clone()));
+                for (selector, row) in selectors.zip(rows) {
+                    for (variable, index) in variables.iter().zip(row) {
+                        for literal in self.cube(offsets, *variable, index) {
+                            clauses.push(Clause::new([negative(selector), literal]));
+                        }
+                    }
+                }
+            }
+        }
+        clauses
+    }
+
+    fn describe_constraint(&self, constraint: &CspConstraint) -> String {
+        match constraint {
+            CspConstraint::Equal(lhs, rhs) => format!("{} = {}", self.name(*lhs), self.name(*rhs)),
+            CspConstraint::NotEqual(lhs, rhs) => {
+                format!("{} ≠ {}", self.name(*lhs), self.name(*rhs))
+            }
+            CspConstraint::Assign(variable, value) => {
+                format!("{} = {}", self.name(*variable), value)
+            }
+            CspConstraint::Forbid(variable, value) => {
+                format!("{} ≠ {}", self.name(*variable), value)
+            }
+            CspConstraint::Table { variables, .. } => {
+                let names = variables
+                    .iter()
+                    .map(|variable| self.name(*variable))
+                    .collect::<Vec<_>>();
+                format!("table over ({})", names.join(", "))
+            }
+        }
+    }
+}
+
+impl Encode for Csp {
+    fn encode(&self) -> Vec<Clause> {
+        flatten(self.constraints())
+    }
+}
+
+impl Explain for Csp {
+    fn constraints(&self) -> Vec<Constraint> {
+        let offsets = self.offsets();
+        let mut next = offsets[self.variables.len()];
+        let mut constraints = (0..self.variables.len())
+            .map(|variable| self.domain_constraint(&offsets, variable))
+            .collect::<Vec<_>>();
+        for constraint in &self.constraints {
+            let clauses = self.constraint_clauses(&offsets, &mut next, constraint);
+            constraints.push(Constraint::new(
+                self.describe_constraint(constraint),
+                clauses,
+            ));
+        }
+        constraints
+    }
+
+    fn describe(&self, ident: Ident) -> Option<String> {
+        let offsets = self.offsets();
+        let ident = ident as usize;
+        let variable = (0..self.variables.len())
+            .find(|variable| (offsets[*variable]..offsets[variable + 1]).contains(&ident))?;
+        let domain = &self.variables[variable];
+        let position = ident - offsets[variable];
+        Some(match self.encoding {
+            DomainEncoding::Direct => format!("{} = {}", domain.name, domain.values[position]),
+            DomainEncoding::Log => format!("bit {} of {}", position, domain.name),
+        })
+    }
+}
+
+impl Decode for Csp {
+    type Solution = Vec<i64>;
+
+    fn decode(&self, model: &Model) -> Option<Self::Solution> {
+        let offsets = self.offsets();
+        let holds = |ident: usize| model.get(ident as Ident) == Some(true);
+        (0..self.variables.len())
+            .map(|variable| {
+                let range = offsets[variable]..offsets[variable + 1];
+                let index = match self.encoding {
+                    DomainEncoding::Direct => range.clone().position(holds)?,
+                    DomainEncoding::Log => range
+                        .clone()
+                        .enumerate()
+                        .filter(|(_, ident)| holds(*ident))
+                        .map(|(bit, _)| 1 << bit)
+                        .sum(),
+                };
+                self.variables[variable].values.get(index).copied()
+            })
+            .collect()
+    }
+}
+
+fn bits(size: usize) -> usize {
+    match size {
+        0 | 1 => 0,
+        size => (usize::BITS - (size - 1).leading_zeros()) as usize,
+    }
+}
@@ -0,0 +1,236 @@
+use std::{collections::BTreeSet, fmt, rc::Rc};
+
+use crate::{ast::Ident, ltl::KripkeStructure};
+
+pub type CtlFormula = Rc<CtlNode>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CtlNode {
+    Variable(Ident),
+    Not(CtlFormula),
+    And(CtlFormula, CtlFormula),
+    Or(CtlFormula, CtlFormula),
+    ExistsNext(CtlFormula),
+    AllNext(CtlFormula),
+    ExistsFinally(CtlFormula),
+    AllFinally(CtlFormula),
+    ExistsGlobally(CtlFormula),
+    AllGlobally(CtlFormula),
+    ExistsUntil(CtlFormula, CtlFormula),
+    AllUntil(CtlFormula, CtlFormula),
+}
+
+impl fmt::Display for CtlNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CtlNode::Variable(identifier) => write!(f, "var{}", identifier),
+            CtlNode::Not(p) => write!(f, "¬{}", p),
+            CtlNode::And(lhs, rhs) => write!(f, "({} ∧ {})", lhs, rhs),
+            CtlNode::Or(lhs, rhs) => write!(f, "({} ∨ {})", lhs, rhs),
+            CtlNode::ExistsNext(p) => write!(f, "EX{}", p),
+            CtlNode::AllNext(p) => write!(f, "AX{}", p),
+            CtlNode::ExistsFinally(p) => write!(f, "EF{}", p),
+            CtlNode::AllFinally(p) => write!(f, "AF{}", p),
+            CtlNode::ExistsGlobally(p) => write!(f, "EG{}", p),
+            CtlNode::AllGlobally(p) => write!(f, "AG{}", p),
+            CtlNode::ExistsUntil(lhs, rhs) => write!(f, "E({} U {})", lhs, rhs),
+            CtlNode::AllUntil(lhs, rhs) => write!(f, "A({} U {})", lhs, rhs),
+        }
+    }
+}
+
+pub trait BranchingTimeLogic {
+    fn variable(ident: Ident) -> CtlFormula;
+    fn not(&self) -> CtlFormula;
+    fn and(&self, other: CtlFormula) -> CtlFormula;
+    fn or(&self, other: CtlFormula) -> CtlFormula;
+    fn exists_next(&self) -> CtlFormula;
+    fn all_next(&self) -> CtlFormula;
+    fn exists_finally(&self) -> CtlFormula;
+    fn all_finally(&self) -> CtlFormula;
+    fn exists_globally(&self) -> CtlFormula;
+    fn all_globally(&self) -> CtlFormula;
+    fn exists_until(&self, other: CtlFormula) -> CtlFormula;
+    fn all_until(&self, other: CtlFormula) -> CtlFormula;
+}
+
+impl BranchingTimeLogic for CtlFormula {
+    fn variable(ident: Ident) -> CtlFormula {
+        Rc::new(CtlNode::Variable(ident))
+    }
+
+    fn not(&self) -> CtlFormula {
+        Rc::new(CtlNode::Not(self.clone()))
+    }
+
+    fn and(&self, other: CtlFormula) -> CtlFormula {
+        Rc::new(CtlNode::And(self.clone(), other))
+    }
+
+    fn or(&self, other: CtlFormula) -> CtlFormula {
+        Rc::new(CtlNode::Or(self.clone(), other))
+    }
+
+    fn exists_next(&self) -> CtlFormula {
+        Rc::new(CtlNode::ExistsNext(self.clone()))
+    }
+
+    fn all_next(&self) -> CtlFormula {
+        Rc::new(CtlNode::AllNext(self.clone()))
+    }
+
+    fn exists_finally(&self) -> CtlFormula {
+        Rc::new(CtlNode::ExistsFinally(self.clone()))
+    }
+
+    fn all_finally(&self) -> CtlFormula {
+        Rc::new(CtlNode::AllFinally(self.clone()))
+    }
+
+    fn exists_globally(&self) -> CtlFormula {
+        Rc::new(CtlNode::ExistsGlobally(self.clone()))
+    }
+
+    fn all_globally(&self) -> CtlFormula {
+        Rc::new(CtlNode::AllGlobally(self.clone()))
+    }
+
+    fn exists_until(&self, other: CtlFormula) -> CtlFormula {
+        Rc::new(CtlNode::ExistsUntil(self.clone(), other))
+    }
+
+    fn all_until(&self, other: CtlFormula) -> CtlFormula {
+        Rc::new(CtlNode::AllUntil(self.clone(), other))
+    }
+}
+
+type States = BTreeSet<usize>;
+
+struct CtlChecker<'a> {
+    structure: &'a KripkeStructure,
+}
+
+impl CtlChecker<'_> {
+    fn all(&self) -> States {
+        (0..self.structure.model.worlds()).collect()
+    }
+
+    fn successors(&self, state: usize) -> &States {
+        &self.structure.model.relation[state]
+    }
+
+    fn exists_next(&self, states: &States) -> States {
+        self.all()
+            .into_iter()
+            .filter(|state| !self.successors(*state).is_disjoint(states))
+            .collect()
+    }
+
+    fn all_next(&self, states: &States) -> States {
+        self.all()
+            .into_iter()
+            .filter(|state| self.successors(*state).is_subset(states))
+            .collect()
+    }
+
+    fn least_fixpoint(&self, step: impl Fn(&States) -> States) -> States {
+        let mut current = States::new();
+        loop {
+            let next = step(&current);
+            if next == current {
+                return current;
+            }
+            current = next;
+        }
+    }
+
+    fn greatest_fixpoint(&self, step: impl Fn(&States) -> States) -> States {
+        let mut current = self.all();
+        loop {
+            let next = step(&current);
+            if next == current {
+                return current;
+            }
+            current = next;
+        }
+    }
+
+    fn exists_until(&self, lhs: &States, rhs: &States) -> States {
+        self.least_fixpoint(|current| {
+            let next = self.exists_next(current);
+            rhs.union(&lhs.intersection(&next).copied().collect())
+                .copied()
+                .collect()
+        })
+    }
+
+    fn all_until(&self, lhs: &States, rhs: &States) -> States {
+        let live = self.exists_next(&self.all());
+        self.least_fixpoint(|current| {
+            let next = self.all_next(current);
+            let step = lhs
+                .iter()
+                .filter(|state| next.contains(state) && live.contains(state))
+                .copied()
+                .collect::<States>();
+            rhs.union(&step).copied().collect()
+        })
+    }
+
+    fn exists_globally(&self, states: &States) -> States {
+        self.greatest_fixpoint(|current| {
+            states
+                .intersection(&self.exists_next(current))
+                .copied()
+                .collect()
+        })
+    }
+
+    fn complement(&self, states: &States) -> States {
+        self.all().difference(states).copied().collect()
+    }
+
+    fn satisfying(&self, formula: &CtlFormula) -> States {
+        match &**formula {
+            CtlNode::Variable(ident) => self
+                .all()
+                .into_iter()
+                .filter(|state| self.structure.model.valuation[*state].contains(ident))
+                .collect(),
+            CtlNode::Not(p) => self.complement(&self.satisfying(p)),
+            CtlNode::And(p1, p2) => self
+                .satisfying(p1)
+                .intersection(&self.satisfying(p2))
+                .copied()
+                .collect(),
+            CtlNode::Or(p1, p2) => self
+                .satisfying(p1)
+                .union(&self.satisfying(p2))
+                .copied()
+                .collect(),
+            CtlNode::ExistsNext(p) => self.exists_next(&self.satisfying(p)),
+            CtlNode::AllNext(p) => self.all_next(&self.satisfying(p)),
+            CtlNode::ExistsFinally(p) => self.exists_until(&self.all(), &self.satisfying(p)),
+            CtlNode::AllFinally(p) => self.all_until(&self.all(), &self.satisfying(p)),
+            CtlNode::ExistsGlobally(p) => self.exists_globally(&self.satisfying(p)),
+            CtlNode::AllGlobally(p) => {
+                let violations = self.complement(&self.satisfying(p));
+                self.complement(&self.exists_until(&self.all(), &violations))
+            }
+            CtlNode::ExistsUntil(p1, p2) => {
+                self.exists_until(&self.satisfying(p1), &self.satisfying(p2))
+            }
+            CtlNode::AllUntil(p1, p2) => self.all_until(&self.satisfying(p1), &self.satisfying(p2)),
+        }
+    }
+}
+
+pub fn satisfying_states(structure: &KripkeStructure, formula: &CtlFormula) -> BTreeSet<usize> {
+    CtlChecker { structure }.satisfying(formula)
+}
+
+pub fn check(structure: &KripkeStructure, formula: &CtlFormula) -> bool {
+    structure
+        .initial
+        .is_subset(&satisfying_states(structure, formula))
+}
@@ -0,0 +1,174 @@
+use std::{
+    collections::VecDeque,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    ast::{Ident, Model},
+    cdcl::{CDCLSolver, SolveError, SolverConfig},
+    sat::{Clause, Literal, Polarity},
+};
+
+pub type Cube = Vec<Literal>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubeAndConquer {
+    pub depth: usize,
+    pub workers: usize,
+    pub config: SolverConfig,
+    pub deterministic: bool,
+}
+
+impl Default for CubeAndConquer {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            workers: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            config: SolverConfig::default(),
+            deterministic: false,
+        }
+    }
+}
+
+impl CubeAndConquer {
+    pub fn cubes(&self, clauses: &[Clause]) -> Vec<Cube> {
+        let mut solver = CDCLSolver::from(clauses.to_vec());
+        let mut cubes = vec![];
+        split(&mut solver, vec![], self.depth, &mut cubes);
+        cubes
+    }
+
+    pub fn solve(&self, clauses: &[Clause]) -> Result<Option<Model>, SolveError> {
+        let cubes = self.cubes(clauses);
+        let workers = self.workers.clamp(1, cubes.len().max(1));
+        let queues = (0..workers)
+            .map(|worker| {
+                Mutex::new(
+                    cubes
+                        .iter()
+                        .cloned()
+                        .enumerate()
+                        .skip(worker)
+                        .step_by(workers)
+                        .collect::<VecDeque<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut solver = CDCLSolver::new(self.config);
+        for clause in clauses {
+            solver.add_clause(clause);
+        }
+        let best = AtomicUsize::new(usize::MAX);
+        let model = Mutex::new(None);
+        let error = Mutex::new(None);
+        thread::scope(|scope| {
+            for worker in 0..workers {
+                let (solver, queues, best, model, error) =
+                    (&solver, &queues, &best, &model, &error);
+                scope.spawn(move || self.conquer(solver, queues, worker, best, model, error));
+            }
+        });
+        match (model.into_inner().unwrap(), error.into_inner().unwrap()) {
+            (Some((_, model)), _) => Ok(Some(model)),
+            (None, Some(error)) => Err(error),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn conquer(
+        &self,
+        solver: &CDCLSolver,
+        queues: &[Mutex<VecDeque<(usize, Cube)>>],
+        worker: usize,
+        best: &AtomicUsize,
+        model: &Mutex<Option<(usize, Model)>>,
+        error: &Mutex<Option<SolveError>>,
+    ) {
+        let mut incremental = solver.clone();
+        while let Some((index, cube)) = next_cube(queues, worker) {
+            let bound = best.load(Ordering::Relaxed);
+            if bound != usize::MAX && !self.deterministic {
+                return;
+            }
+            if index > bound {
+                continue;
+            }
+            let mut fresh;
+            let solver = match self.deterministic {
+                true => {
+                    fresh = solver.clone();
+                    &mut fresh
+                }
+                false => &mut incremental,
+            };
+            match solver.try_solve_with_assumptions(&cube) {
+                Ok(true) => {
+                    let mut model = model.lock().unwrap();
+                    if model.as_ref().is_none_or(|(found, _)| index < *found) {
+                        *model = Some((index, solver.model().cloned().unwrap_or_default()));
+                    }
+                    best.fetch_min(index, Ordering::Relaxed);
+                }
+                Ok(false) => (),
+                Err(err) => {
+                    error.lock().unwrap().get_or_insert(err);
+                }
+            }
+        }
+    }
+}
+
+fn next_cube(queues: &[Mutex<VecDeque<(usize, Cube)>>], worker: usize) -> Option<(usize, Cube)> {
+    if let Some(cube) = queues[worker].lock().unwrap().pop_front() {
+        return Some(cube);
+    }
+    (1..queues.len())
+        .map(|offset| (worker + offset) % queues.len())
+        .find_map(|victim| queues[victim].lock().unwrap().pop_back())
+}
+
+fn split(solver: &mut CDCLSolver, cube: Cube, depth: usize, cubes: &mut Vec<Cube>) {
+    if !solver.assume(&cube) {
+        return;
+    }
+    if depth == 0 {
+        cubes.push(cube);
+        return;
+    }
+    let mut best = None;
+    for ident in 0..solver.variables() as Ident {
+        if solver.is_assigned(ident) {
+            continue;
+        }
+        let positive = Literal::new(ident, Polarity::Positive);
+        let negative = Literal::new(ident, Polarity::Negative);
+        match (solver.probe(&positive), solver.probe(&negative)) {
+            (None, None) => return,
+            (None, Some(_)) => return split(solver, extend(&cube, negative), depth, cubes),
+            (Some(_), None) => return split(solver, extend(&cube, positive), depth, cubes),
+            (Some(positive), Some(negative)) => {
+                let score = (positive + 1) * (negative + 1);
+                if best.is_none_or(|(best, _)| score > best) {
+                    best = Some((score, ident));
+                }
+            }
+        }
+    }
+    match best {
+        Some((_, ident)) => {
+            let positive = Literal::new(ident, Polarity::Positive);
+            split(solver, extend(&cube, positive.not()), depth - 1, cubes);
+            split(solver, extend(&cube, positive), depth - 1, cubes);
+        }
+        None => cubes.push(cube),
+    }
+}
+
+fn extend(cube: &[Literal], literal: Literal) -> Cube {
+    cube.iter().cloned().chain([literal]).collect()
+}
@@ -0,0 +1,223 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{
+    ast::{AbstractSyntaxTree, AST},
+    knowledge::KnowledgeBase,
+    sat::Literal,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalDefault {
+    pub name: String,
+    pub prerequisite: Option<AST>,
+    pub consequent: AST,
+}
+
+impl fmt::Display for NormalDefault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.prerequisite {
+            Some(prerequisite) => write!(
+                f,
+                "{}: {} : {} / {}",
+                self.name, prerequisite, self.consequent, self.consequent
+            ),
+            None => write!(
+                f,
+                "{}: : {} / {}",
+                self.name, self.consequent, self.consequent
+            ),
+        }
+    }
+}
+
+impl NormalDefault {
+    pub fn new<T: AbstractSyntaxTree>(name: &str, prerequisite: &T, consequent: &T) -> Self {
+        Self {
+            name: name.to_owned(),
+            prerequisite: Some(prerequisite.to_ast()),
+            consequent: consequent.to_ast(),
+        }
+    }
+
+    pub fn unconditional<T: AbstractSyntaxTree>(name: &str, consequent: &T) -> Self {
+        Self {
+            name: name.to_owned(),
+            prerequisite: None,
+            consequent: consequent.to_ast(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extension {
+    pub defaults: Vec<String>,
+    pub conclusions: Vec<AST>,
+}
+
+impl fmt::Display for Extension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conclusions = self
+            .conclusions
+            .iter()
+            .map(|conclusion| conclusion.to_string())
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "{{{}}} from {{{}}}",
+            conclusions.join(", "),
+            self.defaults.join(", ")
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DefaultTheory {
+    pub knowledge: KnowledgeBase,
+    defaults: Vec<NormalDefault>,
+}
+
+impl fmt::Display for DefaultTheory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.knowledge)?;
+        for default in &self.defaults {
+            writeln!(f, "{}", default)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<KnowledgeBase> for DefaultTheory {
+    fn from(value: KnowledgeBase) -> Self {
+        Self {
+            knowledge: value,
+            defaults: vec![],
+        }
+    }
+}
+
+impl DefaultTheory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn defaults(&self) -> &[NormalDefault] {
+        &self.defaults
+    }
+
+    pub fn add_default(&mut self, default: NormalDefault) -> Option<NormalDefault> {
+        let previous = self.retract_default(&default.name);
+        self.defaults.push(default);
+        previous
+    }
+
+    pub fn retract_default(&mut self, name: &str) -> Option<NormalDefault> {
+        let index = self
+            .defaults
+            .iter()
+            .position(|default| default.name == name)?;
+        Some(self.defaults.remove(index))
+    }
+
+    pub fn extensions(&mut self) -> Vec<Extension> {
+        let literals = self
+            .defaults
+            .iter()
+            .map(|default| {
+                let prerequisite = default
+                    .prerequisite
+                    .as_ref()
+                    .map(|prerequisite| self.knowledge.literal(prerequisite));
+                let consequent = self.knowledge.literal(&default.consequent);
+                (prerequisite, consequent)
+            })
+            .collect::<Vec<_>>();
+        let mut visited = BTreeSet::new();
+        let mut extensions = vec![];
+        let mut stack = vec![vec![]];
+        while let Some(applied) = stack.pop() {
+            if !visited.insert(applied.clone()) {
+                continue;
+            }
+            let assumed = applied
+                .iter()
+                .map(|index: &usize| literals[*index].1.clone())
+                .collect::<Vec<_>>();
+            let applicable = (0..self.defaults.len())
+                .filter(|index| !applied.contains(index))
+                .filter(|index| {
+                    let (prerequisite, consequent) = &literals[*index];
+                    self.is_applicable(&assumed, prerequisite.as_ref(), consequent)
+                })
+                .collect::<Vec<_>>();
+            if applicable.is_empty() {
+                extensions.push(applied);
+                continue;
+            }
+            for index in applicable.into_iter().rev() {
+                let mut next = applied.clone();
+                next.push(index);
+                next.sort_unstable();
+                stack.push(next);
+            }
+        }
+        extensions
+            .into_iter()
+            .map(|applied| Extension {
+                defaults: applied
+                    .iter()
+                    .map(|index| self.defaults[*index].name.clone())
+                    .collect(),
+                conclusions: applied
+                    .iter()
+                    .map(|index| self.defaults[*index].consequent.clone())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    pub fn skeptically_entails<T: AbstractSyntaxTree>(&mut self, query: &T) -> bool {
+        self.extension_entailment(query)
+            .into_iter()
+            .all(|entailed| entailed)
+    }
+
+    pub fn credulously_entails<T: AbstractSyntaxTree>(&mut self, query: &T) -> bool {
+        self.extension_entailment(query)
+            .into_iter()
+            .any(|entailed| entailed)
+    }
+
+    fn extension_entailment<T: AbstractSyntaxTree>(&mut self, query: &T) -> Vec<bool> {
+        let refutation = self.knowledge.literal(query).not();
+        self.extensions()
+            .iter()
+            .map(|extension| {
+                let mut assumed = extension
+                    .conclusions
+                    .iter()
+                    .map(|conclusion| self.knowledge.literal(conclusion))
+                    .collect::<Vec<_>>();
+                assumed.push(refutation.clone());
+                !self.knowledge.check_assuming(&assumed)
+            })
+            .collect()
+    }
+
+    fn is_applicable(
+        &mut self,
+        assumed: &[Literal],
+        prerequisite: Option<&Literal>,
+        consequent: &Literal,
+    ) -> bool {
+        let mut with = assumed.to_vec();
+        if let Some(prerequisite) = prerequisite {
+            with.push(prerequisite.not());
+            if self.knowledge.check_assuming(&with) {
+                return false;
+            }
+            with.pop();
+        }
+        with.push(consequent.clone());
+        self.knowledge.check_assuming(&with)
+    }
+}
@@ -0,0 +1,184 @@
+use std::{fmt, io};
+
+use crate::{
+    ast::AbstractSyntaxTree,
+    sat::{stream_clauses_from_tree, Clause, ClauseError, Literal},
+};
+
+#[derive(Debug)]
+pub enum DimacsError {
+    MissingHeader,
+    InvalidHeader(usize),
+    InvalidToken(usize, String),
+    UnterminatedClause,
+    ClauseCountMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DimacsError::MissingHeader => write!(f, "missing problem line"),
+            DimacsError::InvalidHeader(line) => write!(f, "line {}: invalid problem line", line),
+            DimacsError::InvalidToken(line, token) => {
+                write!(f, "line {}: invalid token '{}'", line, token)
+            }
+            DimacsError::UnterminatedClause => write!(f, "last clause is not terminated by 0"),
+            DimacsError::ClauseCountMismatch { expected, found } => write!(
+                f,
+                "problem line declares {} clauses but {} were found",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DimacsLines<'a> {
+    pub variables: usize,
+    pub clauses: usize,
+    pub body: Vec<(usize, &'a str)>,
+}
+
+pub(crate) fn read_header<'a>(
+    input: &'a str,
+    format: &str,
+) -> Result<DimacsLines<'a>, DimacsError> {
+    let mut header = None;
+    let mut body = vec![];
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('%') {
+            break;
+        }
+        if line.is_empty() || line.starts_with('c') {
+            continue;
+        }
+        if line.starts_with('p') {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match fields.as_slice() {
+                ["p", f, variables, clauses] if *f == format => {
+                    header = Some((
+                        variables
+                            .parse()
+                            .map_err(|_| DimacsError::InvalidHeader(index + 1))?,
+                        clauses
+                            .parse()
+                            .map_err(|_| DimacsError::InvalidHeader(index + 1))?,
+                    ))
+                }
+                _ => return Err(DimacsError::InvalidHeader(index + 1)),
+            }
+            continue;
+        }
+        if header.is_none() {
+            return Err(DimacsError::MissingHeader);
+        }
+        body.push((index + 1, line));
+    }
+    let (variables, clauses) = header.ok_or(DimacsError::MissingHeader)?;
+    Ok(DimacsLines {
+        variables,
+        clauses,
+        body,
+    })
+}
+
+pub(crate) fn parse_literals(
+    variables: usize,
+    line: usize,
+    text: &str,
+) -> Result<Vec<i64>, DimacsError> {
+    text.split_whitespace()
+        .map(|token| match token.parse::<i64>() {
+            Ok(value) if value.unsigned_abs() as usize <= variables => Ok(value),
+            _ => Err(DimacsError::InvalidToken(line, token.to_owned())),
+        })
+        .collect()
+}
+
+pub(crate) fn parse_clauses<'a>(
+    variables: usize,
+    lines: impl IntoIterator<Item = &'a (usize, &'a str)>,
+) -> Result<Vec<Clause>, DimacsError> {
+    let mut clauses = vec![];
+    let mut current = vec![];
+    for (line, text) in lines {
+        for value in parse_literals(variables, *line, text)? {
+            if value == 0 {
+                clauses.push(Clause::new(current.drain(..)));
+            } else {
+                current.push(Literal::from_dimacs(value));
+            }
+        }
+    }
+    if !current.is_empty() {
+        return Err(DimacsError::UnterminatedClause);
+    }
+    Ok(clauses)
+}
+
+pub fn parse_dimacs(input: &str) -> Result<Vec<Clause>, DimacsError> {
+    let lines = read_header(input, "cnf")?;
+    let clauses = parse_clauses(lines.variables, &lines.body)?;
+    if clauses.len() != lines.clauses {
+        return Err(DimacsError::ClauseCountMismatch {
+            expected: lines.clauses,
+            found: clauses.len(),
+        });
+    }
+    Ok(clauses)
+}
+
+pub fn variable_count(clauses: &[Clause]) -> usize {
+    clauses
+        .iter()
+        .flat_map(|clause| clause.literals())
+        .map(|literal| literal.identifier() as usize + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn write_dimacs_to<W: io::Write>(
+    out: &mut W,
+    variables: usize,
+    clauses: usize,
+    stream: impl IntoIterator<Item = Clause>,
+) -> io::Result<()> {
+    writeln!(out, "p cnf {} {}", variables, clauses)?;
+    for clause in stream {
+        writeln!(out, "{}", clause.to_dimacs())?;
+    }
+    Ok(())
+}
+
+pub fn write_cnf_tree_to<W: io::Write, T: AbstractSyntaxTree>(
+    out: &mut W,
+    cnf: T,
+) -> io::Result<()> {
+    let invalid = |err: ClauseError| io::Error::new(io::ErrorKind::InvalidData, err);
+    let (mut variables, mut clauses) = (0, 0);
+    for clause in stream_clauses_from_tree(cnf.clone()) {
+        let clause = clause.map_err(invalid)?;
+        variables = clause
+            .literals()
+            .map(|literal| literal.identifier() as usize + 1)
+            .fold(variables, usize::max);
+        clauses += 1;
+    }
+    writeln!(out, "p cnf {} {}", variables, clauses)?;
+    for clause in stream_clauses_from_tree(cnf) {
+        writeln!(out, "{}", clause.map_err(invalid)?.to_dimacs())?;
+    }
+    Ok(())
+}
+
+pub fn write_dimacs(clauses: &[Clause]) -> String {
+    let mut dimacs = format!("p cnf {} {}\n", variable_count(clauses), clauses.len());
+    for clause in clauses {
+        dimacs.push_str(&clause.to_dimacs());
+        dimacs.push('\n');
+    }
+    dimacs
+}
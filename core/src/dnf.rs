@@ -0,0 +1,177 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, Node, AST},
+    cube::Cube,
+    error::Error,
+    sat::{ClauseError, Literal},
+};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DnfFormula {
+    cubes: Vec<Cube>,
+}
+
+impl fmt::Display for DnfFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}]",
+            self.cubes
+                .iter()
+                .map(|cube| {
+                    cube.iter()
+                        .map(|literal| literal.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" ∧ ")
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl From<Vec<Cube>> for DnfFormula {
+    fn from(value: Vec<Cube>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl TryFrom<&AST> for DnfFormula {
+    type Error = Error;
+
+    fn try_from(value: &AST) -> Result<Self, Self::Error> {
+        Ok(Self::from(try_generate_cubes_from_tree(value.dnf()?)?))
+    }
+}
+
+impl FromIterator<Cube> for DnfFormula {
+    fn from_iter<I: IntoIterator<Item = Cube>>(iter: I) -> Self {
+        let mut formula = Self::new();
+        formula.extend(iter);
+        formula
+    }
+}
+
+impl Extend<Cube> for DnfFormula {
+    fn extend<I: IntoIterator<Item = Cube>>(&mut self, iter: I) {
+        for cube in iter {
+            self.add_cube(cube);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DnfFormula {
+    type Item = &'a Cube;
+    type IntoIter = std::slice::Iter<'a, Cube>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cubes.iter()
+    }
+}
+
+impl DnfFormula {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_cube(&mut self, mut cube: Cube) {
+        cube.sort();
+        cube.dedup();
+        self.cubes.push(cube);
+    }
+
+    pub fn cubes(&self) -> impl Iterator<Item = &Cube> {
+        self.cubes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cubes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cubes.is_empty()
+    }
+
+    pub fn variables(&self) -> BTreeSet<Ident> {
+        self.cubes
+            .iter()
+            .flatten()
+            .map(|literal| literal.identifier())
+            .collect()
+    }
+
+    pub fn covering<'a>(&'a self, model: &'a Model) -> impl Iterator<Item = &'a Cube> {
+        self.cubes.iter().filter(move |cube| covers(cube, model))
+    }
+
+    pub fn verify_model(&self, model: &Model) -> bool {
+        self.covering(model).next().is_some()
+    }
+
+    pub fn to_ast<T: AbstractSyntaxTree>(&self) -> T {
+        let constant = |value: bool| {
+            let var = T::variable(0);
+            match value {
+                true => var.or(var.not()),
+                false => var.and(var.not()),
+            }
+        };
+        self.cubes
+            .iter()
+            .map(|cube| {
+                cube.iter()
+                    .map(|literal| literal.to_ast::<T>())
+                    .reduce(|lhs, rhs| lhs.and(rhs))
+                    .unwrap_or_else(|| constant(true))
+            })
+            .reduce(|lhs, rhs| lhs.or(rhs))
+            .unwrap_or_else(|| constant(false))
+    }
+}
+
+fn covers(cube: &Cube, model: &Model) -> bool {
+    cube.iter()
+        .all(|literal| model.get(literal.identifier()) == Some(literal.is_positive()))
+}
+
+pub fn try_generate_cubes_from_tree<T: AbstractSyntaxTree>(
+    ast: T,
+) -> Result<Vec<Cube>, ClauseError> {
+    let mut cubes = vec![];
+    let mut subtrees = vec![ast];
+    while let Some(subtree) = subtrees.pop() {
+        match subtree.node() {
+            Node::Or(p1, p2) => {
+                subtrees.push(p2);
+                subtrees.push(p1);
+            }
+            _ => cubes.push(generate_cube_from_subtree(subtree)?),
+        }
+    }
+    Ok(cubes)
+}
+
+fn generate_cube_from_subtree<T: AbstractSyntaxTree>(ast: T) -> Result<Cube, ClauseError> {
+    let mut literals = BTreeSet::new();
+    let mut subtrees = vec![ast];
+    while let Some(subtree) = subtrees.pop() {
+        match subtree.node() {
+            Node::Variable(ident) => {
+                literals.insert(Literal::positive(ident));
+            }
+            Node::Not(variable) => match variable.node() {
+                Node::Variable(ident) => {
+                    literals.insert(Literal::negative(ident));
+                }
+                _ => return Err(ClauseError::NotALiteral(subtree.to_ast().to_string())),
+            },
+            Node::And(p1, p2) => {
+                subtrees.push(p1);
+                subtrees.push(p2);
+            }
+            _ => return Err(ClauseError::NotALiteral(subtree.to_ast().to_string())),
+        }
+    }
+    Ok(literals.into_iter().collect())
+}
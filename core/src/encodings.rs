@@ -0,0 +1,510 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{
+    ast::{Ident, Model},
+    cdcl::CDCLSolver,
+    counting::ProjectedModels,
+    mus::minimal_unsatisfiable_subset,
+    sat::{Clause, Literal, Polarity},
+};
+
+pub trait Encode {
+    fn encode(&self) -> Vec<Clause>;
+}
+
+pub trait Explain: Encode {
+    fn constraints(&self) -> Vec<Constraint>;
+    fn describe(&self, ident: Ident) -> Option<String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    pub name: String,
+    pub clauses: Vec<Clause>,
+}
+
+impl Constraint {
+    pub fn new(name: impl Into<String>, clauses: Vec<Clause>) -> Self {
+        Self {
+            name: name.into(),
+            clauses,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub constraint: String,
+    pub clauses: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub conflicts: Vec<Conflict>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .conflicts
+            .iter()
+            .map(|conflict| format!("{}: {}", conflict.constraint, conflict.clauses.join("; ")))
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+pub trait Decode {
+    type Solution;
+
+    fn decode(&self, model: &Model) -> Option<Self::Solution>;
+}
+
+pub fn solve<P: Encode + Decode>(problem: &P) -> Option<P::Solution> {
+    let mut solver = CDCLSolver::from(problem.encode());
+    match solver.solve_complete(&[]) {
+        true => problem.decode(solver.model()?),
+        false => None,
+    }
+}
+
+pub fn solutions<P: Encode + Decode>(problem: &P) -> impl Iterator<Item = P::Solution> + '_ {
+    let clauses = problem.encode();
+    let variables = clauses
+        .iter()
+        .flat_map(|clause| clause.literals())
+        .map(|literal| literal.identifier())
+        .collect::<BTreeSet<_>>();
+    ProjectedModels::new(&clauses, variables).filter_map(|model| problem.decode(&model))
+}
+
+pub fn explain<P: Explain>(problem: &P) -> Option<Explanation> {
+    let constraints = problem.constraints();
+    let groups = constraints
+        .iter()
+        .map(|constraint| constraint.clauses.clone())
+        .collect::<Vec<_>>();
+    let core = minimal_unsatisfiable_subset(&groups)?;
+    let describe = |literal: &Literal| {
+        let name = problem
+            .describe(literal.identifier())
+            .unwrap_or_else(|| format!("var{}", literal.identifier()));
+        match literal.polarity() {
+            Polarity::Positive => name,
+            Polarity::Negative => format!("¬({})", name),
+        }
+    };
+    let conflicts = core
+        .into_iter()
+        .map(|index| Conflict {
+            constraint: constraints[index].name.clone(),
+            clauses: constraints[index]
+                .clauses
+                .iter()
+                .map(|clause| {
+                    clause
+                        .literals()
+                        .map(describe)
+                        .collect::<Vec<_>>()
+                        .join(" ∨ ")
+                })
+                .collect(),
+        })
+        .collect();
+    Some(Explanation { conflicts })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pigeonhole {
+    pub pigeons: usize,
+    pub holes: usize,
+}
+
+impl Pigeonhole {
+    pub fn new(pigeons: usize, holes: usize) -> Self {
+        Self { pigeons, holes }
+    }
+
+    pub fn variable(&self, pigeon: usize, hole: usize) -> Ident {
+        (pigeon * self.holes + hole) as Ident
+    }
+}
+
+impl Encode for Pigeonhole {
+    fn encode(&self) -> Vec<Clause> {
+        flatten(self.constraints())
+    }
+}
+
+impl Explain for Pigeonhole {
+    fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = vec![];
+        for pigeon in 0..self.pigeons {
+            constraints.push(Constraint::new(
+                format!("pigeon {} sits in a hole", pigeon),
+                vec![at_least_one(
+                    (0..self.holes).map(|hole| self.variable(pigeon, hole)),
+                )],
+            ));
+        }
+        for hole in 0..self.holes {
+            constraints.push(Constraint::new(
+                format!("hole {} holds at most one pigeon", hole),
+                at_most_one((0..self.pigeons).map(|pigeon| self.variable(pigeon, hole))),
+            ));
+        }
+        constraints
+    }
+
+    fn describe(&self, ident: Ident) -> Option<String> {
+        let ident = ident as usize;
+        (self.holes > 0 && ident < self.pigeons * self.holes).then(|| {
+            format!(
+                "pigeon {} in hole {}",
+                ident / self.holes,
+                ident % self.holes
+            )
+        })
+    }
+}
+
+impl Decode for Pigeonhole {
+    type Solution = Vec<usize>;
+
+    fn decode(&self, model: &Model) -> Option<Self::Solution> {
+        (0..self.pigeons)
+            .map(|pigeon| {
+                first_true(
+                    model,
+                    (0..self.holes).map(|hole| self.variable(pigeon, hole)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphColoring {
+    pub vertices: usize,
+    pub edges: Vec<(usize, usize)>,
+    pub colors: usize,
+}
+
+impl GraphColoring {
+    pub fn new(vertices: usize, edges: Vec<(usize, usize)>, colors: usize) -> Self {
+        Self {
+            vertices,
+            edges,
+            colors,
+        }
+    }
+
+    pub fn variable(&self, vertex: usize, color: usize) -> Ident {
+        (vertex * self.colors + color) as Ident
+    }
+}
+
+impl Encode for GraphColoring {
+    fn encode(&self) -> Vec<Clause> {
+        flatten(self.constraints())
+    }
+}
+
+impl Explain for GraphColoring {
+    fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = vec![];
+        for vertex in 0..self.vertices {
+            let variables = (0..self.colors).map(|color| self.variable(vertex, color));
+            let mut clauses = vec![at_least_one(variables.clone())];
+            clauses.extend(at_most_one(variables));
+            constraints.push(Constraint::new(
+                format!("vertex {} has exactly one color", vertex),
+                clauses,
+            ));
+        }
+        for (from, to) in &self.edges {
+            constraints.push(Constraint::new(
+                format!("vertices {} and {} differ in color", from, to),
+                (0..self.colors)
+                    .map(|color| {
+                        Clause::new([
+                            negative(self.variable(*from, color)),
+                            negative(self.variable(*to, color)),
+                        ])
+                    })
+                    .collect(),
+            ));
+        }
+        constraints
+    }
+
+    fn describe(&self, ident: Ident) -> Option<String> {
+        let ident = ident as usize;
+        (self.colors > 0 && ident < self.vertices * self.colors).then(|| {
+            format!(
+                "vertex {} has color {}",
+                ident / self.colors,
+                ident % self.colors
+            )
+        })
+    }
+}
+
+impl Decode for GraphColoring {
+    type Solution = Vec<usize>;
+
+    fn decode(&self, model: &Model) -> Option<Self::Solution> {
+        (0..self.vertices)
+            .map(|vertex| {
+                first_true(
+                    model,
+                    (0..self.colors).map(|color| self.variable(vertex, color)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NQueens {
+    pub size: usize,
+}
+
+impl NQueens {
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+
+    pub fn variable(&self, row: usize, column: usize) -> Ident {
+        (row * self.size + column) as Ident
+    }
+}
+
+impl Encode for NQueens {
+    fn encode(&self) -> Vec<Clause> {
+        flatten(self.constraints())
+    }
+}
+
+impl Explain for NQueens {
+    fn constraints(&self) -> Vec<Constraint> {
+        let n = self.size;
+        let mut constraints = vec![];
+        for row in 0..n {
+            let variables = (0..n).map(|column| self.variable(row, column));
+            let mut clauses = vec![at_least_one(variables.clone())];
+            clauses.extend(at_most_one(variables));
+            constraints.push(Constraint::new(
+                format!("row {} holds exactly one queen", row),
+                clauses,
+            ));
+        }
+        for column in 0..n {
+            constraints.push(Constraint::new(
+                format!("column {} holds at most one queen", column),
+                at_most_one((0..n).map(|row| self.variable(row, column))),
+            ));
+        }
+        for diagonal in 0..(2 * n).saturating_sub(1) {
+            let squares = (0..n).filter_map(|row| {
+                let column = diagonal.checked_sub(row)?;
+                (column < n).then_some((row, column))
+            });
+            constraints.push(Constraint::new(
+                format!("diagonal {} holds at most one queen", diagonal),
+                at_most_one(
+                    squares
+                        .clone()
+                        .map(|(row, column)| self.variable(row, column)),
+                ),
+            ));
+            constraints.push(Constraint::new(
+                format!("anti-diagonal {} holds at most one queen", diagonal),
+                at_most_one(squares.map(|(row, column)| self.variable(row, n - 1 - column))),
+            ));
+        }
+        constraints
+    }
+
+    fn describe(&self, ident: Ident) -> Option<String> {
+        let ident = ident as usize;
+        (ident < self.size * self.size)
+            .then(|| format!("queen at ({}, {})", ident / self.size, ident % self.size))
+    }
+}
+
+impl Decode for NQueens {
+    type Solution = Vec<usize>;
+
+    fn decode(&self, model: &Model) -> Option<Self::Solution> {
+        (0..self.size)
+            .map(|row| {
+                first_true(
+                    model,
+                    (0..self.size).map(|column| self.variable(row, column)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sudoku {
+    pub block: usize,
+    pub givens: Vec<Vec<usize>>,
+}
+
+impl Sudoku {
+    pub fn new(givens: Vec<Vec<usize>>) -> Self {
+        Self::with_block(3, givens)
+    }
+
+    pub fn with_block(block: usize, givens: Vec<Vec<usize>>) -> Self {
+        Self { block, givens }
+    }
+
+    pub fn size(&self) -> usize {
+        self.block * self.block
+    }
+
+    pub fn variable(&self, row: usize, column: usize, digit: usize) -> Ident {
+        let n = self.size();
+        ((row * n + column) * n + digit - 1) as Ident
+    }
+}
+
+impl Encode for Sudoku {
+    fn encode(&self) -> Vec<Clause> {
+        flatten(self.constraints())
+    }
+}
+
+impl Explain for Sudoku {
+    fn constraints(&self) -> Vec<Constraint> {
+        let n = self.size();
+        let mut constraints = vec![];
+        let mut groups = vec![];
+        for index in 0..n {
+            groups.push((
+                format!("row {}", index),
+                (0..n).map(|column| (index, column)).collect::<Vec<_>>(),
+            ));
+            groups.push((
+                format!("column {}", index),
+                (0..n).map(|row| (row, index)).collect(),
+            ));
+            let (top, left) = (
+                index / self.block * self.block,
+                index % self.block * self.block,
+            );
+            groups.push((
+                format!("box {}", index),
+                (0..n)
+                    .map(|cell| (top + cell / self.block, left + cell % self.block))
+                    .collect(),
+            ));
+        }
+        for row in 0..n {
+            for column in 0..n {
+                let variables = (1..=n).map(|digit| self.variable(row, column, digit));
+                let mut clauses = vec![at_least_one(variables.clone())];
+                clauses.extend(at_most_one(variables));
+                constraints.push(Constraint::new(
+                    format!("cell ({}, {}) holds exactly one digit", row, column),
+                    clauses,
+                ));
+            }
+        }
+        for (name, group) in &groups {
+            for digit in 1..=n {
+                constraints.push(Constraint::new(
+                    format!("{} holds digit {} at most once", name, digit),
+                    at_most_one(
+                        group
+                            .iter()
+                            .map(|(row, column)| self.variable(*row, *column, digit)),
+                    ),
+                ));
+            }
+        }
+        for (row, digits) in self.givens.iter().enumerate().take(n) {
+            for (column, digit) in digits.iter().enumerate().take(n) {
+                if (1..=n).contains(digit) {
+                    constraints.push(Constraint::new(
+                        format!("cell ({}, {}) is given as {}", row, column, digit),
+                        vec![Clause::new([positive(self.variable(row, column, *digit))])],
+                    ));
+                }
+            }
+        }
+        constraints
+    }
+
+    fn describe(&self, ident: Ident) -> Option<String> {
+        let n = self.size();
+        let ident = ident as usize;
+        (n > 0 && ident < n * n * n).then(|| {
+            format!(
+                "cell ({}, {}) = {}",
+                ident / n / n,
+                ident / n % n,
+                ident % n + 1
+            )
+        })
+    }
+}
+
+impl Decode for Sudoku {
+    type Solution = Vec<Vec<usize>>;
+
+    fn decode(&self, model: &Model) -> Option<Self::Solution> {
+        let n = self.size();
+        (0..n)
+            .map(|row| {
+                (0..n)
+                    .map(|column| {
+                        first_true(
+                            model,
+                            (1..=n).map(|digit| self.variable(row, column, digit)),
+                        )
+                        .map(|digit| digit + 1)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn flatten(constraints: Vec<Constraint>) -> Vec<Clause> {
+    constraints
+        .into_iter()
+        .flat_map(|constraint| constraint.clauses)
+        .collect()
+}
+
+pub(crate) fn positive(ident: Ident) -> Literal {
+    Literal::new(ident, Polarity::Positive)
+}
+
+pub(crate) fn negative(ident: Ident) -> Literal {
+    Literal::new(ident, Polarity::Negative)
+}
+
+pub(crate) fn at_least_one(variables: impl Iterator<Item = Ident>) -> Clause {
+    Clause::new(variables.map(positive))
+}
+
+pub(crate) fn at_most_one(variables: impl Iterator<Item = Ident>) -> Vec<Clause> {
+    let variables = variables.collect::<Vec<_>>();
+    let mut clauses = vec![];
+    for (index, first) in variables.iter().enumerate() {
+        for second in &variables[index + 1..] {
+            clauses.push(Clause::new([negative(*first), negative(*second)]));
+        }
+    }
+    clauses
+}
+
+fn first_true(model: &Model, mut variables: impl Iterator<Item = Ident>) -> Option<usize> {
+    variables.position(|ident| model.get(ident) == Some(true))
+}
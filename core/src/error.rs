@@ -0,0 +1,115 @@
+use std::fmt;
+
+use crate::{
+    ast::ParseError, budget::BudgetError, cdcl::SolveError, config::ConfigError,
+    dimacs::DimacsError, karnaugh::KarnaughError, proof::ProofError, rewrite::RewriteError,
+    sat::ClauseError, unify::UnificationError,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(ParseError),
+    Rewrite(RewriteError),
+    Clause(ClauseError),
+    Solve(SolveError),
+    Config(ConfigError),
+    Dimacs(DimacsError),
+    Proof(ProofError),
+    Unification(UnificationError),
+    Karnaugh(KarnaughError),
+    Budget(BudgetError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "parse error: {}", err),
+            Error::Rewrite(err) => write!(f, "rewrite error: {}", err),
+            Error::Clause(err) => write!(f, "clause error: {}", err),
+            Error::Solve(err) => write!(f, "solver error: {}", err),
+            Error::Config(err) => write!(f, "configuration error: {}", err),
+            Error::Dimacs(err) => write!(f, "dimacs error: {}", err),
+            Error::Proof(err) => write!(f, "proof error: {}", err),
+            Error::Unification(err) => write!(f, "unification error: {}", err),
+            Error::Karnaugh(err) => write!(f, "karnaugh map error: {}", err),
+            Error::Budget(err) => write!(f, "budget exceeded: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            Error::Rewrite(err) => Some(err),
+            Error::Clause(err) => Some(err),
+            Error::Solve(err) => Some(err),
+            Error::Config(err) => Some(err),
+            Error::Dimacs(err) => Some(err),
+            Error::Proof(err) => Some(err),
+            Error::Unification(err) => Some(err),
+            Error::Karnaugh(err) => Some(err),
+            Error::Budget(err) => Some(err),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(value: ParseError) -> Self {
+        Error::Parse(value)
+    }
+}
+
+impl From<RewriteError> for Error {
+    fn from(value: RewriteError) -> Self {
+        Error::Rewrite(value)
+    }
+}
+
+impl From<ClauseError> for Error {
+    fn from(value: ClauseError) -> Self {
+        Error::Clause(value)
+    }
+}
+
+impl From<SolveError> for Error {
+    fn from(value: SolveError) -> Self {
+        Error::Solve(value)
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(value: ConfigError) -> Self {
+        Error::Config(value)
+    }
+}
+
+impl From<DimacsError> for Error {
+    fn from(value: DimacsError) -> Self {
+        Error::Dimacs(value)
+    }
+}
+
+impl From<ProofError> for Error {
+    fn from(value: ProofError) -> Self {
+        Error::Proof(value)
+    }
+}
+
+impl From<UnificationError> for Error {
+    fn from(value: UnificationError) -> Self {
+        Error::Unification(value)
+    }
+}
+
+impl From<KarnaughError> for Error {
+    fn from(value: KarnaughError) -> Self {
+        Error::Karnaugh(value)
+    }
+}
+
+impl From<BudgetError> for Error {
+    fn from(value: BudgetError) -> Self {
+        Error::Budget(value)
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use crate::cnf::CnfFormula;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Summary {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub deviation: f64,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {}, max {}, mean {:.3}, deviation {:.3}",
+            self.min, self.max, self.mean, self.deviation
+        )
+    }
+}
+
+impl Summary {
+    fn new(values: impl IntoIterator<Item = usize>) -> Self {
+        let values = values.into_iter().collect::<Vec<_>>();
+        if values.is_empty() {
+            return Self::default();
+        }
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<usize>() as f64 / count;
+        let variance = values
+            .iter()
+            .map(|value| (*value as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        Self {
+            min: values.iter().copied().min().unwrap_or(0),
+            max: values.iter().copied().max().unwrap_or(0),
+            mean,
+            deviation: variance.sqrt(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InstanceFeatures {
+    pub variables: usize,
+    pub clauses: usize,
+    pub ratio: f64,
+    pub length_histogram: BTreeMap<usize, usize>,
+    pub clause_length: Summary,
+    pub horn_fraction: f64,
+    pub positive_fraction: f64,
+    pub variable_degree: Summary,
+}
+
+impl fmt::Display for InstanceFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let histogram = self
+            .length_histogram
+            .iter()
+            .map(|(length, count)| format!("{}: {}", length, count))
+            .collect::<Vec<_>>();
+        writeln!(f, "variables:       {}", self.variables)?;
+        writeln!(f, "clauses:         {}", self.clauses)?;
+        writeln!(f, "ratio:           {:.3}", self.ratio)?;
+        writeln!(f, "lengths:         {{{}}}", histogram.join(", "))?;
+        writeln!(f, "clause length:   {}", self.clause_length)?;
+        writeln!(f, "horn fraction:   {:.3}", self.horn_fraction)?;
+        writeln!(f, "positive:        {:.3}", self.positive_fraction)?;
+        write!(f, "variable degree: {}", self.variable_degree)
+    }
+}
+
+impl InstanceFeatures {
+    pub fn vector(&self) -> Vec<f64> {
+        let fraction = |length: usize| {
+            self.length_histogram.get(&length).copied().unwrap_or(0) as f64
+                / self.clauses.max(1) as f64
+        };
+        vec![
+            self.variables as f64,
+            self.clauses as f64,
+            self.ratio,
+            fraction(1),
+            fraction(2),
+            fraction(3),
+            self.clause_length.mean,
+            self.clause_length.deviation,
+            self.clause_length.max as f64,
+            self.horn_fraction,
+            self.positive_fraction,
+            self.variable_degree.mean,
+            self.variable_degree.deviation,
+            self.variable_degree.min as f64,
+            self.variable_degree.max as f64,
+        ]
+    }
+}
+
+pub fn features(formula: &CnfFormula) -> InstanceFeatures {
+    let mut length_histogram = BTreeMap::new();
+    let mut degrees = HashMap::new();
+    let mut horn = 0;
+    let mut positive = 0;
+    let mut occurrences = 0;
+    for clause in formula.clauses() {
+        *length_histogram.entry(clause.len()).or_insert(0) += 1;
+        let positives = clause
+            .literals()
+            .filter(|literal| literal.is_positive())
+            .count();
+        if positives <= 1 {
+            horn += 1;
+        }
+        positive += positives;
+        occurrences += clause.len();
+        for literal in clause.literals() {
+            *degrees.entry(literal.identifier()).or_insert(0) += 1;
+        }
+    }
+    let clauses = formula.len();
+    let variables = degrees.len();
+    InstanceFeatures {
+        variables,
+        clauses,
+        ratio: clauses as f64 / variables.max(1) as f64,
+        clause_length: Summary::new(formula.clauses().map(|clause| clause.len())),
+        length_histogram,
+        horn_fraction: horn as f64 / clauses.max(1) as f64,
+        positive_fraction: positive as f64 / occurrences.max(1) as f64,
+        variable_degree: Summary::new(degrees.into_values()),
+    }
+}
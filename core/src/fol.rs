@@ -0,0 +1,486 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    rc::Rc,
+};
+
+use crate::sat::Polarity;
+
+pub type Term = Rc<TermNode>;
+pub type Formula = Rc<FormulaNode>;
+pub type Substitution = HashMap<String, Term>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TermNode {
+    Variable(String),
+    Function(String, Vec<Term>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FormulaNode {
+    Predicate(String, Vec<Term>),
+    Not(Formula),
+    And(Formula, Formula),
+    Or(Formula, Formula),
+    ForAll(String, Formula),
+    Exists(String, Formula),
+}
+
+fn write_arguments(f: &mut fmt::Formatter<'_>, name: &str, arguments: &[Term]) -> fmt::Result {
+    if arguments.is_empty() {
+        return write!(f, "{}", name);
+    }
+    write!(
+        f,
+        "{}({})",
+        name,
+        arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+impl fmt::Display for TermNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TermNode::Variable(name) => write!(f, "{}", name),
+            TermNode::Function(name, arguments) => write_arguments(f, name, arguments),
+        }
+    }
+}
+
+impl fmt::Display for FormulaNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormulaNode::Predicate(name, arguments) => write_arguments(f, name, arguments),
+            FormulaNode::Not(p) => write!(f, "¬{}", p),
+            FormulaNode::And(lhs, rhs) => write!(f, "({} ∧ {})", lhs, rhs),
+            FormulaNode::Or(lhs, rhs) => write!(f, "({} ∨ {})", lhs, rhs),
+            FormulaNode::ForAll(variable, p) => write!(f, "∀{}.{}", variable, p),
+            FormulaNode::Exists(variable, p) => write!(f, "∃{}.{}", variable, p),
+        }
+    }
+}
+
+pub fn variable(name: &str) -> Term {
+    Rc::new(TermNode::Variable(name.to_owned()))
+}
+
+pub fn function(name: &str, arguments: Vec<Term>) -> Term {
+    Rc::new(TermNode::Function(name.to_owned(), arguments))
+}
+
+pub fn constant(name: &str) -> Term {
+    function(name, vec![])
+}
+
+pub fn predicate(name: &str, arguments: Vec<Term>) -> Formula {
+    Rc::new(FormulaNode::Predicate(name.to_owned(), arguments))
+}
+
+pub fn fresh_variable(base: &str, avoid: &BTreeSet<String>) -> String {
+    (1..)
+        .map(|n| format!("{}{}", base, n))
+        .find(|candidate| !avoid.contains(candidate))
+        .unwrap()
+}
+
+pub trait FirstOrderTerm {
+    fn variables(&self) -> BTreeSet<String>;
+    fn substitute(&self, substitution: &Substitution) -> Term;
+    fn occurs(&self, variable: &str) -> bool;
+}
+
+impl FirstOrderTerm for Term {
+    fn variables(&self) -> BTreeSet<String> {
+        let mut variables = BTreeSet::new();
+        let mut subterms = vec![self];
+        while let Some(subterm) = subterms.pop() {
+            match &**subterm {
+                TermNode::Variable(name) => {
+                    variables.insert(name.clone());
+                }
+                TermNode::Function(_, arguments) => subterms.extend(arguments),
+            }
+        }
+        variables
+    }
+
+    fn substitute(&self, substitution: &Substitution) -> Term {
+        match &**self {
+            TermNode::Variable(name) => match substitution.get(name) {
+                Some(term) => term.clone(),
+                None => self.clone(),
+            },
+            TermNode::Function(name, arguments) => Rc::new(TermNode::Function(
+                name.clone(),
+                arguments
+                    .iter()
+                    .map(|argument| argument.substitute(substitution))
+                    .collect(),
+            )),
+        }
+    }
+
+    fn occurs(&self, variable: &str) -> bool {
+        match &**self {
+            TermNode::Variable(name) => name == variable,
+            TermNode::Function(_, arguments) => {
+                arguments.iter().any(|argument| argument.occurs(variable))
+            }
+        }
+    }
+}
+
+pub trait FirstOrderFormula {
+    fn not(&self) -> Formula;
+    fn and(&self, other: Formula) -> Formula;
+    fn or(&self, other: Formula) -> Formula;
+    fn forall(&self, variable: &str) -> Formula;
+    fn exists(&self, variable: &str) -> Formula;
+    fn free_variables(&self) -> BTreeSet<String>;
+    fn substitute(&self, substitution: &Substitution) -> Formula;
+    fn substitute_variable(&self, variable: &str, term: Term) -> Formula;
+    fn is_closed(&self) -> bool;
+}
+
+impl FirstOrderFormula for Formula {
+    fn not(&self) -> Formula {
+        Rc::new(FormulaNode::Not(self.clone()))
+    }
+
+    fn and(&self, other: Formula) -> Formula {
+        Rc::new(FormulaNode::And(self.clone(), other))
+    }
+
+    fn or(&self, other: Formula) -> Formula {
+        Rc::new(FormulaNode::Or(self.clone(), other))
+    }
+
+    fn forall(&self, variable: &str) -> Formula {
+        Rc::new(FormulaNode::ForAll(variable.to_owned(), self.clone()))
+    }
+
+    fn exists(&self, variable: &str) -> Formula {
+        Rc::new(FormulaNode::Exists(variable.to_owned(), self.clone()))
+    }
+
+    fn free_variables(&self) -> BTreeSet<String> {
+        match &**self {
+            FormulaNode::Predicate(_, arguments) => arguments
+                .iter()
+                .flat_map(|argument| argument.variables())
+                .collect(),
+            FormulaNode::Not(p) => p.free_variables(),
+            FormulaNode::And(p1, p2) | FormulaNode::Or(p1, p2) => {
+                let mut variables = p1.free_variables();
+                variables.extend(p2.free_variables());
+                variables
+            }
+            FormulaNode::ForAll(variable, p) | FormulaNode::Exists(variable, p) => {
+                let mut variables = p.free_variables();
+                variables.remove(variable);
+                variables
+            }
+        }
+    }
+
+    fn substitute(&self, substitution: &Substitution) -> Formula {
+        match &**self {
+            FormulaNode::Predicate(name, arguments) => Rc::new(FormulaNode::Predicate(
+                name.clone(),
+                arguments
+                    .iter()
+                    .map(|argument| argument.substitute(substitution))
+                    .collect(),
+            )),
+            FormulaNode::Not(p) => p.substitute(substitution).not(),
+            FormulaNode::And(p1, p2) => {
+                p1.substitute(substitution).and(p2.substitute(substitution))
+            }
+            FormulaNode::Or(p1, p2) => p1.substitute(substitution).or(p2.substitute(substitution)),
+            FormulaNode::ForAll(variable, p) | FormulaNode::Exists(variable, p) => {
+                let mut inner = substitution.clone();
+                inner.remove(variable);
+                let free = p.free_variables();
+                inner.retain(|name, _| free.contains(name));
+                let captured = inner.values().any(|term| term.occurs(variable));
+                let (bound, body) = if captured {
+                    let mut avoid = free;
+                    for term in inner.values() {
+                        avoid.extend(term.variables());
+                    }
+                    avoid.extend(inner.keys().cloned());
+                    let renamed = fresh_variable(variable, &avoid);
+                    inner.insert(variable.clone(), self::variable(&renamed));
+                    (renamed, p.substitute(&inner))
+                } else {
+                    (variable.clone(), p.substitute(&inner))
+                };
+                match &**self {
+                    FormulaNode::ForAll(..) => body.forall(&bound),
+                    _ => body.exists(&bound),
+                }
+            }
+        }
+    }
+
+    fn substitute_variable(&self, variable: &str, term: Term) -> Formula {
+        self.substitute(&HashMap::from([(variable.to_owned(), term)]))
+    }
+
+    fn is_closed(&self) -> bool {
+        self.free_variables().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FolLiteral {
+    pub polarity: Polarity,
+    pub predicate: String,
+    pub arguments: Vec<Term>,
+}
+
+impl fmt::Display for FolLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.polarity == Polarity::Negative {
+            write!(f, "¬")?;
+        }
+        write_arguments(f, &self.predicate, &self.arguments)
+    }
+}
+
+impl FolLiteral {
+    pub fn not(&self) -> Self {
+        Self {
+            polarity: match self.polarity {
+                Polarity::Positive => Polarity::Negative,
+                Polarity::Negative => Polarity::Positive,
+            },
+            predicate: self.predicate.clone(),
+            arguments: self.arguments.clone(),
+        }
+    }
+
+    pub fn substitute(&self, substitution: &Substitution) -> Self {
+        Self {
+            polarity: self.polarity.clone(),
+            predicate: self.predicate.clone(),
+            arguments: self
+                .arguments
+                .iter()
+                .map(|argument| argument.substitute(substitution))
+                .collect(),
+        }
+    }
+}
+
+pub type FolClause = Vec<FolLiteral>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantifier {
+    ForAll,
+    Exists,
+}
+
+fn term_symbols(term: &Term, symbols: &mut BTreeSet<String>) {
+    match &**term {
+        TermNode::Variable(_) => (),
+        TermNode::Function(name, arguments) => {
+            symbols.insert(name.clone());
+            for argument in arguments {
+                term_symbols(argument, symbols);
+            }
+        }
+    }
+}
+
+fn formula_symbols(formula: &Formula, symbols: &mut BTreeSet<String>) {
+    match &**formula {
+        FormulaNode::Predicate(name, arguments) => {
+            symbols.insert(name.clone());
+            for argument in arguments {
+                term_symbols(argument, symbols);
+            }
+        }
+        FormulaNode::Not(p) => formula_symbols(p, symbols),
+        FormulaNode::And(p1, p2) | FormulaNode::Or(p1, p2) => {
+            formula_symbols(p1, symbols);
+            formula_symbols(p2, symbols);
+        }
+        FormulaNode::ForAll(_, p) | FormulaNode::Exists(_, p) => formula_symbols(p, symbols),
+    }
+}
+
+fn negation_normal_form(formula: &Formula, negated: bool) -> Formula {
+    match (&**formula, negated) {
+        (FormulaNode::Predicate(..), false) => formula.clone(),
+        (FormulaNode::Predicate(..), true) => formula.not(),
+        (FormulaNode::Not(p), _) => negation_normal_form(p, !negated),
+        (FormulaNode::And(p1, p2), false) => {
+            negation_normal_form(p1, false).and(negation_normal_form(p2, false))
+        }
+        (FormulaNode::And(p1, p2), true) => {
+            negation_normal_form(p1, true).or(negation_normal_form(p2, true))
+        }
+        (FormulaNode::Or(p1, p2), false) => {
+            negation_normal_form(p1, false).or(negation_normal_form(p2, false))
+        }
+        (FormulaNode::Or(p1, p2), true) => {
+            negation_normal_form(p1, true).and(negation_normal_form(p2, true))
+        }
+        (FormulaNode::ForAll(variable, p), false) | (FormulaNode::Exists(variable, p), true) => {
+            negation_normal_form(p, negated).forall(variable)
+        }
+        (FormulaNode::Exists(variable, p), false) | (FormulaNode::ForAll(variable, p), true) => {
+            negation_normal_form(p, negated).exists(variable)
+        }
+    }
+}
+
+fn standardize_apart(formula: &Formula, used: &mut BTreeSet<String>) -> Formula {
+    match &**formula {
+        FormulaNode::Predicate(..) => formula.clone(),
+        FormulaNode::Not(p) => standardize_apart(p, used).not(),
+        FormulaNode::And(p1, p2) => standardize_apart(p1, used).and(standardize_apart(p2, used)),
+        FormulaNode::Or(p1, p2) => standardize_apart(p1, used).or(standardize_apart(p2, used)),
+        FormulaNode::ForAll(name, p) | FormulaNode::Exists(name, p) => {
+            let renamed = match used.contains(name) {
+                true => fresh_variable(name, used),
+                false => name.clone(),
+            };
+            used.insert(renamed.clone());
+            let body = standardize_apart(&p.substitute_variable(name, variable(&renamed)), used);
+            match &**formula {
+                FormulaNode::ForAll(..) => body.forall(&renamed),
+                _ => body.exists(&renamed),
+            }
+        }
+    }
+}
+
+fn strip_quantifiers(formula: &Formula, prefix: &mut Vec<(Quantifier, String)>) -> Formula {
+    match &**formula {
+        FormulaNode::Predicate(..) | FormulaNode::Not(_) => formula.clone(),
+        FormulaNode::And(p1, p2) => {
+            strip_quantifiers(p1, prefix).and(strip_quantifiers(p2, prefix))
+        }
+        FormulaNode::Or(p1, p2) => strip_quantifiers(p1, prefix).or(strip_quantifiers(p2, prefix)),
+        FormulaNode::ForAll(variable, p) => {
+            prefix.push((Quantifier::ForAll, variable.clone()));
+            strip_quantifiers(p, prefix)
+        }
+        FormulaNode::Exists(variable, p) => {
+            prefix.push((Quantifier::Exists, variable.clone()));
+            strip_quantifiers(p, prefix)
+        }
+    }
+}
+
+fn quantify(prefix: &[(Quantifier, String)], matrix: Formula) -> Formula {
+    prefix
+        .iter()
+        .rev()
+        .fold(matrix, |body, (quantifier, variable)| match quantifier {
+            Quantifier::ForAll => body.forall(variable),
+            Quantifier::Exists => body.exists(variable),
+        })
+}
+
+fn matrix_clauses(matrix: &Formula) -> Vec<FolClause> {
+    match &**matrix {
+        FormulaNode::Predicate(name, arguments) => vec![vec![FolLiteral {
+            polarity: Polarity::Positive,
+            predicate: name.clone(),
+            arguments: arguments.clone(),
+        }]],
+        FormulaNode::Not(p) => matrix_clauses(p)
+            .into_iter()
+            .flatten()
+            .map(|literal| vec![literal.not()])
+            .collect(),
+        FormulaNode::And(p1, p2) => {
+            let mut clauses = matrix_clauses(p1);
+            clauses.extend(matrix_clauses(p2));
+            clauses
+        }
+        FormulaNode::Or(p1, p2) => {
+            let rhs = matrix_clauses(p2);
+            matrix_clauses(p1)
+                .into_iter()
+                .flat_map(|left| {
+                    rhs.iter().map(move |right| {
+                        let mut clause = left.clone();
+                        for literal in right {
+                            if !clause.contains(literal) {
+                                clause.push(literal.clone());
+                            }
+                        }
+                        clause
+                    })
+                })
+                .collect()
+        }
+        FormulaNode::ForAll(..) | FormulaNode::Exists(..) => unreachable!(),
+    }
+}
+
+pub trait NormalForms {
+    fn nnf(&self) -> Formula;
+    fn prenex(&self) -> (Vec<(Quantifier, String)>, Formula);
+    fn prenex_normal_form(&self) -> Formula;
+    fn skolemize(&self) -> Formula;
+    fn clause_form(&self) -> Vec<FolClause>;
+}
+
+impl NormalForms for Formula {
+    fn nnf(&self) -> Formula {
+        negation_normal_form(self, false)
+    }
+
+    fn prenex(&self) -> (Vec<(Quantifier, String)>, Formula) {
+        let mut used = self.free_variables();
+        formula_symbols(self, &mut used);
+        let standardized = standardize_apart(&self.nnf(), &mut used);
+        let mut prefix = vec![];
+        let matrix = strip_quantifiers(&standardized, &mut prefix);
+        (prefix, matrix)
+    }
+
+    fn prenex_normal_form(&self) -> Formula {
+        let (prefix, matrix) = self.prenex();
+        quantify(&prefix, matrix)
+    }
+
+    fn skolemize(&self) -> Formula {
+        let mut symbols = BTreeSet::new();
+        formula_symbols(self, &mut symbols);
+        let (prefix, matrix) = self.prenex();
+        let mut universals = vec![];
+        let mut substitution = Substitution::new();
+        for (quantifier, name) in prefix {
+            match quantifier {
+                Quantifier::ForAll => universals.push(name),
+                Quantifier::Exists => {
+                    let skolem = fresh_variable("sk", &symbols);
+                    symbols.insert(skolem.clone());
+                    let arguments = universals.iter().map(|name| variable(name)).collect();
+                    substitution.insert(name, function(&skolem, arguments));
+                }
+            }
+        }
+        let prefix = universals
+            .into_iter()
+            .map(|name| (Quantifier::ForAll, name))
+            .collect::<Vec<_>>();
+        quantify(&prefix, matrix.substitute(&substitution))
+    }
+
+    fn clause_form(&self) -> Vec<FolClause> {
+        let mut prefix = vec![];
+        let matrix = strip_quantifiers(&self.skolemize(), &mut prefix);
+        matrix_clauses(&matrix)
+    }
+}
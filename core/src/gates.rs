@@ -0,0 +1,340 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident},
+    sat::{Clause, Literal},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Gate {
+    And {
+        output: Literal,
+        inputs: Vec<Literal>,
+    },
+    Xor {
+        output: Literal,
+        inputs: [Literal; 2],
+    },
+    Ite {
+        output: Literal,
+        condition: Literal,
+        then: Literal,
+        otherwise: Literal,
+    },
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gate::And { output, inputs } => write!(
+                f,
+                "{} ↔ ({})",
+                output,
+                inputs
+                    .iter()
+                    .map(Literal::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ∧ ")
+            ),
+            Gate::Xor { output, inputs } => {
+                write!(f, "{} ↔ ({} ⊕ {})", output, inputs[0], inputs[1])
+            }
+            Gate::Ite {
+                output,
+                condition,
+                then,
+                otherwise,
+            } => write!(f, "{} ↔ ({} ? {} : {})", output, condition, then, otherwise),
+        }
+    }
+}
+
+impl Gate {
+    pub fn output(&self) -> &Literal {
+        match self {
+            Gate::And { output, .. } | Gate::Xor { output, .. } | Gate::Ite { output, .. } => {
+                output
+            }
+        }
+    }
+
+    pub fn inputs(&self) -> Vec<&Literal> {
+        match self {
+            Gate::And { inputs, .. } => inputs.iter().collect(),
+            Gate::Xor { inputs, .. } => inputs.iter().collect(),
+            Gate::Ite {
+                condition,
+                then,
+                otherwise,
+                ..
+            } => vec![condition, then, otherwise],
+        }
+    }
+
+    pub fn clauses(&self) -> Vec<Clause> {
+        match self {
+            Gate::And { output, inputs } => inputs
+                .iter()
+                .map(|input| Clause::new([output.not(), input.clone()]))
+                .chain([Clause::new(
+                    inputs.iter().map(Literal::not).chain([output.clone()]),
+                )])
+                .collect(),
+            Gate::Xor {
+                output,
+                inputs: [first, second],
+            } => vec![
+                Clause::new([output.not(), first.clone(), second.clone()]),
+                Clause::new([output.not(), first.not(), second.not()]),
+                Clause::new([output.clone(), first.not(), second.clone()]),
+                Clause::new([output.clone(), first.clone(), second.not()]),
+            ],
+            Gate::Ite {
+                output,
+                condition,
+                then,
+                otherwise,
+            } => vec![
+                Clause::new([condition.not(), then.not(), output.clone()]),
+                Clause::new([condition.not(), then.clone(), output.not()]),
+                Clause::new([condition.clone(), otherwise.not(), output.clone()]),
+                Clause::new([condition.clone(), otherwise.clone(), output.not()]),
+            ],
+        }
+    }
+
+    fn to_ast<T: AbstractSyntaxTree>(
+        &self,
+        positive: bool,
+        literal: &mut impl FnMut(&Literal) -> T,
+    ) -> T {
+        let sign = |literal: &Literal| match positive {
+            true => literal.clone(),
+            false => literal.not(),
+        };
+        match self {
+            Gate::And { inputs, .. } => {
+                let inputs = inputs.iter().map(|input| literal(&sign(input)));
+                match positive {
+                    true => inputs.reduce(|lhs, rhs| lhs.and(rhs)).unwrap(),
+                    false => inputs.reduce(|lhs, rhs| lhs.or(rhs)).unwrap(),
+                }
+            }
+            Gate::Xor {
+                inputs: [first, second],
+                ..
+            } => literal(first).ite(literal(&sign(&second.not())), literal(&sign(second))),
+            Gate::Ite {
+                condition,
+                then,
+                otherwise,
+                ..
+            } => literal(condition).ite(literal(&sign(then)), literal(&sign(otherwise))),
+        }
+    }
+}
+
+pub fn candidate_gates(clauses: &[Clause]) -> Vec<Gate> {
+    let present = clauses.iter().collect::<HashSet<_>>();
+    let mut occurrences = HashMap::<&Literal, Vec<&Clause>>::new();
+    for clause in clauses.iter().filter(|clause| clause.len() == 3) {
+        for literal in clause.literals() {
+            occurrences.entry(literal).or_default().push(clause);
+        }
+    }
+    let mut candidates = vec![];
+    for clause in clauses.iter().filter(|clause| clause.len() >= 2) {
+        for output in clause.literals() {
+            let inputs = clause
+                .literals()
+                .filter(|literal| *literal != output)
+                .map(Literal::not)
+                .collect::<Vec<_>>();
+            candidates.push(Gate::And {
+                output: output.clone(),
+                inputs,
+            });
+        }
+    }
+    let mut ternary = HashMap::<BTreeSet<Ident>, Vec<&Clause>>::new();
+    for clause in clauses.iter().filter(|clause| clause.len() == 3) {
+        let variables = clause
+            .literals()
+            .map(Literal::identifier)
+            .collect::<BTreeSet<_>>();
+        if variables.len() == 3 {
+            ternary.entry(variables).or_default().push(clause);
+        }
+        for output in clause.literals() {
+            for condition in clause.literals().filter(|literal| *literal != output) {
+                let then = clause
+                    .literals()
+                    .find(|literal| *literal != output && *literal != condition)
+                    .unwrap()
+                    .not();
+                let negated = condition.not();
+                for other in occurrences
+                    .get(output)
+                    .into_iter()
+                    .flatten()
+                    .filter(|other| other.contains(&negated))
+                {
+                    let otherwise = other
+                        .literals()
+                        .find(|literal| *literal != output && **literal != negated)
+                        .unwrap()
+                        .not();
+                    candidates.push(Gate::Ite {
+                        output: output.clone(),
+                        condition: negated.clone(),
+                        then: then.clone(),
+                        otherwise,
+                    });
+                }
+            }
+        }
+    }
+    for (variables, _) in ternary.into_iter().filter(|(_, group)| group.len() >= 4) {
+        let variables = variables.into_iter().collect::<Vec<_>>();
+        for output in 0..3 {
+            let inputs = variables
+                .iter()
+                .enumerate()
+                .filter(|(position, _)| *position != output)
+                .map(|(_, ident)| Literal::positive(*ident))
+                .collect::<Vec<_>>();
+            for output in [
+                Literal::positive(variables[output]),
+                Literal::negative(variables[output]),
+            ] {
+                candidates.push(Gate::Xor {
+                    output,
+                    inputs: [inputs[0].clone(), inputs[1].clone()],
+                });
+            }
+        }
+    }
+    candidates.retain(|gate| {
+        let output = gate.output().identifier();
+        gate.inputs()
+            .iter()
+            .all(|input| input.identifier() != output)
+            && gate.clauses().iter().all(|clause| present.contains(clause))
+    });
+    candidates
+}
+
+pub fn detect_gates(clauses: &[Clause]) -> Vec<Gate> {
+    let mut candidates = candidate_gates(clauses);
+    candidates.sort_by_key(|gate| {
+        let kind = match gate {
+            Gate::Xor { .. } => 0,
+            Gate::Ite { .. } => 1,
+            Gate::And { .. } => 2,
+        };
+        (std::cmp::Reverse(gate.output().identifier()), kind)
+    });
+    let mut available = HashMap::<&Clause, usize>::new();
+    for clause in clauses {
+        *available.entry(clause).or_insert(0) += 1;
+    }
+    let mut definitions = BTreeMap::<Ident, Vec<Ident>>::new();
+    let mut gates = vec![];
+    for gate in candidates {
+        let output = gate.output().identifier();
+        if definitions.contains_key(&output) {
+            continue;
+        }
+        let inputs = gate
+            .inputs()
+            .iter()
+            .map(|input| input.identifier())
+            .collect::<Vec<_>>();
+        let mut stack = inputs.clone();
+        let mut visited = BTreeSet::new();
+        let mut cyclic = false;
+        while let Some(ident) = stack.pop() {
+            if ident == output {
+                cyclic = true;
+                break;
+            }
+            if visited.insert(ident) {
+                stack.extend(definitions.get(&ident).into_iter().flatten());
+            }
+        }
+        let needed = gate.clauses();
+        if cyclic
+            || !needed
+                .iter()
+                .all(|clause| available.get(clause).is_some_and(|count| *count > 0))
+        {
+            continue;
+        }
+        for clause in &needed {
+            *available.get_mut(clause).unwrap() -= 1;
+        }
+        definitions.insert(output, inputs);
+        gates.push(gate);
+    }
+    gates
+}
+
+pub(crate) fn substitute<T: AbstractSyntaxTree>(gates: &[Gate], clauses: &[Clause]) -> Vec<T> {
+    let defined = gates
+        .iter()
+        .map(|gate| (gate.output().identifier(), gate))
+        .collect::<HashMap<_, _>>();
+    let mut memo = HashMap::new();
+    let mut remaining = HashMap::<&Clause, usize>::new();
+    for clause in clauses {
+        *remaining.entry(clause).or_insert(0) += 1;
+    }
+    for clause in gates.iter().flat_map(Gate::clauses) {
+        if let Some(count) = remaining.get_mut(&clause) {
+            *count -= 1;
+        }
+    }
+    clauses
+        .iter()
+        .filter(|clause| {
+            let count = remaining.get_mut(clause).unwrap();
+            let keep = *count > 0;
+            *count = count.saturating_sub(1);
+            keep
+        })
+        .map(|clause| {
+            clause
+                .literals()
+                .map(|literal| expand::<T>(literal, &defined, &mut memo))
+                .reduce(|lhs, rhs| lhs.or(rhs))
+                .unwrap_or_else(|| {
+                    let var = T::variable(0);
+                    var.and(var.not())
+                })
+        })
+        .collect()
+}
+
+fn expand<T: AbstractSyntaxTree>(
+    literal: &Literal,
+    defined: &HashMap<Ident, &Gate>,
+    memo: &mut HashMap<Literal, T>,
+) -> T {
+    if let Some(formula) = memo.get(literal) {
+        return formula.clone();
+    }
+    let formula = match defined.get(&literal.identifier()) {
+        Some(gate) => {
+            let positive = literal == gate.output();
+            gate.to_ast(positive, &mut |input: &Literal| {
+                expand(input, defined, memo)
+            })
+        }
+        None => literal.to_ast::<T>(),
+    };
+    memo.insert(literal.clone(), formula.clone());
+    formula
+}
@@ -0,0 +1,329 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use crate::{
+    ast::{ASTNode, AST},
+    schema::{implies, FormulaSchema},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HilbertError {
+    EmptyProof,
+    NotAnAxiom { step: usize, axiom: HilbertAxiom },
+    UnknownHypothesis { step: usize },
+    InvalidReference { step: usize, reference: usize },
+    InvalidModusPonens { step: usize },
+}
+
+impl fmt::Display for HilbertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HilbertError::EmptyProof => write!(f, "proof has no steps"),
+            HilbertError::NotAnAxiom { step, axiom } => {
+                write!(f, "step {} is not an instance of axiom {}", step, axiom)
+            }
+            HilbertError::UnknownHypothesis { step } => {
+                write!(f, "step {} cites a formula that is not a hypothesis", step)
+            }
+            HilbertError::InvalidReference { step, reference } => write!(
+                f,
+                "step {} refers to step {} which does not precede it",
+                step, reference
+            ),
+            HilbertError::InvalidModusPonens { step } => {
+                write!(f, "step {} does not follow by modus ponens", step)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HilbertError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HilbertAxiom {
+    K,
+    S,
+    Contraposition,
+}
+
+impl fmt::Display for HilbertAxiom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HilbertAxiom::K => write!(f, "K"),
+            HilbertAxiom::S => write!(f, "S"),
+            HilbertAxiom::Contraposition => write!(f, "contraposition"),
+        }
+    }
+}
+
+impl HilbertAxiom {
+    pub fn schema(&self) -> FormulaSchema {
+        match self {
+            HilbertAxiom::K => FormulaSchema::k(),
+            HilbertAxiom::S => FormulaSchema::s(),
+            HilbertAxiom::Contraposition => FormulaSchema::contraposition(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HilbertStep {
+    Axiom(HilbertAxiom, AST),
+    Hypothesis(AST),
+    ModusPonens {
+        premise: usize,
+        implication: usize,
+        formula: AST,
+    },
+}
+
+impl HilbertStep {
+    pub fn formula(&self) -> &AST {
+        match self {
+            HilbertStep::Axiom(_, formula)
+            | HilbertStep::Hypothesis(formula)
+            | HilbertStep::ModusPonens { formula, .. } => formula,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HilbertProof {
+    pub hypotheses: Vec<AST>,
+    pub steps: Vec<HilbertStep>,
+}
+
+impl fmt::Display for HilbertProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            match step {
+                HilbertStep::Axiom(axiom, formula) => {
+                    writeln!(f, "{}: {} (axiom {})", index, formula, axiom)?
+                }
+                HilbertStep::Hypothesis(formula) => {
+                    writeln!(f, "{}: {} (hypothesis)", index, formula)?
+                }
+                HilbertStep::ModusPonens {
+                    premise,
+                    implication,
+                    formula,
+                } => writeln!(
+                    f,
+                    "{}: {} (modus ponens from {} and {})",
+                    index, formula, premise, implication
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl HilbertProof {
+    pub fn conclusion(&self) -> Option<&AST> {
+        self.steps.last().map(HilbertStep::formula)
+    }
+
+    pub fn check(&self) -> Result<(), HilbertError> {
+        if self.steps.is_empty() {
+            return Err(HilbertError::EmptyProof);
+        }
+        for (step, entry) in self.steps.iter().enumerate() {
+            match entry {
+                HilbertStep::Axiom(axiom, formula) => {
+                    if axiom.schema().matches(formula).is_none() {
+                        return Err(HilbertError::NotAnAxiom {
+                            step,
+                            axiom: *axiom,
+                        });
+                    }
+                }
+                HilbertStep::Hypothesis(formula) => {
+                    if !self.hypotheses.contains(formula) {
+                        return Err(HilbertError::UnknownHypothesis { step });
+                    }
+                }
+                HilbertStep::ModusPonens {
+                    premise,
+                    implication,
+                    formula,
+                } => {
+                    if let Some(reference) = [*premise, *implication]
+                        .into_iter()
+                        .find(|reference| *reference >= step)
+                    {
+                        return Err(HilbertError::InvalidReference { step, reference });
+                    }
+                    let expected = implies(self.steps[*premise].formula(), formula.clone());
+                    if *self.steps[*implication].formula() != expected {
+                        return Err(HilbertError::InvalidModusPonens { step });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Derivation {
+    Axiom(HilbertAxiom),
+    Hypothesis,
+    ModusPonens(AST, AST),
+}
+
+pub fn prove(goal: &AST, hypotheses: &[AST], limit: usize) -> Option<HilbertProof> {
+    let mut terms = vec![];
+    let mut seen = HashSet::new();
+    for formula in hypotheses.iter().chain([goal]) {
+        subformulas(formula, &mut seen, &mut terms);
+    }
+    let mut search = Search {
+        derived: HashMap::new(),
+        waiting: HashMap::new(),
+        limit,
+    };
+    for hypothesis in hypotheses {
+        search.derive(hypothesis.clone(), Derivation::Hypothesis);
+    }
+    let instances = [
+        HilbertAxiom::K,
+        HilbertAxiom::Contraposition,
+        HilbertAxiom::S,
+    ]
+    .into_iter()
+    .flat_map(|axiom| {
+        let schema = axiom.schema();
+        tuples(&terms, schema.arity())
+            .map(move |arguments| (axiom, schema.instantiate(&arguments).unwrap()))
+    });
+    for (axiom, instance) in instances {
+        if search.derived.contains_key(goal) || search.derived.len() >= limit {
+            break;
+        }
+        search.derive(instance, Derivation::Axiom(axiom));
+    }
+    search.derived.contains_key(goal).then(|| HilbertProof {
+        hypotheses: hypotheses.to_vec(),
+        steps: search.extract(goal),
+    })
+}
+
+struct Search {
+    derived: HashMap<AST, Derivation>,
+    waiting: HashMap<AST, Vec<AST>>,
+    limit: usize,
+}
+
+impl Search {
+    fn derive(&mut self, formula: AST, derivation: Derivation) {
+        let mut queue = VecDeque::from([(formula, derivation)]);
+        while let Some((formula, derivation)) = queue.pop_front() {
+            if self.derived.contains_key(&formula) || self.derived.len() >= self.limit {
+                continue;
+            }
+            self.derived.insert(formula.clone(), derivation);
+            if let Some((antecedent, consequent)) = implication(&formula) {
+                match self.derived.contains_key(&antecedent) {
+                    true => queue.push_back((
+                        consequent,
+                        Derivation::ModusPonens(antecedent, formula.clone()),
+                    )),
+                    false => self
+                        .waiting
+                        .entry(antecedent)
+                        .or_default()
+                        .push(formula.clone()),
+                }
+            }
+            for waiting in self.waiting.remove(&formula).unwrap_or_default() {
+                let (_, consequent) = implication(&waiting).unwrap();
+                queue.push_back((
+                    consequent,
+                    Derivation::ModusPonens(formula.clone(), waiting),
+                ));
+            }
+        }
+    }
+
+    fn extract(&self, goal: &AST) -> Vec<HilbertStep> {
+        let mut steps = vec![];
+        let mut indices = HashMap::new();
+        let mut stack = vec![(goal.clone(), false)];
+        while let Some((formula, expanded)) = stack.pop() {
+            if indices.contains_key(&formula) {
+                continue;
+            }
+            let step = match (&self.derived[&formula], expanded) {
+                (Derivation::ModusPonens(premise, implication), false) => {
+                    stack.push((formula.clone(), true));
+                    stack.push((implication.clone(), false));
+                    stack.push((premise.clone(), false));
+                    continue;
+                }
+                (Derivation::ModusPonens(premise, implication), true) => HilbertStep::ModusPonens {
+                    premise: indices[premise],
+                    implication: indices[implication],
+                    formula: formula.clone(),
+                },
+                (Derivation::Axiom(axiom), _) => HilbertStep::Axiom(*axiom, formula.clone()),
+                (Derivation::Hypothesis, _) => HilbertStep::Hypothesis(formula.clone()),
+            };
+            indices.insert(formula, steps.len());
+            steps.push(step);
+        }
+        steps
+    }
+}
+
+fn implication(formula: &AST) -> Option<(AST, AST)> {
+    match &**formula {
+        ASTNode::Or(p1, p2) => match &**p1 {
+            ASTNode::Not(antecedent) => Some((antecedent.clone(), p2.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn subformulas(formula: &AST, seen: &mut HashSet<AST>, terms: &mut Vec<AST>) {
+    if !seen.insert(formula.clone()) {
+        return;
+    }
+    match &**formula {
+        ASTNode::Variable(_) => (),
+        ASTNode::Not(p) => subformulas(p, seen, terms),
+        ASTNode::And(p1, p2)
+        | ASTNode::Or(p1, p2)
+        | ASTNode::Nand(p1, p2)
+        | ASTNode::Nor(p1, p2) => {
+            subformulas(p1, seen, terms);
+            subformulas(p2, seen, terms);
+        }
+        ASTNode::Ite(p1, p2, p3) => {
+            subformulas(p1, seen, terms);
+            subformulas(p2, seen, terms);
+            subformulas(p3, seen, terms);
+        }
+    }
+    terms.push(formula.clone());
+}
+
+fn tuples(terms: &[AST], arity: usize) -> impl Iterator<Item = Vec<AST>> + '_ {
+    let count = u32::try_from(arity)
+        .ok()
+        .and_then(|arity| terms.len().checked_pow(arity))
+        .unwrap_or(usize::MAX);
+    (0..count).map(move |index| {
+        let mut tuple = (0..arity)
+            .scan(index, |rest, _| {
+                let term = terms[*rest % terms.len()].clone();
+                *rest /= terms.len();
+                Some(term)
+            })
+            .collect::<Vec<_>>();
+        tuple.reverse();
+        tuple
+    })
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::sat::{Clause, Literal};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImplicationGraph {
+    edges: BTreeMap<Literal, BTreeSet<Literal>>,
+}
+
+impl fmt::Display for ImplicationGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let edges = self
+            .edges
+            .iter()
+            .flat_map(|(from, targets)| targets.iter().map(move |to| format!("{} → {}", from, to)))
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", edges.join(", "))
+    }
+}
+
+impl From<&[Clause]> for ImplicationGraph {
+    fn from(clauses: &[Clause]) -> Self {
+        let mut graph = Self::default();
+        for clause in clauses {
+            graph.add_clause(clause);
+        }
+        graph
+    }
+}
+
+impl ImplicationGraph {
+    pub fn add_clause(&mut self, clause: &Clause) -> bool {
+        let Some((first, second)) = binary(clause) else {
+            return false;
+        };
+        let added = self
+            .edges
+            .entry(first.not())
+            .or_default()
+            .insert(second.clone());
+        self.edges.entry(second.not()).or_default().insert(first);
+        added
+    }
+
+    pub fn remove_clause(&mut self, clause: &Clause) -> bool {
+        let Some((first, second)) = binary(clause) else {
+            return false;
+        };
+        let mut remove = |from: Literal, to: &Literal| {
+            let removed = self
+                .edges
+                .get_mut(&from)
+                .is_some_and(|targets| targets.remove(to));
+            if self.edges.get(&from).is_some_and(BTreeSet::is_empty) {
+                self.edges.remove(&from);
+            }
+            removed
+        };
+        let removed = remove(first.not(), &second);
+        remove(second.not(), &first);
+        removed
+    }
+
+    pub fn contains(&self, clause: &Clause) -> bool {
+        binary(clause)
+            .is_some_and(|(first, second)| self.implications(&first.not()).any(|to| *to == second))
+    }
+
+    pub fn literals(&self) -> BTreeSet<Literal> {
+        self.edges
+            .iter()
+            .flat_map(|(from, targets)| std::iter::once(from).chain(targets))
+            .cloned()
+            .collect()
+    }
+
+    pub fn implications(&self, literal: &Literal) -> impl Iterator<Item = &Literal> {
+        self.edges.get(literal).into_iter().flatten()
+    }
+
+    pub fn edges(&self) -> usize {
+        self.edges.values().map(BTreeSet::len).sum()
+    }
+
+    pub fn clauses(&self) -> Vec<Clause> {
+        self.edges
+            .iter()
+            .flat_map(|(from, targets)| {
+                targets
+                    .iter()
+                    .filter(|to| from.not() < **to)
+                    .map(|to| Clause::new([from.not(), to.clone()]))
+            })
+            .collect()
+    }
+
+    pub fn reachable(&self, literal: &Literal) -> BTreeSet<Literal> {
+        let mut reached = BTreeSet::new();
+        let mut stack = vec![literal];
+        while let Some(current) = stack.pop() {
+            for next in self.implications(current) {
+                if reached.insert(next.clone()) {
+                    stack.push(next);
+                }
+            }
+        }
+        reached
+    }
+
+    pub fn implies(&self, from: &Literal, to: &Literal) -> bool {
+        from == to || self.reachable(from).contains(to)
+    }
+
+    pub fn failed_literals(&self) -> Vec<Literal> {
+        self.literals()
+            .into_iter()
+            .filter(|literal| self.reachable(literal).contains(&literal.not()))
+            .collect()
+    }
+
+    pub fn components(&self) -> Vec<Vec<Literal>> {
+        let literals = self.literals().into_iter().collect::<Vec<_>>();
+        let index = literals
+            .iter()
+            .enumerate()
+            .map(|(position, literal)| (literal, position))
+            .collect::<BTreeMap<_, _>>();
+        let edges = literals
+            .iter()
+            .map(|literal| self.implications(literal).map(|to| index[to]).collect())
+            .collect::<Vec<Vec<_>>>();
+        let mut components = BTreeMap::<usize, Vec<Literal>>::new();
+        for (position, component) in strongly_connected_components(&edges)
+            .into_iter()
+            .enumerate()
+        {
+            components
+                .entry(component)
+                .or_default()
+                .push(literals[position].clone());
+        }
+        components.into_values().collect()
+    }
+
+    pub fn equivalence_classes(&self) -> Vec<Vec<Literal>> {
+        self.components()
+            .into_iter()
+            .filter(|class| class.len() > 1 && class[0].is_positive())
+            .collect()
+    }
+
+    pub fn representatives(&self) -> Option<BTreeMap<Literal, Literal>> {
+        let mut representatives = BTreeMap::new();
+        for class in self
+            .components()
+            .into_iter()
+            .filter(|class| class.len() > 1)
+        {
+            if class
+                .windows(2)
+                .any(|pair| pair[0].identifier() == pair[1].identifier())
+            {
+                return None;
+            }
+            for literal in &class[1..] {
+                representatives.insert(literal.clone(), class[0].clone());
+            }
+        }
+        Some(representatives)
+    }
+
+    pub fn transitive_reduction(&self) -> Self {
+        let mut reduced = self.clone();
+        for clause in self.clauses() {
+            reduced.remove_clause(&clause);
+            let (first, second) = binary(&clause).unwrap();
+            if !reduced.implies(&first.not(), &second) {
+                reduced.add_clause(&clause);
+            }
+        }
+        reduced
+    }
+
+    pub fn strengthen(&self, clause: &Clause) -> Clause {
+        let mut literals = clause.literals().cloned().collect::<Vec<_>>();
+        let mut position = 0;
+        while position < literals.len() {
+            let reached = self.reachable(&literals[position]);
+            let implied = literals
+                .iter()
+                .enumerate()
+                .any(|(other, literal)| other != position && reached.contains(literal));
+            match implied {
+                true => {
+                    literals.remove(position);
+                }
+                false => position += 1,
+            }
+        }
+        Clause::new(literals)
+    }
+
+    pub fn is_hidden_tautology(&self, clause: &Clause) -> bool {
+        clause.literals().any(|literal| {
+            let reached = self.reachable(&literal.not());
+            clause
+                .literals()
+                .any(|other| other != literal && reached.contains(other))
+        })
+    }
+}
+
+fn binary(clause: &Clause) -> Option<(Literal, Literal)> {
+    let mut literals = clause.literals().cloned();
+    match (literals.next(), literals.next(), literals.next()) {
+        (Some(first), Some(second), None) if first.identifier() != second.identifier() => {
+            Some((first, second))
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<usize> {
+    let count = edges.len();
+    let mut index = vec![usize::MAX; count];
+    let mut lowlink = vec![0; count];
+    let mut on_stack = vec![false; count];
+    let mut component = vec![usize::MAX; count];
+    let mut stack = vec![];
+    let mut visited = 0;
+    let mut components = 0;
+    for root in 0..count {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        let mut work = vec![(root, 0)];
+        index[root] = visited;
+        lowlink[root] = visited;
+        visited += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some((vertex, edge)) = work.last_mut() {
+            let vertex = *vertex;
+            if let Some(&child) = edges[vertex].get(*edge) {
+                *edge += 1;
+                if index[child] == usize::MAX {
+                    index[child] = visited;
+                    lowlink[child] = visited;
+                    visited += 1;
+                    stack.push(child);
+                    on_stack[child] = true;
+                    work.push((child, 0));
+                } else if on_stack[child] {
+                    lowlink[vertex] = lowlink[vertex].min(index[child]);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some((parent, _)) = work.last() {
+                lowlink[*parent] = lowlink[*parent].min(lowlink[vertex]);
+            }
+            if lowlink[vertex] == index[vertex] {
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component[member] = components;
+                    if member == vertex {
+                        break;
+                    }
+                }
+                components += 1;
+            }
+        }
+    }
+    component
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use crate::{
+    ast::AbstractSyntaxTree,
+    bmc::{Bmc, Trace},
+    transition::TransitionSystem,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InductionResult {
+    Safe { depth: usize },
+    Unsafe(Trace),
+    Unknown { bound: usize },
+}
+
+impl fmt::Display for InductionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InductionResult::Safe { depth } => write!(f, "safe ({}-inductive)", depth),
+            InductionResult::Unsafe(trace) => write!(f, "unsafe:\n{}", trace),
+            InductionResult::Unknown { bound } => write!(f, "unknown up to depth {}", bound),
+        }
+    }
+}
+
+pub fn k_induction(system: &TransitionSystem, max_depth: usize) -> InductionResult {
+    let mut base = Bmc::new(system);
+    let mut step = Bmc::unconstrained(system);
+    for depth in 0..=max_depth {
+        if let Some(trace) = base.check_step(depth) {
+            return InductionResult::Unsafe(trace);
+        }
+        step.assert_at(&system.bad.not(), depth);
+        step.unroll(depth + 1);
+        for earlier in 0..=depth {
+            step.assert_distinct(earlier, depth + 1);
+        }
+        if !step.reaches_bad(depth + 1) {
+            return InductionResult::Safe { depth: depth + 1 };
+        }
+    }
+    InductionResult::Unknown { bound: max_depth }
+}
@@ -0,0 +1,245 @@
+use std::fmt;
+
+use crate::{
+    ast::{ASTNode, AbstractSyntaxTree, AST},
+    sequent::{self, Sequent},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofStateError {
+    NoGoals,
+    NoSuchHypothesis { index: usize },
+    NotApplicable { rule: Rule, goal: Sequent },
+}
+
+impl fmt::Display for ProofStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofStateError::NoGoals => write!(f, "no goals remain"),
+            ProofStateError::NoSuchHypothesis { index } => {
+                write!(f, "the current goal has no hypothesis {}", index)
+            }
+            ProofStateError::NotApplicable { rule, goal } => {
+                write!(f, "{} does not apply to {}", rule, goal)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProofStateError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Intro,
+    Split,
+    Left,
+    Right,
+    ByContradiction,
+    Assumption,
+    Contradiction,
+    Destruct(usize),
+    Cases(usize),
+    Apply(usize),
+    Auto,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Intro => write!(f, "intro"),
+            Rule::Split => write!(f, "split"),
+            Rule::Left => write!(f, "left"),
+            Rule::Right => write!(f, "right"),
+            Rule::ByContradiction => write!(f, "by_contradiction"),
+            Rule::Assumption => write!(f, "assumption"),
+            Rule::Contradiction => write!(f, "contradiction"),
+            Rule::Destruct(index) => write!(f, "destruct {}", index),
+            Rule::Cases(index) => write!(f, "cases {}", index),
+            Rule::Apply(index) => write!(f, "apply {}", index),
+            Rule::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProofState {
+    goals: Vec<Sequent>,
+    history: Vec<(Rule, Vec<Sequent>)>,
+}
+
+impl fmt::Display for ProofState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.goals.len() {
+            0 => write!(f, "no goals"),
+            count => {
+                write!(f, "{} goal{}", count, if count == 1 { "" } else { "s" })?;
+                for (index, goal) in self.goals.iter().enumerate() {
+                    write!(f, "\n  {}: {}", index, goal)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ProofState {
+    pub fn new(goal: &AST) -> Self {
+        Self::from_sequent(Sequent::from(goal))
+    }
+
+    pub fn from_sequent(goal: Sequent) -> Self {
+        Self {
+            goals: vec![goal],
+            history: vec![],
+        }
+    }
+
+    pub fn goals(&self) -> &[Sequent] {
+        &self.goals
+    }
+
+    pub fn current(&self) -> Option<&Sequent> {
+        self.goals.first()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.goals.is_empty()
+    }
+
+    pub fn history(&self) -> impl Iterator<Item = Rule> + '_ {
+        self.history.iter().map(|(rule, _)| *rule)
+    }
+
+    pub fn apply(&mut self, rule: Rule) -> Result<(), ProofStateError> {
+        let goal = self.current().ok_or(ProofStateError::NoGoals)?;
+        let subgoals = subgoals(goal, rule)?;
+        let previous = self.goals.clone();
+        self.goals.splice(0..1, subgoals);
+        self.history.push((rule, previous));
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((_, previous)) => {
+                self.goals = previous;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn intro(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::Intro)
+    }
+
+    pub fn split(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::Split)
+    }
+
+    pub fn left(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::Left)
+    }
+
+    pub fn right(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::Right)
+    }
+
+    pub fn by_contradiction(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::ByContradiction)
+    }
+
+    pub fn assumption(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::Assumption)
+    }
+
+    pub fn contradiction(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::Contradiction)
+    }
+
+    pub fn destruct(&mut self, hypothesis: usize) -> Result<(), ProofStateError> {
+        self.apply(Rule::Destruct(hypothesis))
+    }
+
+    pub fn cases(&mut self, hypothesis: usize) -> Result<(), ProofStateError> {
+        self.apply(Rule::Cases(hypothesis))
+    }
+
+    pub fn apply_hypothesis(&mut self, hypothesis: usize) -> Result<(), ProofStateError> {
+        self.apply(Rule::Apply(hypothesis))
+    }
+
+    pub fn auto(&mut self) -> Result<(), ProofStateError> {
+        self.apply(Rule::Auto)
+    }
+}
+
+fn subgoals(goal: &Sequent, rule: Rule) -> Result<Vec<Sequent>, ProofStateError> {
+    let not_applicable = || ProofStateError::NotApplicable {
+        rule,
+        goal: goal.clone(),
+    };
+    let hypothesis = |index: usize| {
+        goal.antecedent
+            .get(index)
+            .ok_or(ProofStateError::NoSuchHypothesis { index })
+    };
+    let target = goal.succedent.first().map(|target| &**target);
+    let with = |extra: Vec<AST>, target: Option<AST>| {
+        let mut antecedent = goal.antecedent.clone();
+        antecedent.extend(extra);
+        Sequent::new(antecedent, target.into_iter().collect())
+    };
+    let replacing = |index: usize, extra: Vec<AST>| {
+        let mut antecedent = goal.antecedent.clone();
+        antecedent.splice(index..index + 1, extra);
+        Sequent::new(antecedent, goal.succedent.clone())
+    };
+    match (rule, target) {
+        (Rule::Intro, Some(ASTNode::Or(p1, p2))) => match &**p1 {
+            ASTNode::Not(antecedent) => Ok(vec![with(vec![antecedent.clone()], Some(p2.clone()))]),
+            _ => Err(not_applicable()),
+        },
+        (Rule::Intro, Some(ASTNode::Not(p))) => Ok(vec![with(vec![p.clone()], None)]),
+        (Rule::Split, Some(ASTNode::And(p1, p2))) => Ok(vec![
+            with(vec![], Some(p1.clone())),
+            with(vec![], Some(p2.clone())),
+        ]),
+        (Rule::Left, Some(ASTNode::Or(p1, _))) => Ok(vec![with(vec![], Some(p1.clone()))]),
+        (Rule::Right, Some(ASTNode::Or(_, p2))) => Ok(vec![with(vec![], Some(p2.clone()))]),
+        (Rule::ByContradiction, Some(_)) => Ok(vec![with(vec![goal.succedent[0].not()], None)]),
+        (Rule::Assumption, Some(_)) if goal.antecedent.contains(&goal.succedent[0]) => Ok(vec![]),
+        (Rule::Contradiction, _)
+            if goal
+                .antecedent
+                .iter()
+                .any(|hypothesis| match &**hypothesis {
+                    ASTNode::Not(p) => goal.antecedent.contains(p),
+                    _ => false,
+                }) =>
+        {
+            Ok(vec![])
+        }
+        (Rule::Destruct(index), _) => match &**hypothesis(index)? {
+            ASTNode::And(p1, p2) => Ok(vec![replacing(index, vec![p1.clone(), p2.clone()])]),
+            _ => Err(not_applicable()),
+        },
+        (Rule::Cases(index), _) => match &**hypothesis(index)? {
+            ASTNode::Or(p1, p2) => Ok(vec![
+                replacing(index, vec![p1.clone()]),
+                replacing(index, vec![p2.clone()]),
+            ]),
+            _ => Err(not_applicable()),
+        },
+        (Rule::Apply(index), _) => match (&**hypothesis(index)?, target) {
+            (ASTNode::Not(p), None) => Ok(vec![with(vec![], Some(p.clone()))]),
+            (ASTNode::Or(p1, p2), Some(_)) if *p2 == goal.succedent[0] => match &**p1 {
+                ASTNode::Not(antecedent) => Ok(vec![with(vec![], Some(antecedent.clone()))]),
+                _ => Err(not_applicable()),
+            },
+            _ => Err(not_applicable()),
+        },
+        (Rule::Auto, _) if sequent::prove(goal).is_some() => Ok(vec![]),
+        _ => Err(not_applicable()),
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    analysis::{self, Residual},
+    assignment::Assignment,
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    minimize,
+    proof::{ProofStep, ResolutionProof},
+    sat::{Clause, DPLLSolver},
+    tseitin,
+};
+
+const SYNTHESIS_LIMIT: usize = 10;
+
+pub fn interpolant(a: &AST, b: &AST) -> Option<AST> {
+    sequence_interpolants(&[a.clone(), b.clone()])?.pop()
+}
+
+pub fn sequence_interpolants(formulas: &[AST]) -> Option<Vec<AST>> {
+    let mut first = formulas
+        .iter()
+        .flat_map(|formula| formula.variables())
+        .max()
+        .map_or(0, |ident| ident + 1);
+    let mut partitions = vec![];
+    for formula in formulas {
+        let (clauses, auxiliary) = tseitin::cnf_clauses_from(formula, first);
+        first = auxiliary.last().map_or(first, |ident| ident + 1);
+        partitions.push(clauses);
+    }
+    let mut origins = HashMap::new();
+    for (index, clauses) in partitions.iter().enumerate() {
+        for clause in clauses {
+            origins.entry(clause.clone()).or_insert(index);
+        }
+    }
+    let proof = DPLLSolver::from(partitions.concat()).refute()?;
+    let occurrences = partitions
+        .iter()
+        .map(|clauses| {
+            clauses
+                .iter()
+                .flat_map(|clause| clause.literals())
+                .map(|literal| literal.identifier())
+                .collect::<BTreeSet<_>>()
+        })
+        .collect::<Vec<_>>();
+    let vocabulary = |formulas: &[AST]| {
+        formulas
+            .iter()
+            .flat_map(|formula| formula.variables())
+            .collect::<BTreeSet<_>>()
+    };
+    (1..formulas.len())
+        .map(|cut| {
+            let later = occurrences[cut..]
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<BTreeSet<_>>();
+            let (prefix, suffix) = (vocabulary(&formulas[..cut]), vocabulary(&formulas[cut..]));
+            let within = prefix
+                .intersection(&suffix)
+                .chain(&prefix)
+                .next()
+                .map_or(formulas[0].clone(), |ident| {
+                    <AST as AbstractSyntaxTree>::variable(*ident)
+                });
+            Some(
+                partial_interpolant(&proof, |clause| origins[clause] < cut, &later)?
+                    .into_ast(&within),
+            )
+        })
+        .collect()
+}
+
+pub fn uniform_interpolant(formula: &AST, keep: &BTreeSet<Ident>) -> AST {
+    let mut residual = Residual::Formula(formula.clone());
+    for ident in formula.variables().difference(keep) {
+        residual = match residual {
+            Residual::Formula(formula) => simplify(forget(&formula, *ident)),
+            constant => constant,
+        };
+    }
+    let within = keep.first().map_or(formula.clone(), |ident| {
+        <AST as AbstractSyntaxTree>::variable(*ident)
+    });
+    residual.into_ast(&within)
+}
+
+fn forget(formula: &AST, ident: Ident) -> Residual {
+    let cofactor =
+        |value: bool| analysis::restrict(formula, &Assignment::from_iter([(ident, value)]));
+    cofactor(false).or(cofactor(true))
+}
+
+fn simplify(residual: Residual) -> Residual {
+    let Residual::Formula(formula) = residual else {
+        return residual;
+    };
+    let variables = formula.variables().into_iter().collect::<Vec<_>>();
+    if variables.len() > SYNTHESIS_LIMIT {
+        return Residual::Formula(formula);
+    }
+    let bits = (0..1u64 << variables.len())
+        .map(|minterm| {
+            let model = variables
+                .iter()
+                .enumerate()
+                .map(|(index, ident)| (*ident, minterm >> index & 1 == 1))
+                .collect::<Model>();
+            formula.verify_model(&model)
+        })
+        .collect::<Vec<_>>();
+    match (bits.iter().all(|bit| *bit), bits.iter().any(|bit| *bit)) {
+        (true, _) => Residual::Constant(true),
+        (_, false) => Residual::Constant(false),
+        _ => Residual::Formula(minimize::synthesize(&bits, &variables).unwrap_or(formula)),
+    }
+}
+
+fn partial_interpolant(
+    proof: &ResolutionProof,
+    in_prefix: impl Fn(&Clause) -> bool,
+    later: &BTreeSet<Ident>,
+) -> Option<Residual> {
+    let empty = proof.empty_clause()?;
+    let mut partial: Vec<Residual> = vec![];
+    for step in &proof.steps[..=empty] {
+        let residual = match step {
+            ProofStep::Premise(clause) if in_prefix(clause) => clause
+                .literals()
+                .filter(|literal| later.contains(&literal.identifier()))
+                .map(|literal| Residual::Formula(literal.to_ast()))
+                .fold(Residual::Constant(false), Residual::or),
+            ProofStep::Premise(_) => Residual::Constant(true),
+            ProofStep::Resolution {
+                left, right, pivot, ..
+            } => {
+                let (left, right) = (partial[*left].clone(), partial[*right].clone());
+                match later.contains(pivot) {
+                    true => left.and(right),
+                    false => left.or(right),
+                }
+            }
+        };
+        partial.push(residual);
+    }
+    partial.pop()
+}
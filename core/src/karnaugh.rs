@@ -0,0 +1,212 @@
+use std::fmt;
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    minimize::{self, Implicant},
+};
+
+pub const MAX_VARIABLES: usize = 6;
+
+#[derive(Debug)]
+pub enum KarnaughError {
+    TooManyVariables(usize),
+}
+
+impl fmt::Display for KarnaughError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KarnaughError::TooManyVariables(count) => write!(
+                f,
+                "{} variables exceed the Karnaugh map limit of {}",
+                count, MAX_VARIABLES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KarnaughError {}
+
+pub struct KarnaughMap {
+    variables: Vec<Ident>,
+    values: Vec<bool>,
+    groups: Vec<Implicant>,
+}
+
+impl TryFrom<&AST> for KarnaughMap {
+    type Error = KarnaughError;
+
+    fn try_from(formula: &AST) -> Result<Self, Self::Error> {
+        let variables = formula.variables().into_iter().collect::<Vec<_>>();
+        if variables.len() > MAX_VARIABLES {
+            return Err(KarnaughError::TooManyVariables(variables.len()));
+        }
+        let values = (0..1u64 << variables.len())
+            .map(|minterm| {
+                let model = variables
+                    .iter()
+                    .enumerate()
+                    .map(|(index, ident)| (*ident, minterm >> index & 1 == 1))
+                    .collect::<Model>();
+                formula.verify_model(&model)
+            })
+            .collect::<Vec<_>>();
+        let groups = minimize::minimize_truth_table(&values, variables.len())
+            .ok_or(KarnaughError::TooManyVariables(variables.len()))?;
+        Ok(Self {
+            variables,
+            values,
+            groups,
+        })
+    }
+}
+
+impl fmt::Display for KarnaughMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (rows, columns) = self.split();
+        let label = |variables: &[usize]| {
+            variables
+                .iter()
+                .map(|index| format!("var{}", self.variables[*index]))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let corner = format!("{} \\ {}", label(&rows), label(&columns));
+        let cells = self.cells(&rows, &columns);
+        let column_labels = gray_codes(columns.len())
+            .map(|code| bit_string(code, columns.len()))
+            .collect::<Vec<_>>();
+        let width = cells
+            .iter()
+            .flatten()
+            .chain(&column_labels)
+            .map(|cell| cell.chars().count())
+            .max()
+            .unwrap_or(1);
+        let corner_width = corner.chars().count().max(rows.len());
+        write!(f, "{:corner_width$}", corner)?;
+        for column_label in &column_labels {
+            write!(f, " | {:width$}", column_label)?;
+        }
+        writeln!(f)?;
+        write!(f, "{}", "-".repeat(corner_width))?;
+        for _ in &column_labels {
+            write!(f, "-+-{}", "-".repeat(width))?;
+        }
+        writeln!(f)?;
+        for (code, row) in gray_codes(rows.len()).zip(&cells) {
+            write!(f, "{:corner_width$}", bit_string(code, rows.len()))?;
+            for cell in row {
+                write!(f, " | {:width$}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        for (index, group) in self.groups.iter().enumerate() {
+            writeln!(f, "{}: {}", group_name(index), self.product(group))?;
+        }
+        Ok(())
+    }
+}
+
+impl KarnaughMap {
+    pub fn variables(&self) -> &[Ident] {
+        &self.variables
+    }
+
+    pub fn groups(&self) -> &[Implicant] {
+        &self.groups
+    }
+
+    pub fn value(&self, model: &Model) -> bool {
+        let minterm = self
+            .variables
+            .iter()
+            .enumerate()
+            .filter(|(_, ident)| model.get(**ident).unwrap_or(false))
+            .fold(0, |minterm, (index, _)| minterm | 1 << index);
+        self.values[minterm]
+    }
+
+    pub fn minimized(&self) -> AST {
+        minimize::synthesize_cover(&self.groups, &self.variables)
+    }
+
+    fn split(&self) -> (Vec<usize>, Vec<usize>) {
+        let indices = (0..self.variables.len()).collect::<Vec<_>>();
+        let (rows, columns) = indices.split_at(self.variables.len() / 2);
+        (rows.to_vec(), columns.to_vec())
+    }
+
+    fn cells(&self, rows: &[usize], columns: &[usize]) -> Vec<Vec<String>> {
+        gray_codes(rows.len())
+            .map(|row| {
+                gray_codes(columns.len())
+                    .map(|column| {
+                        let minterm = place(row, rows) | place(column, columns);
+                        let value = match self.values[minterm as usize] {
+                            true => "1",
+                            false => "0",
+                        };
+                        let names = self
+                            .groups
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, group)| group.covers(minterm))
+                            .map(|(index, _)| group_name(index))
+                            .collect::<String>();
+                        match names.is_empty() {
+                            true => value.to_owned(),
+                            false => format!("{} {}", value, names),
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn product(&self, group: &Implicant) -> String {
+        let literals = self
+            .variables
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| group.care >> index & 1 == 1)
+            .map(|(index, ident)| match group.value >> index & 1 == 1 {
+                true => format!("var{}", ident),
+                false => format!("¬var{}", ident),
+            })
+            .collect::<Vec<_>>();
+        match literals.is_empty() {
+            true => "1".to_owned(),
+            false => literals.join(" ∧ "),
+        }
+    }
+}
+
+fn gray_codes(bits: usize) -> impl Iterator<Item = u64> {
+    (0..1u64 << bits).map(|index| index ^ index >> 1)
+}
+
+fn bit_string(code: u64, bits: usize) -> String {
+    (0..bits)
+        .rev()
+        .map(|bit| match code >> bit & 1 {
+            0 => '0',
+            _ => '1',
+        })
+        .collect()
+}
+
+fn place(code: u64, indices: &[usize]) -> u64 {
+    indices
+        .iter()
+        .rev()
+        .enumerate()
+        .filter(|(bit, _)| code >> bit & 1 == 1)
+        .fold(0, |minterm, (_, index)| minterm | 1 << index)
+}
+
+fn group_name(index: usize) -> char {
+    match index {
+        0..26 => (b'A' + index as u8) as char,
+        _ => (b'a' + (index - 26) as u8) as char,
+    }
+}
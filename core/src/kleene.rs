@@ -0,0 +1,140 @@
+use std::{collections::HashMap, fmt, ops};
+
+use crate::ast::{AbstractSyntaxTree, Ident, Model, Node};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Truth {
+    False,
+    Unknown,
+    True,
+}
+
+impl fmt::Display for Truth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Truth::False => write!(f, "false"),
+            Truth::Unknown => write!(f, "unknown"),
+            Truth::True => write!(f, "true"),
+        }
+    }
+}
+
+impl From<bool> for Truth {
+    fn from(value: bool) -> Self {
+        match value {
+            true => Truth::True,
+            false => Truth::False,
+        }
+    }
+}
+
+impl From<Option<bool>> for Truth {
+    fn from(value: Option<bool>) -> Self {
+        value.map_or(Truth::Unknown, Truth::from)
+    }
+}
+
+impl ops::Not for Truth {
+    type Output = Truth;
+
+    fn not(self) -> Truth {
+        match self {
+            Truth::False => Truth::True,
+            Truth::Unknown => Truth::Unknown,
+            Truth::True => Truth::False,
+        }
+    }
+}
+
+impl ops::BitAnd for Truth {
+    type Output = Truth;
+
+    fn bitand(self, rhs: Truth) -> Truth {
+        match (self, rhs) {
+            (Truth::False, _) | (_, Truth::False) => Truth::False,
+            (Truth::True, Truth::True) => Truth::True,
+            _ => Truth::Unknown,
+        }
+    }
+}
+
+impl ops::BitOr for Truth {
+    type Output = Truth;
+
+    fn bitor(self, rhs: Truth) -> Truth {
+        !(!self & !rhs)
+    }
+}
+
+impl Truth {
+    pub fn to_bool(self) -> Option<bool> {
+        match self {
+            Truth::False => Some(false),
+            Truth::Unknown => None,
+            Truth::True => Some(true),
+        }
+    }
+
+    pub fn kleene_implies(self, rhs: Truth) -> Truth {
+        !self | rhs
+    }
+
+    pub fn lukasiewicz_implies(self, rhs: Truth) -> Truth {
+        match (self, rhs) {
+            (Truth::Unknown, Truth::Unknown) => Truth::True,
+            _ => self.kleene_implies(rhs),
+        }
+    }
+}
+
+pub trait ThreeValued {
+    fn evaluate_three_valued(&self, assignment: &HashMap<Ident, Truth>) -> Truth;
+    fn evaluate_partial(&self, model: &Model) -> Truth;
+}
+
+impl<T: AbstractSyntaxTree> ThreeValued for T {
+    fn evaluate_three_valued(&self, assignment: &HashMap<Ident, Truth>) -> Truth {
+        evaluate(self, assignment, &mut HashMap::new())
+    }
+
+    fn evaluate_partial(&self, model: &Model) -> Truth {
+        self.evaluate_three_valued(
+            &model
+                .iter()
+                .map(|(ident, value)| (ident, Truth::from(value)))
+                .collect(),
+        )
+    }
+}
+
+fn evaluate<T: AbstractSyntaxTree>(
+    formula: &T,
+    assignment: &HashMap<Ident, Truth>,
+    memo: &mut HashMap<usize, Truth>,
+) -> Truth {
+    if let Some(truth) = memo.get(&formula.key()) {
+        return *truth;
+    }
+    let mut recurse = |p: &T| evaluate(p, assignment, memo);
+    let truth = match formula.node() {
+        Node::Variable(ident) => assignment.get(&ident).copied().unwrap_or(Truth::Unknown),
+        Node::Not(p) => !recurse(&p),
+        Node::And(p1, p2) => match recurse(&p1) {
+            Truth::False => Truth::False,
+            lhs => lhs & recurse(&p2),
+        },
+        Node::Or(p1, p2) => match recurse(&p1) {
+            Truth::True => Truth::True,
+            lhs => lhs | recurse(&p2),
+        },
+        Node::Nand(p1, p2) => !(recurse(&p1) & recurse(&p2)),
+        Node::Nor(p1, p2) => !(recurse(&p1) | recurse(&p2)),
+        Node::Ite(p1, p2, p3) => match recurse(&p1) {
+            Truth::True => recurse(&p2),
+            Truth::False => recurse(&p3),
+            Truth::Unknown => (Truth::Unknown & recurse(&p2)) | (Truth::Unknown & recurse(&p3)),
+        },
+    };
+    memo.insert(formula.key(), truth);
+    truth
+}
@@ -0,0 +1,232 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    ast::{AbstractSyntaxTree, AST},
+    context::Context,
+    sat::Literal,
+};
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    formula: AST,
+    activation: Literal,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KnowledgeBase {
+    context: Context,
+    entries: Vec<Entry>,
+}
+
+impl fmt::Display for KnowledgeBase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}: {}", entry.name, entry.formula)?;
+        }
+        Ok(())
+    }
+}
+
+impl KnowledgeBase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    pub fn formulas(&self) -> impl Iterator<Item = (&str, &AST)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), &entry.formula))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AST> {
+        self.position(name)
+            .map(|index| &self.entries[index].formula)
+    }
+
+    pub fn add<T: AbstractSyntaxTree>(&mut self, name: &str, formula: &T) -> Option<AST> {
+        let previous = self.retract(name);
+        let formula = formula.to_ast();
+        let activation = self.context.guard(&formula);
+        self.entries.push(Entry {
+            name: name.to_owned(),
+            formula,
+            activation,
+        });
+        previous
+    }
+
+    pub fn retract(&mut self, name: &str) -> Option<AST> {
+        let entry = self.entries.remove(self.position(name)?);
+        self.context.retire(&entry.activation);
+        Some(entry.formula)
+    }
+
+    pub fn is_consistent(&mut self) -> bool {
+        let all = (0..self.entries.len()).collect::<Vec<_>>();
+        self.check(&all, None)
+    }
+
+    pub fn entails<T: AbstractSyntaxTree>(&mut self, query: &T) -> bool {
+        let all = (0..self.entries.len()).collect::<Vec<_>>();
+        let refutation = self.context.literal(query).not();
+        !self.check(&all, Some(&refutation))
+    }
+
+    pub fn why<T: AbstractSyntaxTree>(&mut self, query: &T) -> Option<Vec<String>> {
+        let all = (0..self.entries.len()).collect::<Vec<_>>();
+        let refutation = self.context.literal(query).not();
+        if self.check(&all, Some(&refutation)) {
+            return None;
+        }
+        let indices = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (entry.activation.clone(), index))
+            .collect::<HashMap<_, _>>();
+        let mut premises = self
+            .context
+            .unsat_core()
+            .iter()
+            .filter_map(|literal| indices.get(literal).copied())
+            .collect::<Vec<_>>();
+        premises.sort_unstable();
+        premises.dedup();
+        let mut index = 0;
+        while index < premises.len() {
+            let candidate = premises[index];
+            premises.remove(index);
+            if self.check(&premises, Some(&refutation)) {
+                premises.insert(index, candidate);
+                index += 1;
+            }
+        }
+        Some(self.names_of(&premises))
+    }
+
+    pub fn maximal_consistent_subsets<T: AbstractSyntaxTree>(
+        &mut self,
+        formula: &T,
+    ) -> Vec<Vec<String>> {
+        let literal = self.context.literal(formula);
+        self.maximal_subsets(&literal)
+            .iter()
+            .map(|subset| self.names_of(subset))
+            .collect()
+    }
+
+    pub fn remainders<T: AbstractSyntaxTree>(&mut self, formula: &T) -> Vec<Vec<String>> {
+        let refutation = self.context.literal(formula).not();
+        self.maximal_subsets(&refutation)
+            .iter()
+            .map(|subset| self.names_of(subset))
+            .collect()
+    }
+
+    pub fn contract<T: AbstractSyntaxTree>(&mut self, formula: &T) -> Vec<String> {
+        let refutation = self.context.literal(formula).not();
+        self.retain_meet(&refutation)
+    }
+
+    pub fn revise<T: AbstractSyntaxTree>(&mut self, name: &str, formula: &T) -> Vec<String> {
+        let literal = self.context.literal(formula);
+        let mut retracted = self.retain_meet(&literal);
+        if self.add(name, formula).is_some() {
+            retracted.push(name.to_owned());
+        }
+        retracted
+    }
+
+    pub(crate) fn literal<T: AbstractSyntaxTree>(&mut self, formula: &T) -> Literal {
+        self.context.literal(formula)
+    }
+
+    pub(crate) fn check_assuming(&mut self, extra: &[Literal]) -> bool {
+        let mut assumptions = self
+            .entries
+            .iter()
+            .map(|entry| entry.activation.clone())
+            .collect::<Vec<_>>();
+        assumptions.extend(extra.iter().cloned());
+        self.context.check_literals(&assumptions)
+    }
+
+    fn retain_meet(&mut self, extra: &Literal) -> Vec<String> {
+        let subsets = self.maximal_subsets(extra);
+        let keep = (0..self.entries.len())
+            .map(|index| subsets.iter().all(|subset| subset.contains(&index)))
+            .collect::<Vec<_>>();
+        let retracted = (0..self.entries.len())
+            .filter(|index| !keep[*index])
+            .collect::<Vec<_>>();
+        let names = self.names_of(&retracted);
+        for name in &names {
+            self.retract(name);
+        }
+        names
+    }
+
+    fn maximal_subsets(&mut self, extra: &Literal) -> Vec<Vec<usize>> {
+        let mut blocked = self.context.clone();
+        let mut subsets = vec![];
+        while blocked.check_literals(std::slice::from_ref(extra)) {
+            let mut subset = (0..self.entries.len())
+                .filter(|index| blocked.holds(&self.entries[*index].activation))
+                .collect::<Vec<_>>();
+            for index in 0..self.entries.len() {
+                if subset.contains(&index) {
+                    continue;
+                }
+                subset.push(index);
+                if !self.check(&subset, Some(extra)) {
+                    subset.pop();
+                }
+            }
+            subset.sort_unstable();
+            let complement = (0..self.entries.len())
+                .filter(|index| !subset.contains(index))
+                .map(|index| self.entries[index].activation.clone())
+                .collect::<Vec<_>>();
+            let complete = complement.is_empty();
+            subsets.push(subset);
+            if complete {
+                break;
+            }
+            blocked.add_clause(complement);
+        }
+        subsets
+    }
+
+    fn names_of(&self, indices: &[usize]) -> Vec<String> {
+        indices
+            .iter()
+            .map(|index| self.entries[*index].name.clone())
+            .collect()
+    }
+
+    fn check(&mut self, indices: &[usize], extra: Option<&Literal>) -> bool {
+        let mut assumptions = indices
+            .iter()
+            .map(|index| self.entries[*index].activation.clone())
+            .collect::<Vec<_>>();
+        assumptions.extend(extra.cloned());
+        self.context.check_literals(&assumptions)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.name == name)
+    }
+}
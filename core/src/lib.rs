@@ -1,3 +1,67 @@
+pub mod analysis;
+pub mod anf;
+pub mod arena;
+pub mod assignment;
 pub mod ast;
+pub mod bdd;
+pub mod benchmark;
+pub mod bmc;
+pub mod budget;
+pub mod canonical;
+pub mod cdcl;
+pub mod cnf;
+pub mod config;
+pub mod confluence;
+pub mod context;
+pub mod counting;
+pub mod cse;
+pub mod csp;
+pub mod ctl;
+pub mod cube;
+pub mod defaults;
+pub mod dimacs;
+pub mod dnf;
+pub mod encodings;
+pub mod error;
+pub mod features;
+pub mod fol;
+pub mod gates;
+pub mod hilbert;
+pub mod implication;
+pub mod induction;
+pub mod interactive;
+pub mod interpolation;
+pub mod karnaugh;
+pub mod kleene;
+pub mod knowledge;
+pub mod lookahead;
+pub mod ltl;
+pub mod minimal;
+pub mod minimize;
+pub mod modal;
+pub mod mus;
+pub mod nary;
+pub mod parse;
+pub mod pdr;
+pub mod portfolio;
+pub mod preprocess;
+pub mod program;
+pub mod proof;
+pub mod qbf;
+pub mod random;
 pub mod rewrite;
+pub mod sampling;
 pub mod sat;
+pub mod schema;
+pub mod sequent;
+pub mod smart;
+pub mod sudoku;
+pub mod survey;
+pub mod symmetry;
+pub mod tableau;
+pub mod tactic;
+pub mod trace;
+pub mod transition;
+pub mod tseitin;
+pub mod unify;
+pub mod walksat;
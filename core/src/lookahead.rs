@@ -0,0 +1,394 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    ast::{Ident, Model},
+    sat::{Clause, Solver},
+    trace::{trace_event, trace_span},
+};
+
+const PRODUCT_WEIGHT: f64 = 1024.0;
+const TRIGGER_DECAY: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lookahead {
+    pub candidates: usize,
+    pub double_lookahead: bool,
+    pub double_candidates: usize,
+    pub necessary_assignments: bool,
+}
+
+impl Default for Lookahead {
+    fn default() -> Self {
+        Self {
+            candidates: 32,
+            double_lookahead: true,
+            double_candidates: 8,
+            necessary_assignments: true,
+        }
+    }
+}
+
+impl Solver for Lookahead {
+    fn solve(&self, clauses: &[Clause]) -> Option<Model> {
+        trace_span!("lookahead");
+        let mut variables = BTreeMap::new();
+        for literal in clauses.iter().flat_map(|clause| clause.literals()) {
+            let next = variables.len();
+            variables.entry(literal.identifier()).or_insert(next);
+        }
+        let clauses = clauses
+            .iter()
+            .map(|clause| {
+                clause
+                    .literals()
+                    .map(|literal| {
+                        2 * variables[&literal.identifier()] + !literal.is_positive() as usize
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|clause| !clause.iter().any(|lit| clause.contains(&(lit ^ 1))))
+            .collect::<Vec<_>>();
+        let mut search = Search::new(*self, variables.len(), clauses)?;
+        let values = search.search()?;
+        trace_event!(
+            "lookahead.solved",
+            decisions = search.decisions,
+            failed = search.failed
+        );
+        Some(
+            variables
+                .into_iter()
+                .map(|(ident, index): (Ident, usize)| (ident, values[index]))
+                .collect(),
+        )
+    }
+}
+
+struct Probe {
+    reduction: f64,
+    implied: Vec<usize>,
+}
+
+struct Search {
+    config: Lookahead,
+    clauses: Vec<Vec<usize>>,
+    watches: Vec<Vec<usize>>,
+    occurrences: Vec<Vec<usize>>,
+    truth: Vec<bool>,
+    trail: Vec<usize>,
+    head: usize,
+    stamps: Vec<usize>,
+    stamp: usize,
+    marks: Vec<usize>,
+    mark: usize,
+    trigger: f64,
+    decisions: usize,
+    failed: usize,
+}
+
+impl Search {
+    fn new(config: Lookahead, variables: usize, clauses: Vec<Vec<usize>>) -> Option<Self> {
+        let mut watches = vec![vec![]; 2 * variables];
+        let mut occurrences = vec![vec![]; 2 * variables];
+        let mut units = vec![];
+        for (index, clause) in clauses.iter().enumerate() {
+            match clause[..] {
+                [] => return None,
+                [unit] => units.push(unit),
+                [first, second, ..] => {
+                    watches[first].push(index);
+                    watches[second].push(index);
+                }
+            }
+            for lit in clause {
+                occurrences[*lit].push(index);
+            }
+        }
+        let mut search = Self {
+            config,
+            stamps: vec![0; clauses.len()],
+            clauses,
+            watches,
+            occurrences,
+            truth: vec![false; 2 * variables],
+            trail: vec![],
+            head: 0,
+            stamp: 0,
+            marks: vec![0; 2 * variables],
+            mark: 0,
+            trigger: 0.0,
+            decisions: 0,
+            failed: 0,
+        };
+        for unit in units {
+            if !search.assert(unit) {
+                return None;
+            }
+        }
+        Some(search)
+    }
+
+    fn is_free(&self, variable: usize) -> bool {
+        !self.truth[2 * variable] && !self.truth[2 * variable + 1]
+    }
+
+    fn assign(&mut self, lit: usize) {
+        self.truth[lit] = true;
+        self.trail.push(lit);
+    }
+
+    fn assert(&mut self, lit: usize) -> bool {
+        if self.truth[lit] {
+            return true;
+        }
+        if self.truth[lit ^ 1] {
+            return false;
+        }
+        self.assign(lit);
+        self.propagate()
+    }
+
+    fn undo(&mut self, length: usize) {
+        for lit in self.trail.drain(length..) {
+            self.truth[lit] = false;
+        }
+        self.head = self.head.min(length);
+    }
+
+    fn propagate(&mut self) -> bool {
+        while let Some(&lit) = self.trail.get(self.head) {
+            self.head += 1;
+            let falsified = lit ^ 1;
+            let mut watchers = std::mem::take(&mut self.watches[falsified]);
+            let mut index = 0;
+            let mut consistent = true;
+            while index < watchers.len() {
+                let clause = &mut self.clauses[watchers[index]];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let other = clause[0];
+                if self.truth[other] {
+                    index += 1;
+                    continue;
+                }
+                if let Some(position) =
+                    (2..clause.len()).find(|position| !self.truth[clause[*position] ^ 1])
+                {
+                    clause.swap(1, position);
+                    let watch = clause[1];
+                    self.watches[watch].push(watchers.swap_remove(index));
+                    continue;
+                }
+                index += 1;
+                if self.truth[other ^ 1] {
+                    consistent = false;
+                    break;
+                }
+                self.assign(other);
+            }
+            self.watches[falsified] = watchers;
+            if !consistent {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn search(&mut self) -> Option<Vec<bool>> {
+        let mut decisions: Vec<(usize, usize, bool)> = vec![];
+        loop {
+            let consistent = match self.lookahead() {
+                Ok(None) => {
+                    let variables = self.truth.len() / 2;
+                    return Some((0..variables).map(|var| self.truth[2 * var]).collect());
+                }
+                Ok(Some(lit)) => {
+                    self.decisions += 1;
+                    decisions.push((self.trail.len(), lit, false));
+                    self.assign(lit);
+                    self.propagate()
+                }
+                Err(()) => false,
+            };
+            if consistent {
+                continue;
+            }
+            loop {
+                let (length, lit, flipped) = decisions.pop()?;
+                self.undo(length);
+                if flipped {
+                    continue;
+                }
+                decisions.push((length, lit ^ 1, true));
+                self.assign(lit ^ 1);
+                if self.propagate() {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn lookahead(&mut self) -> Result<Option<usize>, ()> {
+        self.trigger *= TRIGGER_DECAY;
+        loop {
+            let candidates = self.preselect(self.config.candidates.max(1));
+            if candidates.is_empty() {
+                return Ok(None);
+            }
+            let mut changed = false;
+            let mut best: Option<(f64, usize)> = None;
+            for variable in candidates {
+                if !self.is_free(variable) {
+                    continue;
+                }
+                let positive = 2 * variable;
+                let Some(first) = self.probe(positive) else {
+                    self.failed_literal(positive)?;
+                    changed = true;
+                    continue;
+                };
+                let Some(second) = self.probe(positive ^ 1) else {
+                    self.failed_literal(positive ^ 1)?;
+                    changed = true;
+                    continue;
+                };
+                if self.config.necessary_assignments {
+                    self.mark += 1;
+                    for lit in &first.implied {
+                        self.marks[*lit] = self.mark;
+                    }
+                    for lit in second.implied {
+                        if self.marks[lit] == self.mark && !self.truth[lit] {
+                            if !self.assert(lit) {
+                                return Err(());
+                            }
+                            changed = true;
+                        }
+                    }
+                }
+                let score = PRODUCT_WEIGHT * first.reduction * second.reduction
+                    + first.reduction
+                    + second.reduction;
+                let branch = match first.reduction <= second.reduction {
+                    true => positive,
+                    false => positive ^ 1,
+                };
+                if best.is_none_or(|(best, _)| score > best) {
+                    best = Some((score, branch));
+                }
+            }
+            if !changed {
+                return Ok(best.map(|(_, branch)| branch));
+            }
+        }
+    }
+
+    fn failed_literal(&mut self, lit: usize) -> Result<(), ()> {
+        self.failed += 1;
+        match self.assert(lit ^ 1) {
+            true => Ok(()),
+            false => Err(()),
+        }
+    }
+
+    fn fails(&mut self, lit: usize) -> bool {
+        let length = self.trail.len();
+        self.assign(lit);
+        let consistent = self.propagate();
+        self.undo(length);
+        !consistent
+    }
+
+    fn probe(&mut self, lit: usize) -> Option<Probe> {
+        let length = self.trail.len();
+        self.assign(lit);
+        if !self.propagate() {
+            self.undo(length);
+            return None;
+        }
+        let reduction = self.reduction(length);
+        if self.config.double_lookahead && reduction > self.trigger {
+            if !self.double_lookahead() {
+                self.undo(length);
+                return None;
+            }
+            self.trigger = reduction;
+        }
+        let implied = self.trail[length + 1..].to_vec();
+        self.undo(length);
+        Some(Probe { reduction, implied })
+    }
+
+    fn double_lookahead(&mut self) -> bool {
+        for variable in self.preselect(self.config.double_candidates) {
+            for lit in [2 * variable, 2 * variable + 1] {
+                if !self.is_free(variable) {
+                    break;
+                }
+                if self.fails(lit) && !self.assert(lit ^ 1) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn reduction(&mut self, length: usize) -> f64 {
+        self.stamp += 1;
+        let mut reduction = 0.0;
+        for position in length..self.trail.len() {
+            let falsified = self.trail[position] ^ 1;
+            for index in 0..self.occurrences[falsified].len() {
+                let clause = self.occurrences[falsified][index];
+                if self.stamps[clause] == self.stamp {
+                    continue;
+                }
+                self.stamps[clause] = self.stamp;
+                let mut free = 0;
+                let mut satisfied = false;
+                for lit in &self.clauses[clause] {
+                    satisfied |= self.truth[*lit];
+                    free += (!self.truth[*lit] && !self.truth[lit ^ 1]) as usize;
+                }
+                if !satisfied && free >= 2 {
+                    reduction += 0.5f64.powi(free as i32 - 2);
+                }
+            }
+        }
+        reduction
+    }
+
+    fn preselect(&self, count: usize) -> Vec<usize> {
+        let mut scores = vec![0.0; self.truth.len()];
+        for clause in &self.clauses {
+            if clause.iter().any(|lit| self.truth[*lit]) {
+                continue;
+            }
+            let free = clause
+                .iter()
+                .filter(|lit| !self.truth[**lit ^ 1])
+                .collect::<Vec<_>>();
+            let weight = 0.5f64.powi(free.len() as i32);
+            for lit in free {
+                scores[*lit] += weight;
+            }
+        }
+        let mut candidates = (0..self.truth.len() / 2)
+            .filter(|variable| scores[2 * variable] + scores[2 * variable + 1] > 0.0)
+            .map(|variable| {
+                let (positive, negative) = (scores[2 * variable], scores[2 * variable + 1]);
+                (
+                    PRODUCT_WEIGHT * positive * negative + positive + negative,
+                    variable,
+                )
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|left, right| right.0.total_cmp(&left.0).then(left.1.cmp(&right.1)));
+        candidates.truncate(count);
+        candidates
+            .into_iter()
+            .map(|(_, variable)| variable)
+            .collect()
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    fmt,
+    rc::Rc,
+};
+
+use crate::{ast::Ident, modal::KripkeModel};
+
+pub type LtlFormula = Rc<LtlNode>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LtlNode {
+    Variable(Ident),
+    Not(LtlFormula),
+    And(LtlFormula, LtlFormula),
+    Or(LtlFormula, LtlFormula),
+    Next(LtlFormula),
+    Finally(LtlFormula),
+    Globally(LtlFormula),
+    Until(LtlFormula, LtlFormula),
+    Release(LtlFormula, LtlFormula),
+}
+
+impl fmt::Display for LtlNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LtlNode::Variable(identifier) => write!(f, "var{}", identifier),
+            LtlNode::Not(p) => write!(f, "¬{}", p),
+            LtlNode::And(lhs, rhs) => write!(f, "({} ∧ {})", lhs, rhs),
+            LtlNode::Or(lhs, rhs) => write!(f, "({} ∨ {})", lhs, rhs),
+            LtlNode::Next(p) => write!(f, "X{}", p),
+            LtlNode::Finally(p) => write!(f, "F{}", p),
+            LtlNode::Globally(p) => write!(f, "G{}", p),
+            LtlNode::Until(lhs, rhs) => write!(f, "({} U {})", lhs, rhs),
+            LtlNode::Release(lhs, rhs) => write!(f, "({} R {})", lhs, rhs),
+        }
+    }
+}
+
+pub trait TemporalLogic {
+    fn variable(ident: Ident) -> LtlFormula;
+    fn not(&self) -> LtlFormula;
+    fn and(&self, other: LtlFormula) -> LtlFormula;
+    fn or(&self, other: LtlFormula) -> LtlFormula;
+    fn next(&self) -> LtlFormula;
+    fn finally(&self) -> LtlFormula;
+    fn globally(&self) -> LtlFormula;
+    fn until(&self, other: LtlFormula) -> LtlFormula;
+    fn release(&self, other: LtlFormula) -> LtlFormula;
+    fn nnf(&self) -> LtlFormula;
+}
+
+impl TemporalLogic for LtlFormula {
+    fn variable(ident: Ident) -> LtlFormula {
+        Rc::new(LtlNode::Variable(ident))
+    }
+
+    fn not(&self) -> LtlFormula {
+        Rc::new(LtlNode::Not(self.clone()))
+    }
+
+    fn and(&self, other: LtlFormula) -> LtlFormula {
+        Rc::new(LtlNode::And(self.clone(), other))
+    }
+
+    fn or(&self, other: LtlFormula) -> LtlFormula {
+        Rc::new(LtlNode::Or(self.clone(), other))
+    }
+
+    fn next(&self) -> LtlFormula {
+        Rc::new(LtlNode::Next(self.clone()))
+    }
+
+    fn finally(&self) -> LtlFormula {
+        Rc::new(LtlNode::Finally(self.clone()))
+    }
+
+    fn globally(&self) -> LtlFormula {
+        Rc::new(LtlNode::Globally(self.clone()))
+    }
+
+    fn until(&self, other: LtlFormula) -> LtlFormula {
+        Rc::new(LtlNode::Until(self.clone(), other))
+    }
+
+    fn release(&self, other: LtlFormula) -> LtlFormula {
+        Rc::new(LtlNode::Release(self.clone(), other))
+    }
+
+    fn nnf(&self) -> LtlFormula {
+        match &**self {
+            LtlNode::Variable(_) => self.clone(),
+            LtlNode::And(p1, p2) => p1.nnf().and(p2.nnf()),
+            LtlNode::Or(p1, p2) => p1.nnf().or(p2.nnf()),
+            LtlNode::Next(p) => p.nnf().next(),
+            LtlNode::Finally(p) => p.nnf().finally(),
+            LtlNode::Globally(p) => p.nnf().globally(),
+            LtlNode::Until(p1, p2) => p1.nnf().until(p2.nnf()),
+            LtlNode::Release(p1, p2) => p1.nnf().release(p2.nnf()),
+            LtlNode::Not(p) => match &**p {
+                LtlNode::Variable(_) => self.clone(),
+                LtlNode::Not(q) => q.nnf(),
+                LtlNode::And(q1, q2) => q1.not().nnf().or(q2.not().nnf()),
+                LtlNode::Or(q1, q2) => q1.not().nnf().and(q2.not().nnf()),
+                LtlNode::Next(q) => q.not().nnf().next(),
+                LtlNode::Finally(q) => q.not().nnf().globally(),
+                LtlNode::Globally(q) => q.not().nnf().finally(),
+                LtlNode::Until(q1, q2) => q1.not().nnf().release(q2.not().nnf()),
+                LtlNode::Release(q1, q2) => q1.not().nnf().until(q2.not().nnf()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KripkeStructure {
+    pub initial: BTreeSet<usize>,
+    pub model: KripkeModel,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterexample {
+    pub prefix: Vec<usize>,
+    pub cycle: Vec<usize>,
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |states: &[usize]| {
+            states
+                .iter()
+                .map(|state| format!("s{}", state))
+                .collect::<Vec<String>>()
+                .join(" → ")
+        };
+        match self.prefix.is_empty() {
+            true => write!(f, "({})ω", join(&self.cycle)),
+            false => write!(f, "{} → ({})ω", join(&self.prefix), join(&self.cycle)),
+        }
+    }
+}
+
+type Obligations = BTreeSet<LtlFormula>;
+
+#[derive(Clone, Default)]
+struct Expansion {
+    processed: BTreeSet<LtlFormula>,
+    next: Obligations,
+    postponed: BTreeSet<LtlFormula>,
+}
+
+type ProductState = (usize, Rc<Obligations>);
+
+impl KripkeStructure {
+    pub fn new(model: KripkeModel, initial: impl IntoIterator<Item = usize>) -> Self {
+        Self {
+            initial: initial.into_iter().collect(),
+            model,
+        }
+    }
+
+    pub fn check(&self, formula: &LtlFormula) -> Result<(), Counterexample> {
+        let negated = formula.not().nnf();
+        let eventualities = eventualities(&negated);
+        let start = Rc::new(Obligations::from([negated]));
+        let mut index = HashMap::<ProductState, usize>::new();
+        let mut states = vec![];
+        let mut edges = vec![];
+        let mut queue = VecDeque::new();
+        let mut roots = vec![];
+        for initial in &self.initial {
+            let state = (*initial, start.clone());
+            if !index.contains_key(&state) {
+                index.insert(state.clone(), states.len());
+                roots.push(states.len());
+                states.push(state.clone());
+                queue.push_back(state);
+            }
+        }
+        while let Some((world, obligations)) = queue.pop_front() {
+            let mut outgoing = vec![];
+            for expansion in self.expand(world, &obligations) {
+                let accepting = eventualities
+                    .iter()
+                    .map(|eventuality| !expansion.postponed.contains(eventuality))
+                    .collect::<Vec<bool>>();
+                let next = Rc::new(expansion.next);
+                for successor in &self.model.relation[world] {
+                    let state = (*successor, next.clone());
+                    let target = *index.entry(state.clone()).or_insert_with(|| {
+                        states.push(state.clone());
+                        queue.push_back(state);
+                        states.len() - 1
+                    });
+                    outgoing.push((target, accepting.clone()));
+                }
+            }
+            edges.push(outgoing);
+        }
+        let components = strongly_connected_components(&edges);
+        for component in &components {
+            let mut covered = vec![false; eventualities.len()];
+            let mut internal = false;
+            for node in component {
+                for (target, accepting) in &edges[*node] {
+                    if component.contains(target) {
+                        internal = true;
+                        for (covered, accepting) in covered.iter_mut().zip(accepting) {
+                            *covered |= accepting;
+                        }
+                    }
+                }
+            }
+            if internal && covered.iter().all(|covered| *covered) {
+                return Err(self.lasso(&states, &edges, &roots, component, eventualities.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn lasso(
+        &self,
+        states: &[ProductState],
+        edges: &[Vec<(usize, Vec<bool>)>],
+        roots: &[usize],
+        component: &BTreeSet<usize>,
+        eventualities: usize,
+    ) -> Counterexample {
+        let internal = |accepting: Option<usize>| {
+            component
+                .iter()
+                .flat_map(|node| edges[*node].iter().map(move |edge| (*node, edge)))
+                .find(|(_, (target, labels))| {
+                    component.contains(target) && accepting.is_none_or(|index| labels[index])
+                })
+                .map(|(node, (target, _))| (node, *target))
+                .unwrap()
+        };
+        let required = match eventualities {
+            0 => vec![internal(None)],
+            _ => (0..eventualities)
+                .map(|index| internal(Some(index)))
+                .collect(),
+        };
+        let entry = required[0].0;
+        let within = |node: usize| component.contains(&node);
+        let prefix = path(edges, roots, entry, |_| true);
+        let mut cycle = vec![];
+        let mut current = entry;
+        for (from, to) in required {
+            cycle.extend(path(edges, &[current], from, within));
+            current = to;
+        }
+        let back = path(edges, &[current], entry, within);
+        cycle.extend(&back[..back.len() - 1]);
+        let world = |node: &usize| states[*node].0;
+        Counterexample {
+            prefix: prefix[..prefix.len() - 1].iter().map(world).collect(),
+            cycle: cycle.iter().map(world).collect(),
+        }
+    }
+
+    fn expand(&self, world: usize, obligations: &Obligations) -> Vec<Expansion> {
+        let mut expansions = vec![];
+        self.expand_pending(
+            world,
+            obligations.iter().cloned().collect(),
+            Expansion::default(),
+            &mut expansions,
+        );
+        expansions
+    }
+
+    fn expand_pending(
+        &self,
+        world: usize,
+        mut pending: Vec<LtlFormula>,
+        mut expansion: Expansion,
+        expansions: &mut Vec<Expansion>,
+    ) {
+        let holds = |ident: &Ident| self.model.valuation[world].contains(ident);
+        while let Some(formula) = pending.pop() {
+            if !expansion.processed.insert(formula.clone()) {
+                continue;
+            }
+            let branches = match &*formula {
+                LtlNode::Variable(ident) => match holds(ident) {
+                    true => continue,
+                    false => return,
+                },
+                LtlNode::Not(p) => match &**p {
+                    LtlNode::Variable(ident) if holds(ident) => return,
+                    _ => continue,
+                },
+                LtlNode::And(p1, p2) => {
+                    pending.extend([p1.clone(), p2.clone()]);
+                    continue;
+                }
+                LtlNode::Next(p) => {
+                    expansion.next.insert(p.clone());
+                    continue;
+                }
+                LtlNode::Globally(p) => {
+                    pending.push(p.clone());
+                    expansion.next.insert(formula.clone());
+                    continue;
+                }
+                LtlNode::Or(p1, p2) => [(vec![p1.clone()], false), (vec![p2.clone()], false)],
+                LtlNode::Finally(p) => [(vec![p.clone()], false), (vec![], true)],
+                LtlNode::Until(p1, p2) => [(vec![p2.clone()], false), (vec![p1.clone()], true)],
+                LtlNode::Release(p1, p2) => [
+                    (vec![p1.clone(), p2.clone()], false),
+                    (vec![p2.clone()], true),
+                ],
+            };
+            for (extra, postpone) in branches {
+                let mut pending = pending.clone();
+                pending.extend(extra);
+                let mut expansion = expansion.clone();
+                if postpone {
+                    expansion.next.insert(formula.clone());
+                    expansion.postponed.insert(formula.clone());
+                }
+                self.expand_pending(world, pending, expansion, expansions);
+            }
+            return;
+        }
+        expansions.push(expansion);
+    }
+}
+
+fn eventualities(formula: &LtlFormula) -> Vec<LtlFormula> {
+    let mut found = BTreeSet::new();
+    let mut pending = vec![formula.clone()];
+    while let Some(formula) = pending.pop() {
+        match &*formula {
+            LtlNode::Variable(_) => (),
+            LtlNode::Not(p) | LtlNode::Next(p) | LtlNode::Globally(p) => pending.push(p.clone()),
+            LtlNode::And(p1, p2) | LtlNode::Or(p1, p2) | LtlNode::Release(p1, p2) => {
+                pending.extend([p1.clone(), p2.clone()])
+            }
+            LtlNode::Finally(p) => {
+                pending.push(p.clone());
+                found.insert(formula.clone());
+            }
+            LtlNode::Until(p1, p2) => {
+                pending.extend([p1.clone(), p2.clone()]);
+                found.insert(formula.clone());
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+fn path(
+    edges: &[Vec<(usize, Vec<bool>)>],
+    starts: &[usize],
+    goal: usize,
+    allowed: impl Fn(usize) -> bool,
+) -> Vec<usize> {
+    let mut parent = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        parent.entry(*start).or_insert(None);
+        queue.push_back(*start);
+    }
+    while let Some(node) = queue.pop_front() {
+        if node == goal {
+            let mut path = vec![node];
+            while let Some(Some(previous)) = parent.get(path.last().unwrap()) {
+                path.push(*previous);
+            }
+            path.reverse();
+            return path;
+        }
+        for (target, _) in &edges[node] {
+            if allowed(*target) && !parent.contains_key(target) {
+                parent.insert(*target, Some(node));
+                queue.push_back(*target);
+            }
+        }
+    }
+    unreachable!()
+}
+
+fn strongly_connected_components(edges: &[Vec<(usize, Vec<bool>)>]) -> Vec<BTreeSet<usize>> {
+    let mut index = vec![usize::MAX; edges.len()];
+    let mut lowlink = vec![0; edges.len()];
+    let mut on_stack = vec![false; edges.len()];
+    let mut stack = vec![];
+    let mut components = vec![];
+    let mut counter = 0;
+    for root in 0..edges.len() {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        let mut frames = vec![(root, 0)];
+        while let Some((node, child)) = frames.pop() {
+            if child == 0 {
+                index[node] = counter;
+                lowlink[node] = counter;
+                counter += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+            if let Some((target, _)) = edges[node].get(child) {
+                frames.push((node, child + 1));
+                if index[*target] == usize::MAX {
+                    frames.push((*target, 0));
+                } else if on_stack[*target] {
+                    lowlink[node] = lowlink[node].min(index[*target]);
+                }
+                continue;
+            }
+            if lowlink[node] == index[node] {
+                let mut component = BTreeSet::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.insert(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+            if let Some((parent, _)) = frames.last() {
+                lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+            }
+        }
+    }
+    components
+}
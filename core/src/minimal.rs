@@ -0,0 +1,112 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    ast::{Ident, Model},
+    cdcl::CDCLSolver,
+    sat::{Clause, Literal, Polarity},
+};
+
+#[derive(Debug, Clone)]
+pub struct MinimalModels {
+    solver: CDCLSolver,
+    variables: BTreeSet<Ident>,
+    minimized: bool,
+}
+
+impl MinimalModels {
+    pub fn new(clauses: &[Clause]) -> Self {
+        Self::with_minimized(clauses, true)
+    }
+
+    pub fn maximal(clauses: &[Clause]) -> Self {
+        Self::with_minimized(clauses, false)
+    }
+
+    fn with_minimized(clauses: &[Clause], minimized: bool) -> Self {
+        Self {
+            solver: CDCLSolver::from(clauses.to_vec()),
+            variables: clauses
+                .iter()
+                .flat_map(|clause| clause.literals())
+                .map(|literal| literal.identifier())
+                .collect(),
+            minimized,
+        }
+    }
+
+    fn literal(&self, ident: Ident, value: bool) -> Literal {
+        Literal::new(
+            ident,
+            match value {
+                true => Polarity::Positive,
+                false => Polarity::Negative,
+            },
+        )
+    }
+
+    fn current_model(&self) -> Model {
+        let model = self.solver.model().unwrap();
+        self.variables
+            .iter()
+            .map(|ident| (*ident, model.get(*ident).unwrap_or(false)))
+            .collect()
+    }
+
+    fn minimize(&mut self, mut model: Model) -> Model {
+        loop {
+            let (shrinkable, fixed) = self
+                .variables
+                .iter()
+                .partition::<Vec<Ident>, _>(|ident| model.get(**ident) == Some(self.minimized));
+            if shrinkable.is_empty() {
+                return model;
+            }
+            let selector = Literal::new(self.solver.new_variable(), Polarity::Positive);
+            self.solver.add_clause(&Clause::new(
+                shrinkable
+                    .iter()
+                    .map(|ident| self.literal(*ident, !self.minimized))
+                    .chain([selector.not()]),
+            ));
+            let assumptions = fixed
+                .iter()
+                .map(|ident| self.literal(*ident, !self.minimized))
+                .chain([selector.clone()])
+                .collect::<Vec<_>>();
+            let improved = self.solver.solve_complete(&assumptions);
+            if improved {
+                model = self.current_model();
+            }
+            self.solver.add_clause(&Clause::new([selector.not()]));
+            if !improved {
+                return model;
+            }
+        }
+    }
+}
+
+impl Iterator for MinimalModels {
+    type Item = Model;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.solver.solve_complete(&[]) {
+            return None;
+        }
+        let model = self.minimize(self.current_model());
+        self.solver.add_clause(&Clause::new(
+            self.variables
+                .iter()
+                .filter(|ident| model.get(**ident) == Some(self.minimized))
+                .map(|ident| self.literal(*ident, !self.minimized)),
+        ));
+        Some(model)
+    }
+}
+
+pub fn minimal_model(clauses: &[Clause]) -> Option<Model> {
+    MinimalModels::new(clauses).next()
+}
+
+pub fn maximal_model(clauses: &[Clause]) -> Option<Model> {
+    MinimalModels::maximal(clauses).next()
+}
@@ -0,0 +1,471 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{
+    analysis,
+    ast::{AbstractSyntaxTree, Ident, Model, Node, AST},
+    budget,
+    cdcl::{CDCLSolver, SolverConfig},
+    sat::{Clause, Literal, Polarity},
+};
+
+const SEARCH_LIMIT: usize = 100_000;
+const COUNTEREXAMPLES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Implicant {
+    pub care: u64,
+    pub value: u64,
+}
+
+impl fmt::Display for Implicant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = 64 - self.care.leading_zeros() as usize;
+        let digits = (0..width)
+            .rev()
+            .map(|var| match (self.care >> var & 1, self.value >> var & 1) {
+                (0, _) => '-',
+                (_, 0) => '0',
+                _ => '1',
+            })
+            .collect::<String>();
+        match digits.is_empty() {
+            true => write!(f, "-"),
+            false => write!(f, "{}", digits),
+        }
+    }
+}
+
+impl Implicant {
+    pub fn minterm(minterm: u64, variables: usize) -> Self {
+        let care = mask(variables);
+        Self {
+            care,
+            value: minterm & care,
+        }
+    }
+
+    pub fn covers(&self, minterm: u64) -> bool {
+        minterm & self.care == self.value
+    }
+
+    pub fn literals(&self) -> usize {
+        self.care.count_ones() as usize
+    }
+
+    pub fn to_ast(&self) -> Option<AST> {
+        self.to_ast_over(&(0..64).collect::<Vec<_>>())
+    }
+
+    pub fn to_ast_over(&self, variables: &[Ident]) -> Option<AST> {
+        variables
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.care >> index & 1 == 1)
+            .map(|(index, ident)| {
+                let literal = <AST as AbstractSyntaxTree>::variable(*ident);
+                match self.value >> index & 1 == 1 {
+                    true => literal,
+                    false => literal.not(),
+                }
+            })
+            .reduce(|product, literal| product.and(literal))
+    }
+
+    fn merge(&self, other: &Implicant) -> Option<Implicant> {
+        let difference = self.value ^ other.value;
+        (self.care == other.care && difference.count_ones() == 1).then_some(Implicant {
+            care: self.care & !difference,
+            value: self.value & !difference,
+        })
+    }
+}
+
+pub fn prime_implicants(on: &[u64], dont_care: &[u64], variables: usize) -> Vec<Implicant> {
+    let mut current = on
+        .iter()
+        .chain(dont_care)
+        .map(|minterm| Implicant::minterm(*minterm, variables))
+        .collect::<BTreeSet<_>>();
+    let mut primes = BTreeSet::new();
+    while !current.is_empty() {
+        let terms = current.iter().copied().collect::<Vec<_>>();
+        let mut merged = vec![false; terms.len()];
+        let mut next = BTreeSet::new();
+        for (index, first) in terms.iter().enumerate() {
+            for (offset, second) in terms[index + 1..].iter().enumerate() {
+                if let Some(implicant) = first.merge(second) {
+                    next.insert(implicant);
+                    merged[index] = true;
+                    merged[index + 1 + offset] = true;
+                }
+            }
+        }
+        primes.extend(
+            terms
+                .iter()
+                .zip(merged)
+                .filter(|(_, merged)| !merged)
+                .map(|(implicant, _)| *implicant),
+        );
+        current = next;
+    }
+    primes.into_iter().collect()
+}
+
+pub fn minimize(on: &[u64], dont_care: &[u64], variables: usize) -> Vec<Implicant> {
+    let primes = prime_implicants(on, dont_care, variables);
+    let minterms = on.iter().copied().collect::<BTreeSet<_>>();
+    let mut chosen = vec![];
+    let mut uncovered = minterms.clone();
+    for minterm in &minterms {
+        let mut covering = primes.iter().filter(|prime| prime.covers(*minterm));
+        if let (Some(essential), None) = (covering.next(), covering.next()) {
+            if !chosen.contains(essential) {
+                chosen.push(*essential);
+                uncovered.retain(|minterm| !essential.covers(*minterm));
+            }
+        }
+    }
+    let candidates = primes
+        .iter()
+        .filter(|prime| !chosen.contains(prime))
+        .copied()
+        .collect::<Vec<_>>();
+    let mut best = greedy_cover(&candidates, &uncovered);
+    let mut search = CoverSearch {
+        candidates: &candidates,
+        nodes: 0,
+    };
+    search.branch(&uncovered, &mut vec![], &mut best);
+    chosen.extend(best);
+    chosen.sort();
+    chosen
+}
+
+pub fn minimize_truth_table(bits: &[bool], variables: usize) -> Option<Vec<Implicant>> {
+    let rows = u32::try_from(variables)
+        .ok()
+        .and_then(|variables| 1usize.checked_shl(variables))?;
+    if bits.len() != rows {
+        return None;
+    }
+    let on = (0..bits.len() as u64)
+        .filter(|minterm| bits[*minterm as usize])
+        .collect::<Vec<_>>();
+    Some(minimize(&on, &[], variables))
+}
+
+pub fn sum_of_products(implicants: &[Implicant], variables: &[Ident]) -> Option<AST> {
+    let mut terms = implicants
+        .iter()
+        .map(|implicant| implicant.to_ast_over(variables));
+    let first = terms.next()?;
+    terms.try_fold(first?, |sum, term| Some(sum.or(term?)))
+}
+
+pub fn synthesize(bits: &[bool], variables: &[Ident]) -> Option<AST> {
+    let implicants = minimize_truth_table(bits, variables.len())?;
+    Some(synthesize_cover(&implicants, variables))
+}
+
+pub(crate) fn synthesize_cover(implicants: &[Implicant], variables: &[Ident]) -> AST {
+    match sum_of_products(implicants, variables) {
+        Some(sum) => sum,
+        None => {
+            let var =
+                <AST as AbstractSyntaxTree>::variable(variables.first().copied().unwrap_or(0));
+            match implicants.is_empty() {
+                true => var.and(var.not()),
+                false => var.or(var.not()),
+            }
+        }
+    }
+}
+
+pub fn minimize_equivalent(formula: &AST, max_size: usize) -> AST {
+    let mut variables = analysis::support(formula).into_iter().collect::<Vec<_>>();
+    if variables.is_empty() {
+        variables.extend(formula.variables().first());
+    }
+    let mut samples = vec![formula
+        .variables()
+        .into_iter()
+        .map(|ident| (ident, false))
+        .collect::<Model>()];
+    let lower = variables.len() + variables.len().saturating_sub(1).div_ceil(2);
+    let upper = max_size.min(budget::dag_size(formula, max_size + 1).saturating_sub(1));
+    for size in lower..=upper {
+        let mut synthesis = Synthesis::new(&variables, size - variables.len());
+        let mut added = 0;
+        loop {
+            for sample in &samples[added..] {
+                synthesis.add_sample(sample, formula.verify_model(sample));
+            }
+            added = samples.len();
+            let Some(candidate) = synthesis.solve() else {
+                break;
+            };
+            let counterexamples = analysis::witnesses(formula, &candidate, COUNTEREXAMPLES);
+            if counterexamples.is_empty() {
+                return candidate;
+            }
+            samples.extend(counterexamples);
+        }
+    }
+    formula.clone()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Not(usize),
+    And(usize, usize),
+    Or(usize, usize),
+    Nand(usize, usize),
+    Nor(usize, usize),
+    Ite(usize, usize, usize),
+}
+
+impl Operation {
+    fn operands(&self) -> Vec<usize> {
+        match *self {
+            Operation::Not(input) => vec![input],
+            Operation::And(lhs, rhs)
+            | Operation::Or(lhs, rhs)
+            | Operation::Nand(lhs, rhs)
+            | Operation::Nor(lhs, rhs) => vec![lhs, rhs],
+            Operation::Ite(condition, then, otherwise) => vec![condition, then, otherwise],
+        }
+    }
+}
+
+struct Synthesis<'a> {
+    variables: &'a [Ident],
+    solver: CDCLSolver,
+    choices: Vec<Vec<(Literal, Operation)>>,
+}
+
+impl<'a> Synthesis<'a> {
+    fn new(variables: &'a [Ident], gates: usize) -> Self {
+        let mut solver = CDCLSolver::new(SolverConfig::default());
+        let mut choices = vec![];
+        for node in variables.len()..variables.len() + gates {
+            let mut operations = (0..node).map(Operation::Not).collect::<Vec<_>>();
+            for first in 0..node {
+                for second in first + 1..node {
+                    operations.extend([
+                        Operation::And(first, second),
+                        Operation::Or(first, second),
+                        Operation::Nand(first, second),
+                        Operation::Nor(first, second),
+                    ]);
+                }
+            }
+            for condition in 0..node {
+                for then in (0..node).filter(|then| *then != condition) {
+                    for otherwise in (0..node).filter(|other| *other != condition && *other != then)
+                    {
+                        operations.push(Operation::Ite(condition, then, otherwise));
+                    }
+                }
+            }
+            let operations = operations
+                .into_iter()
+                .map(|operation| {
+                    let selector = Literal::new(solver.new_variable(), Polarity::Positive);
+                    (selector, operation)
+                })
+                .collect::<Vec<_>>();
+            solver.add_clause(
+                &operations
+                    .iter()
+                    .map(|(selector, _)| selector.clone())
+                    .collect(),
+            );
+            choices.push(operations);
+        }
+        for node in 0..(variables.len() + gates).saturating_sub(1) {
+            solver.add_clause(
+                &choices
+                    .iter()
+                    .flatten()
+                    .filter(|(_, operation)| operation.operands().contains(&node))
+                    .map(|(selector, _)| selector.clone())
+                    .collect(),
+            );
+        }
+        Self {
+            variables,
+            solver,
+            choices,
+        }
+    }
+
+    fn add_sample(&mut self, sample: &Model, value: bool) {
+        let constant = |literal: Literal, value: bool| match value {
+            true => Clause::new([literal]),
+            false => Clause::new([literal.not()]),
+        };
+        let values = (0..self.variables.len() + self.choices.len())
+            .map(|_| Literal::new(self.solver.new_variable(), Polarity::Positive))
+            .collect::<Vec<_>>();
+        let mut clauses = self
+            .variables
+            .iter()
+            .zip(&values)
+            .map(|(ident, literal)| constant(literal.clone(), sample.get(*ident).unwrap_or(false)))
+            .collect::<Vec<_>>();
+        for (gate, operations) in self.choices.iter().enumerate() {
+            let output = &values[self.variables.len() + gate];
+            for (selector, operation) in operations {
+                let and = |output: Literal, lhs: Literal, rhs: Literal| {
+                    vec![
+                        vec![output.not(), lhs.clone()],
+                        vec![output.not(), rhs.clone()],
+                        vec![output, lhs.not(), rhs.not()],
+                    ]
+                };
+                let constraints = match *operation {
+                    Operation::Not(input) => vec![
+                        vec![output.clone(), values[input].clone()],
+                        vec![output.not(), values[input].not()],
+                    ],
+                    Operation::And(lhs, rhs) => {
+                        and(output.clone(), values[lhs].clone(), values[rhs].clone())
+                    }
+                    Operation::Or(lhs, rhs) => {
+                        and(output.not(), values[lhs].not(), values[rhs].not())
+                    }
+                    Operation::Nand(lhs, rhs) => {
+                        and(output.not(), values[lhs].clone(), values[rhs].clone())
+                    }
+                    Operation::Nor(lhs, rhs) => {
+                        and(output.clone(), values[lhs].not(), values[rhs].not())
+                    }
+                    Operation::Ite(condition, then, otherwise) => {
+                        let (condition, then, otherwise) =
+                            (&values[condition], &values[then], &values[otherwise]);
+                        vec![
+                            vec![condition.not(), then.not(), output.clone()],
+                            vec![condition.not(), then.clone(), output.not()],
+                            vec![condition.clone(), otherwise.not(), output.clone()],
+                            vec![condition.clone(), otherwise.clone(), output.not()],
+                        ]
+                    }
+                };
+                clauses.extend(constraints.into_iter().map(|literals| {
+                    literals
+                        .into_iter()
+                        .chain([selector.not()])
+                        .collect::<Clause>()
+                }));
+            }
+        }
+        clauses.push(constant(values.last().unwrap().clone(), value));
+        for clause in &clauses {
+            self.solver.add_clause(clause);
+        }
+    }
+
+    fn solve(&mut self) -> Option<AST> {
+        if !self.solver.solve_complete(&[]) {
+            return None;
+        }
+        let model = self.solver.model()?;
+        let mut nodes = self
+            .variables
+            .iter()
+            .map(|ident| <AST as AbstractSyntaxTree>::variable(*ident))
+            .collect::<Vec<_>>();
+        for operations in &self.choices {
+            let (_, operation) = operations
+                .iter()
+                .find(|(selector, _)| model.get(selector.identifier()).unwrap_or(false))?;
+            let node = match *operation {
+                Operation::Not(input) => Node::Not(nodes[input].clone()),
+                Operation::And(lhs, rhs) => Node::And(nodes[lhs].clone(), nodes[rhs].clone()),
+                Operation::Or(lhs, rhs) => Node::Or(nodes[lhs].clone(), nodes[rhs].clone()),
+                Operation::Nand(lhs, rhs) => Node::Nand(nodes[lhs].clone(), nodes[rhs].clone()),
+                Operation::Nor(lhs, rhs) => Node::Nor(nodes[lhs].clone(), nodes[rhs].clone()),
+                Operation::Ite(condition, then, otherwise) => Node::Ite(
+                    nodes[condition].clone(),
+                    nodes[then].clone(),
+                    nodes[otherwise].clone(),
+                ),
+            };
+            nodes.push(AST::from_node(node));
+        }
+        nodes.pop()
+    }
+}
+
+fn greedy_cover(candidates: &[Implicant], uncovered: &BTreeSet<u64>) -> Vec<Implicant> {
+    let mut uncovered = uncovered.clone();
+    let mut cover = vec![];
+    while !uncovered.is_empty() {
+        let best = *candidates
+            .iter()
+            .max_by_key(|candidate| {
+                let covered = uncovered
+                    .iter()
+                    .filter(|minterm| candidate.covers(**minterm))
+                    .count();
+                (covered, usize::MAX - candidate.literals())
+            })
+            .unwrap();
+        uncovered.retain(|minterm| !best.covers(*minterm));
+        cover.push(best);
+    }
+    cover
+}
+
+struct CoverSearch<'a> {
+    candidates: &'a [Implicant],
+    nodes: usize,
+}
+
+impl CoverSearch<'_> {
+    fn branch(
+        &mut self,
+        uncovered: &BTreeSet<u64>,
+        cover: &mut Vec<Implicant>,
+        best: &mut Vec<Implicant>,
+    ) {
+        self.nodes += 1;
+        if self.nodes > SEARCH_LIMIT || cover.len() >= best.len() {
+            return;
+        }
+        let Some(minterm) = uncovered.iter().min_by_key(|minterm| {
+            self.candidates
+                .iter()
+                .filter(|candidate| candidate.covers(**minterm))
+                .count()
+        }) else {
+            *best = cover.clone();
+            return;
+        };
+        let mut covering = self
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.covers(*minterm))
+            .copied()
+            .collect::<Vec<_>>();
+        covering.sort_by_key(|candidate| candidate.literals());
+        for candidate in covering {
+            let remaining = uncovered
+                .iter()
+                .filter(|minterm| !candidate.covers(**minterm))
+                .copied()
+                .collect();
+            cover.push(candidate);
+            self.branch(&remaining, cover, best);
+            cover.pop();
+        }
+    }
+}
+
+fn mask(variables: usize) -> u64 {
+    match variables {
+        64.. => u64::MAX,
+        _ => (1 << variables) - 1,
+    }
+}
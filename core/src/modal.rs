@@ -0,0 +1,340 @@
+use std::{collections::BTreeSet, fmt, rc::Rc};
+
+use crate::ast::{ASTNode, Ident, AST};
+
+pub type ModalFormula = Rc<ModalNode>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ModalNode {
+    Variable(Ident),
+    Not(ModalFormula),
+    And(ModalFormula, ModalFormula),
+    Or(ModalFormula, ModalFormula),
+    Box(ModalFormula),
+    Diamond(ModalFormula),
+}
+
+impl fmt::Display for ModalNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModalNode::Variable(identifier) => write!(f, "var{}", identifier),
+            ModalNode::Not(p) => write!(f, "¬{}", p),
+            ModalNode::And(lhs, rhs) => write!(f, "({} ∧ {})", lhs, rhs),
+            ModalNode::Or(lhs, rhs) => write!(f, "({} ∨ {})", lhs, rhs),
+            ModalNode::Box(p) => write!(f, "□{}", p),
+            ModalNode::Diamond(p) => write!(f, "◇{}", p),
+        }
+    }
+}
+
+impl From<&AST> for ModalNode {
+    fn from(value: &AST) -> Self {
+        let lift = |p: &AST| Rc::new(Self::from(p));
+        match &**value {
+            ASTNode::Variable(ident) => ModalNode::Variable(*ident),
+            ASTNode::Not(p) => ModalNode::Not(lift(p)),
+            ASTNode::And(p1, p2) => ModalNode::And(lift(p1), lift(p2)),
+            ASTNode::Or(p1, p2) => ModalNode::Or(lift(p1), lift(p2)),
+            ASTNode::Nand(p1, p2) => ModalNode::Not(Rc::new(ModalNode::And(lift(p1), lift(p2)))),
+            ASTNode::Nor(p1, p2) => ModalNode::Not(Rc::new(ModalNode::Or(lift(p1), lift(p2)))),
+            ASTNode::Ite(p1, p2, p3) => ModalNode::Or(
+                Rc::new(ModalNode::And(lift(p1), lift(p2))),
+                Rc::new(ModalNode::And(Rc::new(ModalNode::Not(lift(p1))), lift(p3))),
+            ),
+        }
+    }
+}
+
+pub trait ModalLogic {
+    fn variable(ident: Ident) -> ModalFormula;
+    fn not(&self) -> ModalFormula;
+    fn and(&self, other: ModalFormula) -> ModalFormula;
+    fn or(&self, other: ModalFormula) -> ModalFormula;
+    fn necessarily(&self) -> ModalFormula;
+    fn possibly(&self) -> ModalFormula;
+    fn nnf(&self) -> ModalFormula;
+}
+
+impl ModalLogic for ModalFormula {
+    fn variable(ident: Ident) -> ModalFormula {
+        Rc::new(ModalNode::Variable(ident))
+    }
+
+    fn not(&self) -> ModalFormula {
+        Rc::new(ModalNode::Not(self.clone()))
+    }
+
+    fn and(&self, other: ModalFormula) -> ModalFormula {
+        Rc::new(ModalNode::And(self.clone(), other))
+    }
+
+    fn or(&self, other: ModalFormula) -> ModalFormula {
+        Rc::new(ModalNode::Or(self.clone(), other))
+    }
+
+    fn necessarily(&self) -> ModalFormula {
+        Rc::new(ModalNode::Box(self.clone()))
+    }
+
+    fn possibly(&self) -> ModalFormula {
+        Rc::new(ModalNode::Diamond(self.clone()))
+    }
+
+    fn nnf(&self) -> ModalFormula {
+        match &**self {
+            ModalNode::Variable(_) => self.clone(),
+            ModalNode::And(p1, p2) => p1.nnf().and(p2.nnf()),
+            ModalNode::Or(p1, p2) => p1.nnf().or(p2.nnf()),
+            ModalNode::Box(p) => p.nnf().necessarily(),
+            ModalNode::Diamond(p) => p.nnf().possibly(),
+            ModalNode::Not(p) => match &**p {
+                ModalNode::Variable(_) => self.clone(),
+                ModalNode::Not(q) => q.nnf(),
+                ModalNode::And(q1, q2) => q1.not().nnf().or(q2.not().nnf()),
+                ModalNode::Or(q1, q2) => q1.not().nnf().and(q2.not().nnf()),
+                ModalNode::Box(q) => q.not().nnf().possibly(),
+                ModalNode::Diamond(q) => q.not().nnf().necessarily(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KripkeModel {
+    pub valuation: Vec<BTreeSet<Ident>>,
+    pub relation: Vec<BTreeSet<usize>>,
+}
+
+impl KripkeModel {
+    pub fn new(worlds: usize) -> Self {
+        Self {
+            valuation: vec![BTreeSet::new(); worlds],
+            relation: vec![BTreeSet::new(); worlds],
+        }
+    }
+
+    pub fn worlds(&self) -> usize {
+        self.valuation.len()
+    }
+
+    pub fn set_true(&mut self, world: usize, ident: Ident) {
+        self.valuation[world].insert(ident);
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.relation[from].insert(to);
+    }
+
+    pub fn evaluate(&self, formula: &ModalFormula, world: usize) -> bool {
+        match &**formula {
+            ModalNode::Variable(ident) => self.valuation[world].contains(ident),
+            ModalNode::Not(p) => !self.evaluate(p, world),
+            ModalNode::And(p1, p2) => self.evaluate(p1, world) && self.evaluate(p2, world),
+            ModalNode::Or(p1, p2) => self.evaluate(p1, world) || self.evaluate(p2, world),
+            ModalNode::Box(p) => self.relation[world]
+                .iter()
+                .all(|successor| self.evaluate(p, *successor)),
+            ModalNode::Diamond(p) => self.relation[world]
+                .iter()
+                .any(|successor| self.evaluate(p, *successor)),
+        }
+    }
+
+    pub fn is_reflexive(&self) -> bool {
+        (0..self.worlds()).all(|world| self.relation[world].contains(&world))
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        (0..self.worlds()).all(|world| {
+            self.relation[world]
+                .iter()
+                .all(|successor| self.relation[*successor].contains(&world))
+        })
+    }
+
+    pub fn is_transitive(&self) -> bool {
+        (0..self.worlds()).all(|world| {
+            self.relation[world].iter().all(|successor| {
+                self.relation[*successor]
+                    .iter()
+                    .all(|next| self.relation[world].contains(next))
+            })
+        })
+    }
+
+    pub fn satisfies_frame_conditions(&self, system: ModalSystem) -> bool {
+        match system {
+            ModalSystem::K => true,
+            ModalSystem::T => self.is_reflexive(),
+            ModalSystem::S4 => self.is_reflexive() && self.is_transitive(),
+            ModalSystem::S5 => self.is_reflexive() && self.is_transitive() && self.is_symmetric(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModalSystem {
+    K,
+    T,
+    S4,
+    S5,
+}
+
+type Label = BTreeSet<ModalFormula>;
+
+impl ModalSystem {
+    pub fn satisfiable(&self, formula: &ModalFormula) -> bool {
+        match self {
+            ModalSystem::S5 => Cluster::default().satisfiable(vec![vec![formula.nnf()]]),
+            _ => self.satisfiable_world(vec![formula.nnf()], &mut vec![]),
+        }
+    }
+
+    pub fn valid(&self, formula: &ModalFormula) -> bool {
+        !self.satisfiable(&formula.not())
+    }
+
+    fn reflexive(&self) -> bool {
+        !matches!(self, ModalSystem::K)
+    }
+
+    fn saturate(
+        &self,
+        mut pending: Vec<ModalFormula>,
+        mut label: Label,
+        found: &mut dyn FnMut(&Label) -> bool,
+    ) -> bool {
+        while let Some(formula) = pending.pop() {
+            if !label.insert(formula.clone()) {
+                continue;
+            }
+            match &*formula {
+                ModalNode::Variable(_) => {
+                    if label.contains(&formula.not()) {
+                        return false;
+                    }
+                }
+                ModalNode::Not(p) => {
+                    if label.contains(p) {
+                        return false;
+                    }
+                }
+                ModalNode::And(p1, p2) => {
+                    pending.push(p1.clone());
+                    pending.push(p2.clone());
+                }
+                ModalNode::Or(p1, p2) => {
+                    return [p1, p2].into_iter().any(|branch| {
+                        let mut pending = pending.clone();
+                        pending.push(branch.clone());
+                        self.saturate(pending, label.clone(), found)
+                    })
+                }
+                ModalNode::Box(p) => {
+                    if self.reflexive() {
+                        pending.push(p.clone());
+                    }
+                }
+                ModalNode::Diamond(_) => (),
+            }
+        }
+        found(&label)
+    }
+
+    fn satisfiable_world(&self, formulas: Vec<ModalFormula>, ancestors: &mut Vec<Label>) -> bool {
+        self.saturate(formulas, Label::new(), &mut |label| {
+            let boxes = label
+                .iter()
+                .filter_map(|formula| match &**formula {
+                    ModalNode::Box(p) => Some((formula.clone(), p.clone())),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            label.iter().all(|formula| {
+                let ModalNode::Diamond(p) = &**formula else {
+                    return true;
+                };
+                let mut successor = vec![p.clone()];
+                for (boxed, inner) in &boxes {
+                    successor.push(match self {
+                        ModalSystem::S4 | ModalSystem::S5 => boxed.clone(),
+                        ModalSystem::K | ModalSystem::T => inner.clone(),
+                    });
+                }
+                if *self == ModalSystem::S4
+                    && ancestors
+                        .iter()
+                        .chain([label])
+                        .any(|ancestor| successor.iter().all(|f| ancestor.contains(f)))
+                {
+                    return true;
+                }
+                ancestors.push(label.clone());
+                let satisfiable = self.satisfiable_world(successor, ancestors);
+                ancestors.pop();
+                satisfiable
+            })
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+struct Cluster {
+    worlds: Vec<Label>,
+}
+
+impl Cluster {
+    fn satisfiable(&self, mut pending: Vec<Vec<ModalFormula>>) -> bool {
+        let Some(world) = pending.pop() else {
+            return self.witness_diamonds();
+        };
+        let mut globals = self.global_formulas();
+        globals.extend(world);
+        ModalSystem::S5.saturate(globals, Label::new(), &mut |label| {
+            let mut cluster = self.clone();
+            cluster.worlds.push(label.clone());
+            let globals = cluster.global_formulas();
+            if cluster
+                .worlds
+                .iter()
+                .all(|world| globals.iter().all(|formula| world.contains(formula)))
+            {
+                return cluster.satisfiable(pending.clone());
+            }
+            let restart = cluster
+                .worlds
+                .iter()
+                .map(|world| world.iter().cloned().collect())
+                .collect::<Vec<Vec<_>>>();
+            Cluster::default().satisfiable([pending.clone(), restart].concat())
+        })
+    }
+
+    fn global_formulas(&self) -> Vec<ModalFormula> {
+        self.worlds
+            .iter()
+            .flatten()
+            .filter_map(|formula| match &**formula {
+                ModalNode::Box(p) => Some(p.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn witness_diamonds(&self) -> bool {
+        let missing = self
+            .worlds
+            .iter()
+            .flatten()
+            .find_map(|formula| match &**formula {
+                ModalNode::Diamond(p) if !self.worlds.iter().any(|world| world.contains(p)) => {
+                    Some(p.clone())
+                }
+                _ => None,
+            });
+        match missing {
+            None => true,
+            Some(p) => self.satisfiable(vec![vec![p]]),
+        }
+    }
+}
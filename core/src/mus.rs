@@ -0,0 +1,170 @@
+use crate::{
+    cdcl::CDCLSolver,
+    cnf::{CnfFormula, GroupId, GroupSolver},
+    sat::{Clause, Literal, Polarity},
+};
+
+fn grouped(groups: &[Vec<Clause>]) -> CnfFormula {
+    let mut formula = CnfFormula::new();
+    for (group, clauses) in groups.iter().enumerate() {
+        formula.add_group(group);
+        for clause in clauses {
+            formula.add_group_clause(group, clause.clone());
+        }
+    }
+    formula
+}
+
+fn unsatisfiable_core(solver: &mut GroupSolver, groups: &[GroupId]) -> Option<Vec<GroupId>> {
+    if solver.solve_groups(groups) {
+        return None;
+    }
+    let core = solver.core();
+    Some(
+        groups
+            .iter()
+            .copied()
+            .filter(|group| core.contains(group))
+            .collect(),
+    )
+}
+
+fn shrink(solver: &mut GroupSolver, mut candidates: Vec<GroupId>) -> Vec<GroupId> {
+    let mut position = 0;
+    while position < candidates.len() {
+        let mut trial = candidates.clone();
+        trial.remove(position);
+        match unsatisfiable_core(solver, &trial) {
+            Some(core) => candidates = core,
+            None => position += 1,
+        }
+    }
+    candidates
+}
+
+fn grow(solver: &mut GroupSolver, universe: &[GroupId], mut seed: Vec<GroupId>) -> Vec<GroupId> {
+    for group in universe {
+        if seed.contains(group) {
+            continue;
+        }
+        seed.push(*group);
+        if unsatisfiable_core(solver, &seed).is_some() {
+            seed.pop();
+        }
+    }
+    seed.sort();
+    seed
+}
+
+pub fn minimal_unsatisfiable_groups(formula: &CnfFormula) -> Option<Vec<GroupId>> {
+    let mut solver = formula.solver();
+    let universe = formula.groups().into_iter().collect::<Vec<_>>();
+    let core = unsatisfiable_core(&mut solver, &universe)?;
+    Some(shrink(&mut solver, core))
+}
+
+pub fn minimal_unsatisfiable_subset(groups: &[Vec<Clause>]) -> Option<Vec<usize>> {
+    minimal_unsatisfiable_groups(&grouped(groups))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarcoSubset {
+    MaximalSatisfiable(Vec<GroupId>),
+    MinimalUnsatisfiable(Vec<GroupId>),
+}
+
+pub struct Marco {
+    solver: GroupSolver,
+    universe: Vec<GroupId>,
+    map: CDCLSolver,
+}
+
+impl From<&CnfFormula> for Marco {
+    fn from(value: &CnfFormula) -> Self {
+        let universe = value.groups().into_iter().collect::<Vec<_>>();
+        let mut map = CDCLSolver::default();
+        for _ in &universe {
+            map.new_variable();
+        }
+        Self {
+            solver: value.solver(),
+            universe,
+            map,
+        }
+    }
+}
+
+impl Marco {
+    pub fn new(groups: &[Vec<Clause>]) -> Self {
+        Self::from(&grouped(groups))
+    }
+
+    fn selector(&self, group: &GroupId, polarity: Polarity) -> Literal {
+        let index = self.universe.binary_search(group).unwrap();
+        Literal::new(index as u32, polarity)
+    }
+}
+
+impl Iterator for Marco {
+    type Item = MarcoSubset;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.map.solve_complete(&[]) {
+            return None;
+        }
+        let model = self.map.model().unwrap();
+        let seed = self
+            .universe
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| model.get(*index as u32) == Some(true))
+            .map(|(_, group)| *group)
+            .collect::<Vec<_>>();
+        match unsatisfiable_core(&mut self.solver, &seed) {
+            None => {
+                let satisfiable = grow(&mut self.solver, &self.universe, seed);
+                let blocking = complement(&self.universe, &satisfiable)
+                    .iter()
+                    .map(|group| self.selector(group, Polarity::Positive))
+                    .collect::<Vec<_>>();
+                self.map.add_clause(&Clause::new(blocking));
+                Some(MarcoSubset::MaximalSatisfiable(satisfiable))
+            }
+            Some(core) => {
+                let mut unsatisfiable = shrink(&mut self.solver, core);
+                unsatisfiable.sort();
+                let blocking = unsatisfiable
+                    .iter()
+                    .map(|group| self.selector(group, Polarity::Negative))
+                    .collect::<Vec<_>>();
+                self.map.add_clause(&Clause::new(blocking));
+                Some(MarcoSubset::MinimalUnsatisfiable(unsatisfiable))
+            }
+        }
+    }
+}
+
+pub fn maximal_satisfiable_subsets(groups: &[Vec<Clause>]) -> Vec<Vec<usize>> {
+    Marco::new(groups)
+        .filter_map(|subset| match subset {
+            MarcoSubset::MaximalSatisfiable(satisfiable) => Some(satisfiable),
+            MarcoSubset::MinimalUnsatisfiable(_) => None,
+        })
+        .collect()
+}
+
+pub fn minimal_correction_sets(groups: &[Vec<Clause>]) -> Vec<Vec<usize>> {
+    let universe = (0..groups.len()).collect::<Vec<_>>();
+    maximal_satisfiable_subsets(groups)
+        .iter()
+        .map(|satisfiable| complement(&universe, satisfiable))
+        .collect()
+}
+
+fn complement(universe: &[GroupId], subset: &[GroupId]) -> Vec<GroupId> {
+    universe
+        .iter()
+        .filter(|group| !subset.contains(group))
+        .copied()
+        .collect()
+}
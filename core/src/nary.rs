@@ -0,0 +1,254 @@
+use std::{collections::HashMap, fmt, rc::Rc};
+
+use crate::{
+    analysis::Residual,
+    ast::{AbstractSyntaxTree, Ident, Node, AST},
+    sat::{Clause, Literal, Polarity},
+};
+
+pub type NaryFormula = Rc<NaryNode>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NaryNode {
+    Variable(Ident),
+    Not(NaryFormula),
+    And(Vec<NaryFormula>),
+    Or(Vec<NaryFormula>),
+    Nand(NaryFormula, NaryFormula),
+    Nor(NaryFormula, NaryFormula),
+    Ite(NaryFormula, NaryFormula, NaryFormula),
+}
+
+impl fmt::Display for NaryNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |operands: &[NaryFormula], connective: &str| {
+            operands
+                .iter()
+                .map(|operand| operand.to_string())
+                .collect::<Vec<String>>()
+                .join(connective)
+        };
+        match self {
+            NaryNode::Variable(identifier) => write!(f, "var{}", identifier),
+            NaryNode::Not(p) => write!(f, "¬{}", p),
+            NaryNode::And(operands) if operands.is_empty() => write!(f, "⊤"),
+            NaryNode::Or(operands) if operands.is_empty() => write!(f, "⊥"),
+            NaryNode::And(operands) => write!(f, "({})", join(operands, " ∧ ")),
+            NaryNode::Or(operands) => write!(f, "({})", join(operands, " ∨ ")),
+            NaryNode::Nand(lhs, rhs) => write!(f, "({} ↑ {})", lhs, rhs),
+            NaryNode::Nor(lhs, rhs) => write!(f, "({} ↓ {})", lhs, rhs),
+            NaryNode::Ite(p1, p2, p3) => write!(f, "({} ? {} : {})", p1, p2, p3),
+        }
+    }
+}
+
+impl<T: AbstractSyntaxTree> From<&T> for NaryNode {
+    fn from(value: &T) -> Self {
+        Rc::unwrap_or_clone(lift(value, &mut HashMap::new()))
+    }
+}
+
+fn lift<T: AbstractSyntaxTree>(formula: &T, memo: &mut HashMap<usize, NaryFormula>) -> NaryFormula {
+    if let Some(lifted) = memo.get(&formula.key()) {
+        return lifted.clone();
+    }
+    let mut recurse = |p: &T| lift(p, memo);
+    let lifted = match formula.node() {
+        Node::Variable(ident) => NaryFormula::variable(ident),
+        Node::Not(p) => recurse(&p).not(),
+        Node::And(p1, p2) => NaryFormula::conjunction([recurse(&p1), recurse(&p2)]),
+        Node::Or(p1, p2) => NaryFormula::disjunction([recurse(&p1), recurse(&p2)]),
+        Node::Nand(p1, p2) => Rc::new(NaryNode::Nand(recurse(&p1), recurse(&p2))),
+        Node::Nor(p1, p2) => Rc::new(NaryNode::Nor(recurse(&p1), recurse(&p2))),
+        Node::Ite(p1, p2, p3) => Rc::new(NaryNode::Ite(recurse(&p1), recurse(&p2), recurse(&p3))),
+    };
+    memo.insert(formula.key(), lifted.clone());
+    lifted
+}
+
+pub trait NaryTree {
+    fn variable(ident: Ident) -> NaryFormula;
+    fn conjunction(operands: impl IntoIterator<Item = NaryFormula>) -> NaryFormula;
+    fn disjunction(operands: impl IntoIterator<Item = NaryFormula>) -> NaryFormula;
+    fn not(&self) -> NaryFormula;
+    fn and(&self, other: NaryFormula) -> NaryFormula;
+    fn or(&self, other: NaryFormula) -> NaryFormula;
+    fn operands(&self) -> &[NaryFormula];
+    fn depth(&self) -> usize;
+    fn sorted(&self) -> NaryFormula;
+    fn clauses(&self) -> Option<Vec<Clause>>;
+    fn as_constant(&self) -> Option<bool>;
+    fn to_ast(&self) -> Option<AST>;
+}
+
+impl NaryTree for NaryFormula {
+    fn variable(ident: Ident) -> NaryFormula {
+        Rc::new(NaryNode::Variable(ident))
+    }
+
+    fn conjunction(operands: impl IntoIterator<Item = NaryFormula>) -> NaryFormula {
+        let mut flattened = vec![];
+        for operand in operands {
+            match &*operand {
+                NaryNode::And(nested) => flattened.extend(nested.iter().cloned()),
+                _ => flattened.push(operand),
+            }
+        }
+        match flattened.len() {
+            1 => flattened.pop().unwrap(),
+            _ => Rc::new(NaryNode::And(flattened)),
+        }
+    }
+
+    fn disjunction(operands: impl IntoIterator<Item = NaryFormula>) -> NaryFormula {
+        let mut flattened = vec![];
+        for operand in operands {
+            match &*operand {
+                NaryNode::Or(nested) => flattened.extend(nested.iter().cloned()),
+                _ => flattened.push(operand),
+            }
+        }
+        match flattened.len() {
+            1 => flattened.pop().unwrap(),
+            _ => Rc::new(NaryNode::Or(flattened)),
+        }
+    }
+
+    fn not(&self) -> NaryFormula {
+        Rc::new(NaryNode::Not(self.clone()))
+    }
+
+    fn and(&self, other: NaryFormula) -> NaryFormula {
+        NaryFormula::conjunction([self.clone(), other])
+    }
+
+    fn or(&self, other: NaryFormula) -> NaryFormula {
+        NaryFormula::disjunction([self.clone(), other])
+    }
+
+    fn operands(&self) -> &[NaryFormula] {
+        match &**self {
+            NaryNode::And(operands) | NaryNode::Or(operands) => operands,
+            _ => &[],
+        }
+    }
+
+    fn depth(&self) -> usize {
+        let children = match &**self {
+            NaryNode::Variable(_) => vec![],
+            NaryNode::Not(p) => vec![p],
+            NaryNode::And(operands) | NaryNode::Or(operands) => operands.iter().collect(),
+            NaryNode::Nand(p1, p2) | NaryNode::Nor(p1, p2) => vec![p1, p2],
+            NaryNode::Ite(p1, p2, p3) => vec![p1, p2, p3],
+        };
+        1 + children.into_iter().map(|p| p.depth()).max().unwrap_or(0)
+    }
+
+    fn sorted(&self) -> NaryFormula {
+        sorted(self, &mut HashMap::new())
+    }
+
+    fn clauses(&self) -> Option<Vec<Clause>> {
+        let literal = |p: &NaryFormula| match &**p {
+            NaryNode::Variable(ident) => Some(Literal::new(*ident, Polarity::Positive)),
+            NaryNode::Not(p) => match &**p {
+                NaryNode::Variable(ident) => Some(Literal::new(*ident, Polarity::Negative)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let clause = |p: &NaryFormula| match &**p {
+            NaryNode::Or(operands) => operands
+                .iter()
+                .map(literal)
+                .collect::<Option<Vec<_>>>()
+                .map(Clause::new),
+            _ => literal(p).map(|literal| Clause::new([literal])),
+        };
+        match &**self {
+            NaryNode::And(operands) => operands.iter().map(clause).collect(),
+            _ => clause(self).map(|clause| vec![clause]),
+        }
+    }
+
+    fn as_constant(&self) -> Option<bool> {
+        match lower(self, &mut HashMap::new()) {
+            Residual::Constant(value) => Some(value),
+            Residual::Formula(_) => None,
+        }
+    }
+
+    fn to_ast(&self) -> Option<AST> {
+        match lower(self, &mut HashMap::new()) {
+            Residual::Constant(_) => None,
+            Residual::Formula(formula) => Some(formula),
+        }
+    }
+}
+
+fn sorted(formula: &NaryFormula, memo: &mut HashMap<*const NaryNode, NaryFormula>) -> NaryFormula {
+    if let Some(sorted) = memo.get(&Rc::as_ptr(formula)) {
+        return sorted.clone();
+    }
+    let mut recurse = |p: &NaryFormula| sorted(p, memo);
+    let result = match &**formula {
+        NaryNode::Variable(_) => formula.clone(),
+        NaryNode::Not(p) => recurse(p).not(),
+        NaryNode::And(operands) | NaryNode::Or(operands) => {
+            let mut operands = operands.iter().map(&mut recurse).collect::<Vec<_>>();
+            operands.sort();
+            match &**formula {
+                NaryNode::And(_) => Rc::new(NaryNode::And(operands)),
+                _ => Rc::new(NaryNode::Or(operands)),
+            }
+        }
+        NaryNode::Nand(p1, p2) => {
+            let (p1, p2) = ordered(recurse(p1), recurse(p2));
+            Rc::new(NaryNode::Nand(p1, p2))
+        }
+        NaryNode::Nor(p1, p2) => {
+            let (p1, p2) = ordered(recurse(p1), recurse(p2));
+            Rc::new(NaryNode::Nor(p1, p2))
+        }
+        NaryNode::Ite(p1, p2, p3) => Rc::new(NaryNode::Ite(recurse(p1), recurse(p2), recurse(p3))),
+    };
+    memo.insert(Rc::as_ptr(formula), result.clone());
+    result
+}
+
+fn lower(formula: &NaryFormula, memo: &mut HashMap<*const NaryNode, Residual>) -> Residual {
+    if let Some(residual) = memo.get(&Rc::as_ptr(formula)) {
+        return residual.clone();
+    }
+    let mut recurse = |p: &NaryFormula| lower(p, memo);
+    let residual = match &**formula {
+        NaryNode::Variable(ident) => {
+            Residual::Formula(<AST as AbstractSyntaxTree>::variable(*ident))
+        }
+        NaryNode::Not(p) => recurse(p).not(),
+        NaryNode::And(operands) => operands
+            .iter()
+            .map(&mut recurse)
+            .fold(Residual::Constant(true), Residual::and),
+        NaryNode::Or(operands) => operands
+            .iter()
+            .map(&mut recurse)
+            .fold(Residual::Constant(false), Residual::or),
+        NaryNode::Nand(p1, p2) => recurse(p1).and(recurse(p2)).not(),
+        NaryNode::Nor(p1, p2) => recurse(p1).or(recurse(p2)).not(),
+        NaryNode::Ite(p1, p2, p3) => match recurse(p1) {
+            Residual::Constant(true) => recurse(p2),
+            Residual::Constant(false) => recurse(p3),
+            Residual::Formula(condition) => Residual::ite(condition, recurse(p2), recurse(p3)),
+        },
+    };
+    memo.insert(Rc::as_ptr(formula), residual.clone());
+    residual
+}
+
+fn ordered(lhs: NaryFormula, rhs: NaryFormula) -> (NaryFormula, NaryFormula) {
+    match lhs <= rhs {
+        true => (lhs, rhs),
+        false => (rhs, lhs),
+    }
+}
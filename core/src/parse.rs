@@ -0,0 +1,257 @@
+use std::{collections::HashMap, iter::Peekable, str::Chars};
+
+use crate::ast::{AbstractSyntaxTree, Ident, ParseError, AST};
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    idents: HashMap<String, Ident>,
+    names: Vec<String>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &str) -> Ident {
+        if let Some(ident) = self.idents.get(name) {
+            return *ident;
+        }
+        let ident = self.names.len() as Ident;
+        self.idents.insert(name.to_owned(), ident);
+        self.names.push(name.to_owned());
+        ident
+    }
+
+    pub fn ident(&self, name: &str) -> Option<Ident> {
+        self.idents.get(name).copied()
+    }
+
+    pub fn name(&self, ident: Ident) -> Option<&str> {
+        self.names.get(ident as usize).map(|name| name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Not,
+    And,
+    Or,
+    Nand,
+    Nor,
+    LeftParen,
+    RightParen,
+    Question,
+    Colon,
+    Identifier(String),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Not => "NOT".to_owned(),
+            Token::And => "AND".to_owned(),
+            Token::Or => "OR".to_owned(),
+            Token::Nand => "NAND".to_owned(),
+            Token::Nor => "NOR".to_owned(),
+            Token::LeftParen => "(".to_owned(),
+            Token::RightParen => ")".to_owned(),
+            Token::Question => "?".to_owned(),
+            Token::Colon => ":".to_owned(),
+            Token::Identifier(name) => name.clone(),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    let mut position = 0;
+    while let Some(&c) = chars.peek() {
+        let start = position;
+        let mut push = |token: Token, length: usize| {
+            tokens.push((token, start));
+            position += length;
+        };
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                position += 1;
+            }
+            '(' => {
+                chars.next();
+                push(Token::LeftParen, 1);
+            }
+            ')' => {
+                chars.next();
+                push(Token::RightParen, 1);
+            }
+            '?' => {
+                chars.next();
+                push(Token::Question, 1);
+            }
+            ':' => {
+                chars.next();
+                push(Token::Colon, 1);
+            }
+            '¬' | '!' => {
+                chars.next();
+                push(Token::Not, 1);
+            }
+            '∧' | '&' => {
+                chars.next();
+                push(Token::And, 1);
+            }
+            '∨' | '|' => {
+                chars.next();
+                push(Token::Or, 1);
+            }
+            '↑' => {
+                chars.next();
+                push(Token::Nand, 1);
+            }
+            '↓' => {
+                chars.next();
+                push(Token::Nor, 1);
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let word = read_word(&mut chars);
+                let length = word.chars().count();
+                let token = match word.as_str() {
+                    "NOT" => Token::Not,
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NAND" => Token::Nand,
+                    "NOR" => Token::Nor,
+                    _ => Token::Identifier(word),
+                };
+                push(token, length);
+            }
+            character => {
+                return Err(ParseError::UnexpectedCharacter {
+                    character,
+                    position,
+                })
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn read_word(chars: &mut Peekable<Chars>) -> String {
+    let mut word = String::new();
+    while let Some(&c) = chars.peek() {
+        if !(c.is_alphanumeric() || c == '_') {
+            break;
+        }
+        word.push(c);
+        chars.next();
+    }
+    word
+}
+
+struct Parser<'a> {
+    tokens: Peekable<std::vec::IntoIter<(Token, usize)>>,
+    symtab: &'a mut SymbolTable,
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Result<(Token, usize), ParseError> {
+        self.tokens
+            .next()
+            .ok_or(ParseError::UnexpectedEndOfInput { position: self.end })
+    }
+
+    fn next_if(&mut self, predicate: impl Fn(&Token) -> bool) -> Option<Token> {
+        self.tokens
+            .next_if(|(token, _)| predicate(token))
+            .map(|(token, _)| token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.next()? {
+            (token, _) if token == expected => Ok(()),
+            (token, position) => Err(unexpected(token, position)),
+        }
+    }
+
+    fn conditional(&mut self) -> Result<AST, ParseError> {
+        let condition = self.disjunction()?;
+        if self.next_if(|token| *token == Token::Question).is_none() {
+            return Ok(condition);
+        }
+        let then = self.conditional()?;
+        self.expect(Token::Colon)?;
+        Ok(condition.ite(then, self.conditional()?))
+    }
+
+    fn disjunction(&mut self) -> Result<AST, ParseError> {
+        let mut lhs = self.conjunction()?;
+        loop {
+            lhs = match self.next_if(|token| matches!(token, Token::Or | Token::Nor)) {
+                Some(Token::Or) => lhs.or(self.conjunction()?),
+                Some(_) => lhs.nor(self.conjunction()?),
+                None => return Ok(lhs),
+            };
+        }
+    }
+
+    fn conjunction(&mut self) -> Result<AST, ParseError> {
+        let mut lhs = self.unary()?;
+        loop {
+            lhs = match self.next_if(|token| matches!(token, Token::And | Token::Nand)) {
+                Some(Token::And) => lhs.and(self.unary()?),
+                Some(_) => lhs.nand(self.unary()?),
+                None => return Ok(lhs),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<AST, ParseError> {
+        match self.next()? {
+            (Token::Not, _) => Ok(self.unary()?.not()),
+            (Token::LeftParen, _) => {
+                let inner = self.conditional()?;
+                self.expect(Token::RightParen)?;
+                Ok(inner)
+            }
+            (Token::Identifier(name), _) => Ok(<AST as AbstractSyntaxTree>::variable(
+                self.symtab.intern(&name),
+            )),
+            (token, position) => Err(unexpected(token, position)),
+        }
+    }
+}
+
+fn unexpected(token: Token, position: usize) -> ParseError {
+    ParseError::UnexpectedToken {
+        token: token.describe(),
+        position,
+    }
+}
+
+pub fn parse(input: &str) -> Result<AST, ParseError> {
+    parse_with(input, &mut SymbolTable::new())
+}
+
+pub fn parse_with(input: &str, symtab: &mut SymbolTable) -> Result<AST, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?.into_iter().peekable(),
+        symtab,
+        end: input.chars().count(),
+    };
+    let ast = parser.conditional()?;
+    match parser.tokens.next() {
+        Some((token, position)) => Err(unexpected(token, position)),
+        None => Ok(ast),
+    }
+}
@@ -0,0 +1,336 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    analysis::Residual,
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    bmc::Trace,
+    cdcl::CDCLSolver,
+    sat::{Clause, Literal},
+    transition::TransitionSystem,
+    tseitin,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdrResult {
+    Safe { invariant: AST, frames: usize },
+    Unsafe(Trace),
+    Unknown { frames: usize },
+}
+
+impl fmt::Display for PdrResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdrResult::Safe { invariant, frames } => {
+                write!(f, "safe after {} frames, invariant {}", frames, invariant)
+            }
+            PdrResult::Unsafe(trace) => write!(f, "unsafe:\n{}", trace),
+            PdrResult::Unknown { frames } => write!(f, "unknown after {} frames", frames),
+        }
+    }
+}
+
+type Cube = Vec<Literal>;
+
+struct Obligation {
+    cube: Cube,
+    inputs: Model,
+    level: usize,
+    successor: Option<usize>,
+}
+
+struct Pdr<'a> {
+    system: &'a TransitionSystem,
+    inputs: Vec<Ident>,
+    primed: HashMap<Ident, Ident>,
+    solver: CDCLSolver,
+    initial: CDCLSolver,
+    init: Literal,
+    trans: Literal,
+    bad: Literal,
+    activations: Vec<Literal>,
+    frames: Vec<Vec<Cube>>,
+}
+
+pub fn pdr(system: &TransitionSystem, max_frames: usize) -> PdrResult {
+    Pdr::new(system).run(max_frames)
+}
+
+impl<'a> Pdr<'a> {
+    fn new(system: &'a TransitionSystem) -> Self {
+        let mut next = [&system.init, &system.trans, &system.bad]
+            .iter()
+            .flat_map(|formula| formula.variables())
+            .chain(
+                system
+                    .state
+                    .iter()
+                    .flat_map(|(current, next)| [*current, *next]),
+            )
+            .max()
+            .map_or(0, |ident| ident + 1);
+        let (init, trans, bad) = (
+            Literal::positive(next),
+            Literal::positive(next + 1),
+            Literal::positive(next + 2),
+        );
+        next += 3;
+        let mut encode = |formula: &AST| {
+            let (clauses, auxiliary) = tseitin::cnf_clauses_from(formula, next);
+            next = auxiliary.last().map_or(next, |ident| ident + 1);
+            clauses
+        };
+        let guarded =
+            |guard: &Literal, formula: &AST| guard.to_ast::<AST>().not().or(formula.clone());
+        let mut solver = CDCLSolver::default();
+        for clause in encode(&guarded(&trans, &system.trans))
+            .into_iter()
+            .chain(encode(&guarded(&init, &system.init)))
+            .chain(encode(&guarded(&bad, &system.bad)))
+        {
+            solver.add_clause(&clause);
+        }
+        let mut initial = CDCLSolver::default();
+        for clause in encode(&system.init) {
+            initial.add_clause(&clause);
+        }
+        Self {
+            system,
+            inputs: system.inputs().into_iter().collect(),
+            primed: system.state.iter().copied().collect(),
+            solver,
+            initial,
+            init,
+            trans,
+            bad,
+            activations: vec![],
+            frames: vec![vec![]],
+        }
+    }
+
+    fn run(&mut self, max_frames: usize) -> PdrResult {
+        let bad = self.bad.clone();
+        if self.query(0, None, std::slice::from_ref(&bad)) {
+            let (cube, inputs) = self.state();
+            return PdrResult::Unsafe(Trace {
+                steps: vec![step(&cube, inputs)],
+            });
+        }
+        self.push_frame();
+        while self.depth() <= max_frames {
+            let k = self.depth();
+            while self.query(k, None, std::slice::from_ref(&bad)) {
+                let (cube, inputs) = self.state();
+                if let Err(trace) = self.block(cube, inputs, k) {
+                    return PdrResult::Unsafe(trace);
+                }
+            }
+            self.push_frame();
+            if let Some(level) = self.propagate() {
+                return PdrResult::Safe {
+                    invariant: self.invariant(level),
+                    frames: self.depth(),
+                };
+            }
+        }
+        PdrResult::Unknown {
+            frames: self.depth(),
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.frames.len() - 1
+    }
+
+    fn push_frame(&mut self) {
+        let activation = Literal::positive(self.solver.new_variable());
+        self.activations.push(activation);
+        self.frames.push(vec![]);
+    }
+
+    fn assumptions(&self, level: usize) -> Vec<Literal> {
+        let mut assumptions = self.activations[level.saturating_sub(1)..].to_vec();
+        if level == 0 {
+            assumptions.push(self.init.clone());
+        }
+        assumptions
+    }
+
+    fn query(&mut self, level: usize, excluded: Option<&Cube>, extra: &[Literal]) -> bool {
+        let mut assumptions = self.assumptions(level);
+        let temporary = excluded.map(|cube| {
+            let temporary = Literal::positive(self.solver.new_variable());
+            self.solver.add_clause(&Clause::new(
+                cube.iter()
+                    .map(|literal| literal.not())
+                    .chain([temporary.not()]),
+            ));
+            temporary
+        });
+        assumptions.extend(temporary.clone());
+        assumptions.extend(extra.iter().cloned());
+        let satisfiable = self.solver.solve_complete(&assumptions);
+        if let Some(temporary) = temporary {
+            self.solver.add_clause(&Clause::new([temporary.not()]));
+        }
+        satisfiable
+    }
+
+    fn state(&self) -> (Cube, Model) {
+        let model = self.solver.model().cloned().unwrap_or_default();
+        let value = |ident: &Ident| model.get(*ident).unwrap_or(false);
+        let cube = self
+            .system
+            .state
+            .iter()
+            .map(|(current, _)| match value(current) {
+                true => Literal::positive(*current),
+                false => Literal::negative(*current),
+            })
+            .collect();
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|ident| (*ident, value(ident)))
+            .collect();
+        (cube, inputs)
+    }
+
+    fn prime(&self, cube: &Cube) -> Vec<Literal> {
+        cube.iter()
+            .map(|literal| {
+                Literal::new(
+                    self.primed[&literal.identifier()],
+                    literal.polarity().clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn transition(&self, primed: &[Literal]) -> Vec<Literal> {
+        [self.trans.clone()]
+            .into_iter()
+            .chain(primed.iter().cloned())
+            .collect()
+    }
+
+    fn is_initial(&mut self, cube: &Cube) -> bool {
+        self.initial.solve_complete(cube)
+    }
+
+    fn block(&mut self, cube: Cube, inputs: Model, level: usize) -> Result<(), Trace> {
+        if self.is_initial(&cube) {
+            return Err(Trace {
+                steps: vec![step(&cube, inputs)],
+            });
+        }
+        let mut obligations = vec![Obligation {
+            cube,
+            inputs,
+            level,
+            successor: None,
+        }];
+        let mut pending = vec![0];
+        while let Some(&index) = pending.last() {
+            let (cube, level) = (obligations[index].cube.clone(), obligations[index].level);
+            let primed = self.prime(&cube);
+            if self.query(level - 1, Some(&cube), &self.transition(&primed)) {
+                let (predecessor, inputs) = self.state();
+                let initial = self.is_initial(&predecessor);
+                obligations.push(Obligation {
+                    cube: predecessor,
+                    inputs,
+                    level: level - 1,
+                    successor: Some(index),
+                });
+                if initial {
+                    return Err(trace(&obligations));
+                }
+                pending.push(obligations.len() - 1);
+                continue;
+            }
+            let core = self.solver.unsat_core().to_vec();
+            let generalized = self.generalize(&cube, &primed, &core);
+            self.add_blocked(generalized, level);
+            pending.pop();
+        }
+        Ok(())
+    }
+
+    fn generalize(&mut self, cube: &Cube, primed: &[Literal], core: &[Literal]) -> Cube {
+        let mut generalized = cube
+            .iter()
+            .zip(primed)
+            .filter(|(_, primed)| core.contains(primed))
+            .map(|(literal, _)| literal.clone())
+            .collect::<Cube>();
+        for literal in cube {
+            if !self.is_initial(&generalized) {
+                break;
+            }
+            if !generalized.contains(literal) {
+                generalized.push(literal.clone());
+            }
+        }
+        generalized
+    }
+
+    fn add_blocked(&mut self, cube: Cube, level: usize) {
+        self.solver.add_clause(&Clause::new(
+            cube.iter()
+                .map(|literal| literal.not())
+                .chain([self.activations[level - 1].not()]),
+        ));
+        self.frames[level].push(cube);
+    }
+
+    fn propagate(&mut self) -> Option<usize> {
+        for level in 1..self.depth() {
+            for cube in std::mem::take(&mut self.frames[level]) {
+                let primed = self.transition(&self.prime(&cube));
+                match self.query(level, None, &primed) {
+                    true => self.frames[level].push(cube),
+                    false => self.add_blocked(cube, level + 1),
+                }
+            }
+            if self.frames[level].is_empty() {
+                return Some(level);
+            }
+        }
+        None
+    }
+
+    fn invariant(&self, level: usize) -> AST {
+        let within = <AST as AbstractSyntaxTree>::variable(
+            self.system.state.first().map_or(0, |(current, _)| *current),
+        );
+        self.frames[level..]
+            .iter()
+            .flatten()
+            .map(|cube| {
+                Clause::new(cube.iter().map(|literal| literal.not()))
+                    .to_ast::<AST>()
+                    .map_or(Residual::Constant(false), Residual::Formula)
+            })
+            .fold(Residual::Constant(true), Residual::and)
+            .into_ast(&within)
+    }
+}
+
+fn step(cube: &Cube, inputs: Model) -> Model {
+    let mut model = inputs;
+    for literal in cube {
+        model.set(literal.identifier(), literal.is_positive());
+    }
+    model
+}
+
+fn trace(obligations: &[Obligation]) -> Trace {
+    let mut steps = vec![];
+    let mut index = Some(obligations.len() - 1);
+    while let Some(current) = index {
+        let obligation = &obligations[current];
+        steps.push(step(&obligation.cube, obligation.inputs.clone()));
+        index = obligation.successor;
+    }
+    Trace { steps }
+}
@@ -0,0 +1,187 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    ast::{Ident, Model},
+    cdcl::CDCLSolver,
+    cnf::CnfFormula,
+    features::{features, InstanceFeatures},
+    implication::strongly_connected_components,
+    lookahead::Lookahead,
+    sat::{Clause, DPLLSolver, IncompleteSolver, Literal, Solver},
+    survey::SurveyPropagation,
+    walksat::WalkSat,
+};
+
+const DPLL_VARIABLES: usize = 40;
+const WALKSAT_VARIABLES: usize = 100;
+const WALKSAT_RATIO: f64 = 4.0;
+const SURVEY_VARIABLES: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    TwoSat,
+    Horn,
+    Dpll,
+    Cdcl,
+    Lookahead,
+    SurveyPropagation,
+    WalkSat,
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::TwoSat => write!(f, "2-sat"),
+            Algorithm::Horn => write!(f, "horn"),
+            Algorithm::Dpll => write!(f, "dpll"),
+            Algorithm::Cdcl => write!(f, "cdcl"),
+            Algorithm::Lookahead => write!(f, "lookahead"),
+            Algorithm::SurveyPropagation => write!(f, "survey-propagation"),
+            Algorithm::WalkSat => write!(f, "walksat"),
+        }
+    }
+}
+
+impl Algorithm {
+    pub fn applies_to(&self, features: &InstanceFeatures) -> bool {
+        match self {
+            Algorithm::TwoSat => features.clause_length.max <= 2,
+            Algorithm::Horn => features.horn_fraction == 1.0 || features.clauses == 0,
+            Algorithm::Dpll
+            | Algorithm::Cdcl
+            | Algorithm::Lookahead
+            | Algorithm::SurveyPropagation
+            | Algorithm::WalkSat => true,
+        }
+    }
+
+    fn run(&self, clauses: &[Clause]) -> Option<Model> {
+        match self {
+            Algorithm::TwoSat => two_sat(clauses),
+            Algorithm::Horn => horn(clauses),
+            Algorithm::Dpll => DPLLSolver::from(vec![]).solve(clauses),
+            Algorithm::Cdcl => CDCLSolver::default().solve(clauses),
+            Algorithm::Lookahead => Lookahead::default().solve(clauses),
+            Algorithm::SurveyPropagation => SurveyPropagation::default().solve(clauses),
+            Algorithm::WalkSat => {
+                let walksat = WalkSat {
+                    max_tries: 1,
+                    ..WalkSat::default()
+                };
+                walksat
+                    .try_solve_clauses(clauses)
+                    .unwrap_or_else(|_| Algorithm::Cdcl.run(clauses))
+            }
+        }
+    }
+}
+
+impl Solver for Algorithm {
+    fn solve(&self, clauses: &[Clause]) -> Option<Model> {
+        match self.applies_to(&features(&CnfFormula::from(clauses.to_vec()))) {
+            true => self.run(clauses),
+            false => Algorithm::Cdcl.run(clauses),
+        }
+    }
+}
+
+pub fn select(features: &InstanceFeatures) -> Algorithm {
+    let uniform = features.clause_length.min == features.clause_length.max;
+    if Algorithm::TwoSat.applies_to(features) {
+        Algorithm::TwoSat
+    } else if Algorithm::Horn.applies_to(features) {
+        Algorithm::Horn
+    } else if features.variables <= DPLL_VARIABLES {
+        Algorithm::Dpll
+    } else if uniform && features.variables >= WALKSAT_VARIABLES && features.ratio < WALKSAT_RATIO {
+        Algorithm::WalkSat
+    } else if uniform && features.variables >= SURVEY_VARIABLES {
+        Algorithm::SurveyPropagation
+    } else if uniform {
+        Algorithm::Lookahead
+    } else {
+        Algorithm::Cdcl
+    }
+}
+
+pub fn solve(clauses: &[Clause]) -> Option<Model> {
+    select(&features(&CnfFormula::from(clauses.to_vec()))).run(clauses)
+}
+
+fn dense(clauses: &[Clause]) -> BTreeMap<Ident, usize> {
+    let mut variables = BTreeMap::new();
+    for literal in clauses.iter().flat_map(|clause| clause.literals()) {
+        let next = variables.len();
+        variables.entry(literal.identifier()).or_insert(next);
+    }
+    variables
+}
+
+fn two_sat(clauses: &[Clause]) -> Option<Model> {
+    if clauses.iter().any(Clause::is_empty) {
+        return None;
+    }
+    let variables = dense(clauses);
+    let node =
+        |literal: &Literal| 2 * variables[&literal.identifier()] + !literal.is_positive() as usize;
+    let mut edges = vec![vec![]; 2 * variables.len()];
+    for clause in clauses {
+        let literals = clause.literals().map(node).collect::<Vec<_>>();
+        let (first, second) = match literals[..] {
+            [only] => (only, only),
+            [first, second] => (first, second),
+            _ => unreachable!(),
+        };
+        edges[first ^ 1].push(second);
+        edges[second ^ 1].push(first);
+    }
+    let components = strongly_connected_components(&edges);
+    let mut model = Model::new();
+    for (ident, index) in &variables {
+        let (positive, negative) = (components[2 * index], components[2 * index + 1]);
+        if positive == negative {
+            return None;
+        }
+        model.set(*ident, positive < negative);
+    }
+    Some(model)
+}
+
+fn horn(clauses: &[Clause]) -> Option<Model> {
+    let variables = dense(clauses);
+    let mut values = vec![false; variables.len()];
+    let mut remaining = vec![0; clauses.len()];
+    let mut watchers = vec![vec![]; variables.len()];
+    let mut queue = vec![];
+    for (origin, clause) in clauses.iter().enumerate() {
+        for literal in clause.literals().filter(|literal| !literal.is_positive()) {
+            remaining[origin] += 1;
+            watchers[variables[&literal.identifier()]].push(origin);
+        }
+        if remaining[origin] == 0 {
+            queue.push(origin);
+        }
+    }
+    while let Some(origin) = queue.pop() {
+        let head = clauses[origin]
+            .literals()
+            .find(|literal| literal.is_positive())?;
+        let variable = variables[&head.identifier()];
+        if values[variable] {
+            continue;
+        }
+        values[variable] = true;
+        for watcher in &watchers[variable] {
+            remaining[*watcher] -= 1;
+            if remaining[*watcher] == 0 {
+                queue.push(*watcher);
+            }
+        }
+    }
+    Some(
+        variables
+            .iter()
+            .map(|(ident, index)| (*ident, values[*index]))
+            .collect(),
+    )
+}
@@ -0,0 +1,342 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    ast::Model,
+    cdcl::CDCLSolver,
+    implication::ImplicationGraph,
+    sat::{Clause, Literal, Polarity, Solver},
+    trace::{trace_event, trace_span},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preprocessor {
+    pub unhiding: bool,
+    pub subsumption: bool,
+    pub self_subsumption: bool,
+    pub failed_literal_probing: bool,
+    pub equivalent_literals: bool,
+    pub pure_literals: bool,
+}
+
+impl Default for Preprocessor {
+    fn default() -> Self {
+        Self {
+            unhiding: true,
+            subsumption: true,
+            self_subsumption: true,
+            failed_literal_probing: true,
+            equivalent_literals: true,
+            pure_literals: true,
+        }
+    }
+}
+
+impl Solver for Preprocessor {
+    fn solve(&self, clauses: &[Clause]) -> Option<Model> {
+        Preprocessor::solve(self, clauses.to_vec())
+    }
+}
+
+impl Preprocessor {
+    pub fn preprocess(&self, mut clauses: Vec<Clause>) -> (Vec<Clause>, ReconstructionStack) {
+        trace_span!("preprocess");
+        if self.unhiding {
+            trace_span!("preprocess.unhiding");
+            unhide(&mut clauses);
+            trace_event!("preprocess.unhidden", clauses = clauses.len());
+        }
+        if self.failed_literal_probing {
+            trace_span!("preprocess.probing");
+            probe(&mut clauses);
+            trace_event!("preprocess.probed", clauses = clauses.len());
+        }
+        let mut stack = ReconstructionStack::default();
+        if self.equivalent_literals {
+            trace_span!("preprocess.equivalences");
+            substitute_equivalences(&mut clauses, &mut stack);
+            trace_event!(
+                "preprocess.substituted",
+                clauses = clauses.len(),
+                witnesses = stack.len()
+            );
+        }
+        let mut clauses = clauses.into_iter().map(Some).collect::<Vec<_>>();
+        {
+            trace_span!("preprocess.subsumption");
+            while self.eliminate_subsumed(&mut clauses) {}
+        }
+        let mut clauses: Vec<Clause> = clauses.into_iter().flatten().collect();
+        trace_event!("preprocess.subsumed", clauses = clauses.len());
+        if self.pure_literals {
+            trace_span!("preprocess.pure_literals");
+            eliminate_pure_literals(&mut clauses, &mut stack);
+            trace_event!(
+                "preprocess.eliminated",
+                clauses = clauses.len(),
+                witnesses = stack.len()
+            );
+        }
+        (clauses, stack)
+    }
+
+    pub fn solve(&self, clauses: Vec<Clause>) -> Option<Model> {
+        let variables = clauses
+            .iter()
+            .flat_map(|clause| clause.literals())
+            .map(|literal| literal.identifier())
+            .collect::<BTreeSet<_>>();
+        let (clauses, stack) = self.preprocess(clauses);
+        let mut solver = CDCLSolver::from(clauses);
+        if !solver.solve_complete(&[]) {
+            return None;
+        }
+        let mut model = solver.model().cloned().unwrap_or_default();
+        for ident in variables {
+            model.set_default(ident, false);
+        }
+        Some(stack.reconstruct(&model))
+    }
+
+    fn eliminate_subsumed(&self, clauses: &mut [Option<Clause>]) -> bool {
+        let mut occurrences = HashMap::<Literal, Vec<usize>>::new();
+        for (index, clause) in clauses.iter().enumerate() {
+            for literal in clause.iter().flat_map(|clause| clause.literals()) {
+                occurrences.entry(literal.clone()).or_default().push(index);
+            }
+        }
+        let mut order = (0..clauses.len())
+            .filter(|index| clauses[*index].is_some())
+            .collect::<Vec<_>>();
+        order.sort_by_key(|index| clauses[*index].as_ref().unwrap().len());
+        let mut changed = false;
+        for index in order {
+            let Some(clause) = clauses[index].clone() else {
+                continue;
+            };
+            if self.subsumption {
+                let Some(rarest) = clause
+                    .literals()
+                    .min_by_key(|literal| occurrences.get(*literal).map_or(0, Vec::len))
+                else {
+                    continue;
+                };
+                for &other in occurrences.get(rarest).into_iter().flatten() {
+                    if other == index {
+                        continue;
+                    }
+                    if let Some(candidate) = &clauses[other] {
+                        if clause.literals().all(|literal| candidate.contains(literal)) {
+                            clauses[other] = None;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            if self.self_subsumption {
+                for pivot in clause.literals() {
+                    let negated = pivot.not();
+                    if clause.contains(&negated) {
+                        continue;
+                    }
+                    for &other in occurrences.get(&negated).into_iter().flatten() {
+                        if other == index {
+                            continue;
+                        }
+                        let Some(candidate) = &clauses[other] else {
+                            continue;
+                        };
+                        if candidate.contains(&negated)
+                            && clause
+                                .literals()
+                                .all(|literal| literal == pivot || candidate.contains(literal))
+                        {
+                            clauses[other] = Some(Clause::new(
+                                candidate
+                                    .literals()
+                                    .filter(|literal| **literal != negated)
+                                    .cloned(),
+                            ));
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconstructionStack {
+    entries: Vec<(Literal, Clause)>,
+}
+
+impl ReconstructionStack {
+    pub fn push(&mut self, witness: Literal, clause: Clause) {
+        self.entries.push((witness, clause));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn reconstruct(&self, model: &Model) -> Model {
+        let mut model = model.clone();
+        for (_, clause) in &self.entries {
+            for literal in clause.literals() {
+                model.set_default(literal.identifier(), false);
+            }
+        }
+        for (witness, clause) in self.entries.iter().rev() {
+            let satisfied = clause.literals().any(|literal| {
+                model.get(literal.identifier()) == Some(*literal.polarity() == Polarity::Positive)
+            });
+            if !satisfied {
+                model.set(
+                    witness.identifier(),
+                    *witness.polarity() == Polarity::Positive,
+                );
+            }
+        }
+        model
+    }
+}
+
+fn eliminate_pure_literals(clauses: &mut Vec<Clause>, stack: &mut ReconstructionStack) {
+    loop {
+        let occurring = clauses
+            .iter()
+            .flat_map(|clause| clause.literals())
+            .cloned()
+            .collect::<HashSet<_>>();
+        let Some(pure) = occurring
+            .iter()
+            .filter(|literal| !occurring.contains(&literal.not()))
+            .min_by_key(|literal| literal.identifier())
+            .cloned()
+        else {
+            return;
+        };
+        clauses.retain(|clause| {
+            if clause.contains(&pure) {
+                stack.push(pure.clone(), clause.clone());
+                return false;
+            }
+            true
+        });
+    }
+}
+
+fn unhide(clauses: &mut Vec<Clause>) {
+    let graph = ImplicationGraph::from(clauses.as_slice()).transitive_reduction();
+    let failed = graph.failed_literals();
+    clauses.retain(|clause| match clause.len() {
+        2 => graph.contains(clause),
+        0 | 1 => true,
+        _ => !graph.is_hidden_tautology(clause),
+    });
+    for clause in clauses.iter_mut().filter(|clause| clause.len() > 2) {
+        *clause = graph.strengthen(clause);
+    }
+    for literal in failed {
+        clauses.push(Clause::new([literal.not()]));
+    }
+}
+
+fn substitute_equivalences(clauses: &mut Vec<Clause>, stack: &mut ReconstructionStack) {
+    loop {
+        let Some(representatives) = ImplicationGraph::from(clauses.as_slice()).representatives()
+        else {
+            clauses.push(Clause::new([]));
+            return;
+        };
+        if representatives.is_empty() {
+            return;
+        }
+        for (literal, representative) in &representatives {
+            if literal.is_positive() {
+                stack.push(
+                    literal.clone(),
+                    Clause::new([literal.clone(), representative.not()]),
+                );
+                stack.push(
+                    literal.not(),
+                    Clause::new([literal.not(), representative.clone()]),
+                );
+            }
+        }
+        let mut seen = HashSet::new();
+        *clauses = clauses
+            .drain(..)
+            .map(|clause| {
+                Clause::new(
+                    clause
+                        .literals()
+                        .map(|literal| representatives.get(literal).unwrap_or(literal).clone()),
+                )
+            })
+            .filter(|clause| {
+                !clause
+                    .literals()
+                    .any(|literal| clause.contains(&literal.not()))
+            })
+            .filter(|clause| seen.insert(clause.clone()))
+            .collect();
+    }
+}
+
+fn probe(clauses: &mut Vec<Clause>) {
+    let variables = clauses
+        .iter()
+        .flat_map(|clause| clause.literals())
+        .map(|literal| literal.identifier())
+        .collect::<BTreeSet<_>>();
+    for ident in variables {
+        let positive = propagate(clauses, Literal::new(ident, Polarity::Positive));
+        let negative = propagate(clauses, Literal::new(ident, Polarity::Negative));
+        let units = match (positive, negative) {
+            (None, None) => {
+                clauses.push(Clause::new([]));
+                return;
+            }
+            (None, Some(implied)) | (Some(implied), None) => implied,
+            (Some(positive), Some(negative)) => positive.intersection(&negative).cloned().collect(),
+        };
+        for unit in units {
+            let clause = Clause::new([unit]);
+            if !clauses.contains(&clause) {
+                clauses.push(clause);
+            }
+        }
+    }
+}
+
+fn propagate(clauses: &[Clause], assumption: Literal) -> Option<BTreeSet<Literal>> {
+    let mut assigned = BTreeSet::from([assumption]);
+    loop {
+        let mut changed = false;
+        for clause in clauses {
+            if clause.literals().any(|literal| assigned.contains(literal)) {
+                continue;
+            }
+            let mut open = clause
+                .literals()
+                .filter(|literal| !assigned.contains(&literal.not()));
+            match (open.next(), open.next()) {
+                (None, _) => return None,
+                (Some(unit), None) => {
+                    assigned.insert(unit.clone());
+                    changed = true;
+                }
+                _ => (),
+            }
+        }
+        if !changed {
+            return Some(assigned);
+        }
+    }
+}
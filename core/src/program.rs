@@ -0,0 +1,359 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, ParseError, AST},
+    context::Context,
+    parse::SymbolTable,
+    schema::implies,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProgramRule {
+    pub head: Option<Ident>,
+    pub positive: Vec<Ident>,
+    pub negative: Vec<Ident>,
+}
+
+impl fmt::Display for ProgramRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(head) = self.head {
+            write!(f, "var{}", head)?;
+        }
+        let body = self
+            .positive
+            .iter()
+            .map(|atom| format!("var{}", atom))
+            .chain(self.negative.iter().map(|atom| format!("not var{}", atom)))
+            .collect::<Vec<_>>();
+        match (self.head, body.is_empty()) {
+            (Some(_), true) => write!(f, "."),
+            (Some(_), false) => write!(f, " :- {}.", body.join(", ")),
+            (None, _) => write!(f, ":- {}.", body.join(", ")),
+        }
+    }
+}
+
+impl ProgramRule {
+    pub fn new(head: Ident, positive: Vec<Ident>, negative: Vec<Ident>) -> Self {
+        Self {
+            head: Some(head),
+            positive,
+            negative,
+        }
+    }
+
+    pub fn fact(head: Ident) -> Self {
+        Self::new(head, vec![], vec![])
+    }
+
+    pub fn constraint(positive: Vec<Ident>, negative: Vec<Ident>) -> Self {
+        Self {
+            head: None,
+            positive,
+            negative,
+        }
+    }
+
+    pub fn atoms(&self) -> impl Iterator<Item = Ident> + '_ {
+        self.head
+            .iter()
+            .chain(&self.positive)
+            .chain(&self.negative)
+            .copied()
+    }
+
+    fn body(&self) -> Option<AST> {
+        self.positive
+            .iter()
+            .map(|atom| atom_formula(*atom))
+            .chain(self.negative.iter().map(|atom| atom_formula(*atom).not()))
+            .reduce(|lhs, rhs| lhs.and(rhs))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LogicProgram {
+    pub rules: Vec<ProgramRule>,
+}
+
+impl fmt::Display for LogicProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<ProgramRule> for LogicProgram {
+    fn from_iter<I: IntoIterator<Item = ProgramRule>>(iter: I) -> Self {
+        Self {
+            rules: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl LogicProgram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(input: &str, symtab: &mut SymbolTable) -> Result<Self, ParseError> {
+        let tokens = tokenize(input)?;
+        let end = input.chars().count();
+        let mut rules = vec![];
+        let mut index = 0;
+        while index < tokens.len() {
+            let (rule, next) = parse_rule(&tokens, index, end, symtab)?;
+            rules.push(rule);
+            index = next;
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn atoms(&self) -> BTreeSet<Ident> {
+        self.rules.iter().flat_map(ProgramRule::atoms).collect()
+    }
+
+    pub fn completion(&self) -> Vec<AST> {
+        let mut formulas = self
+            .atoms()
+            .into_iter()
+            .map(|atom| {
+                let head = atom_formula(atom);
+                let supports = self
+                    .rules
+                    .iter()
+                    .filter(|rule| rule.head == Some(atom))
+                    .map(ProgramRule::body)
+                    .collect::<Option<Vec<_>>>();
+                match supports.map(disjunction) {
+                    None => head,
+                    Some(None) => head.not(),
+                    Some(Some(support)) => {
+                        implies(&head, support.clone()).and(implies(&support, head))
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        for rule in self.rules.iter().filter(|rule| rule.head.is_none()) {
+            formulas.push(match rule.body() {
+                Some(body) => body.not(),
+                None => falsum(),
+            });
+        }
+        formulas
+    }
+
+    pub fn is_stable_model(&self, model: &BTreeSet<Ident>) -> bool {
+        self.unfounded(model).is_empty()
+            && self.rules.iter().all(|rule| {
+                let body = rule.positive.iter().all(|atom| model.contains(atom))
+                    && rule.negative.iter().all(|atom| !model.contains(atom));
+                !body || rule.head.is_some_and(|head| model.contains(&head))
+            })
+    }
+
+    pub fn stable_model(&self) -> Option<BTreeSet<Ident>> {
+        self.stable_models(1).pop()
+    }
+
+    pub fn stable_models(&self, limit: usize) -> Vec<BTreeSet<Ident>> {
+        let atoms = self.atoms();
+        let mut context = Context::default();
+        for formula in self.completion() {
+            context.assert(&formula);
+        }
+        let mut models = vec![];
+        while models.len() < limit && context.check_complete::<AST>(&[]) {
+            let assignment = context.model().unwrap_or_default();
+            let model = atoms
+                .iter()
+                .copied()
+                .filter(|atom| assignment.get(*atom).unwrap_or(false))
+                .collect::<BTreeSet<_>>();
+            let unfounded = self.unfounded(&model);
+            if !unfounded.is_empty() {
+                context.assert(&self.loop_formula(&unfounded));
+                continue;
+            }
+            match disjunction(atoms.iter().map(|atom| {
+                let literal = atom_formula(*atom);
+                match model.contains(atom) {
+                    true => literal.not(),
+                    false => literal,
+                }
+            })) {
+                Some(blocking) => context.assert(&blocking),
+                None => {
+                    models.push(model);
+                    break;
+                }
+            }
+            models.push(model);
+        }
+        models
+    }
+
+    fn unfounded(&self, model: &BTreeSet<Ident>) -> BTreeSet<Ident> {
+        let reduct = self
+            .rules
+            .iter()
+            .filter(|rule| rule.negative.iter().all(|atom| !model.contains(atom)))
+            .filter_map(|rule| rule.head.map(|head| (head, &rule.positive)))
+            .collect::<Vec<_>>();
+        let mut derived = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (head, positive) in &reduct {
+                if !derived.contains(head) && positive.iter().all(|atom| derived.contains(atom)) {
+                    derived.insert(*head);
+                    changed = true;
+                }
+            }
+        }
+        model
+            .iter()
+            .copied()
+            .filter(|atom| !derived.contains(atom))
+            .collect()
+    }
+
+    fn loop_formula(&self, atoms: &BTreeSet<Ident>) -> AST {
+        let external = self
+            .rules
+            .iter()
+            .filter(|rule| rule.head.is_some_and(|head| atoms.contains(&head)))
+            .filter(|rule| rule.positive.iter().all(|atom| !atoms.contains(atom)))
+            .map(ProgramRule::body)
+            .collect::<Option<Vec<_>>>()
+            .expect("unfounded atoms have no fact supporting them");
+        let inside = atoms.iter().map(|atom| atom_formula(*atom).not());
+        match disjunction(external) {
+            Some(support) => inside
+                .map(|outside| outside.or(support.clone()))
+                .reduce(|lhs, rhs| lhs.and(rhs))
+                .unwrap(),
+            None => inside.reduce(|lhs, rhs| lhs.and(rhs)).unwrap(),
+        }
+    }
+}
+
+fn atom_formula(atom: Ident) -> AST {
+    <AST as AbstractSyntaxTree>::variable(atom)
+}
+
+fn falsum() -> AST {
+    atom_formula(0).and(atom_formula(0).not())
+}
+
+fn disjunction(formulas: impl IntoIterator<Item = AST>) -> Option<AST> {
+    formulas.into_iter().reduce(|lhs, rhs| lhs.or(rhs))
+}
+
+fn tokenize(input: &str) -> Result<Vec<(String, usize)>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().enumerate().peekable();
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '%' => while chars.next_if(|(_, c)| *c != '\n').is_some() {},
+            ',' | '.' => tokens.push((c.to_string(), position)),
+            ':' => match chars.next() {
+                Some((_, '-')) => tokens.push((":-".to_owned(), position)),
+                Some((position, character)) => {
+                    return Err(ParseError::UnexpectedCharacter {
+                        character,
+                        position,
+                    })
+                }
+                None => {
+                    return Err(ParseError::UnexpectedEndOfInput {
+                        position: position + 1,
+                    })
+                }
+            },
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                    word.push(c);
+                }
+                tokens.push((word, position));
+            }
+            c if c.is_whitespace() => (),
+            character => {
+                return Err(ParseError::UnexpectedCharacter {
+                    character,
+                    position,
+                })
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_rule(
+    tokens: &[(String, usize)],
+    mut index: usize,
+    end: usize,
+    symtab: &mut SymbolTable,
+) -> Result<(ProgramRule, usize), ParseError> {
+    let next = |index: usize| {
+        tokens
+            .get(index)
+            .cloned()
+            .ok_or(ParseError::UnexpectedEndOfInput { position: end })
+    };
+    let is_atom = |token: &str| token.chars().all(|c| c.is_alphanumeric() || c == '_');
+    let (token, position) = next(index)?;
+    let head = match token.as_str() {
+        ":-" => None,
+        token if is_atom(token) && token != "not" => {
+            index += 1;
+            Some(symtab.intern(token))
+        }
+        _ => return Err(ParseError::UnexpectedToken { token, position }),
+    };
+    let mut rule = ProgramRule {
+        head,
+        positive: vec![],
+        negative: vec![],
+    };
+    let (token, position) = next(index)?;
+    index += 1;
+    match token.as_str() {
+        "." if head.is_some() => return Ok((rule, index)),
+        ":-" => (),
+        _ => return Err(ParseError::UnexpectedToken { token, position }),
+    }
+    if next(index)?.0 == "." {
+        return Ok((rule, index + 1));
+    }
+    loop {
+        let (mut token, mut position) = next(index)?;
+        index += 1;
+        let negated = token == "not";
+        if negated {
+            (token, position) = next(index)?;
+            index += 1;
+        }
+        if !is_atom(&token) || token == "not" {
+            return Err(ParseError::UnexpectedToken { token, position });
+        }
+        let atom = symtab.intern(&token);
+        match negated {
+            true => rule.negative.push(atom),
+            false => rule.positive.push(atom),
+        }
+        let (token, position) = next(index)?;
+        index += 1;
+        match token.as_str() {
+            "," => (),
+            "." => return Ok((rule, index)),
+            _ => return Err(ParseError::UnexpectedToken { token, position }),
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{
+    ast::Ident,
+    sat::{Clause, Literal, Polarity},
+};
+
+#[derive(Debug)]
+pub enum ProofError {
+    UnknownPremise(usize),
+    InvalidReference(usize),
+    InvalidPivot(usize),
+    InvalidResolvent(usize),
+    NotARefutation,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::UnknownPremise(step) => write!(f, "step {}: unknown premise", step),
+            ProofError::InvalidReference(step) => write!(f, "step {}: invalid reference", step),
+            ProofError::InvalidPivot(step) => write!(f, "step {}: invalid pivot", step),
+            ProofError::InvalidResolvent(step) => write!(f, "step {}: invalid resolvent", step),
+            ProofError::NotARefutation => write!(f, "proof does not derive the empty clause"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+#[derive(Debug, Clone)]
+pub enum ProofStep {
+    Premise(Clause),
+    Resolution {
+        left: usize,
+        right: usize,
+        pivot: Ident,
+        resolvent: Clause,
+    },
+}
+
+impl ProofStep {
+    pub fn clause(&self) -> &Clause {
+        match self {
+            ProofStep::Premise(clause) => clause,
+            ProofStep::Resolution { resolvent, .. } => resolvent,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolutionProof {
+    pub steps: Vec<ProofStep>,
+}
+
+impl fmt::Display for ResolutionProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            match step {
+                ProofStep::Premise(clause) => writeln!(f, "{}: {} (premise)", index, clause)?,
+                ProofStep::Resolution {
+                    left,
+                    right,
+                    pivot,
+                    resolvent,
+                } => writeln!(
+                    f,
+                    "{}: {} (resolve {} and {} on var{})",
+                    index, resolvent, left, right, pivot
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProofAssistant {
+    Lean,
+    Coq,
+}
+
+impl ProofAssistant {
+    fn implication(self, clause: &Clause) -> String {
+        let (not, arrow) = match self {
+            ProofAssistant::Lean => ("¬", " → "),
+            ProofAssistant::Coq => ("~ ", " -> "),
+        };
+        clause
+            .literals()
+            .map(|literal| match literal.polarity() {
+                Polarity::Positive => format!("{}p{}", not, literal.identifier()),
+                Polarity::Negative => format!("p{}", literal.identifier()),
+            })
+            .chain(["False".to_owned()])
+            .collect::<Vec<String>>()
+            .join(arrow)
+    }
+
+    fn lambda(self, clause: &Clause, body: String) -> String {
+        match clause.is_empty() {
+            true => body,
+            false => format!(
+                "fun {} => {}",
+                clause.literals().map(binder).collect::<Vec<_>>().join(" "),
+                body
+            ),
+        }
+    }
+
+    fn eliminate(self, literals: &[&Literal], disjunction: &str) -> String {
+        let refute = |literal: &Literal, evidence: &str| match literal.polarity() {
+            Polarity::Positive => format!("{} {}", binder(literal), evidence),
+            Polarity::Negative => format!("{} {}", evidence, binder(literal)),
+        };
+        match literals {
+            [] => disjunction.to_owned(),
+            [literal] => refute(literal, disjunction),
+            [literal, rest @ ..] => match self {
+                ProofAssistant::Lean => format!(
+                    "{}.elim (fun d => {}) (fun d => {})",
+                    disjunction,
+                    refute(literal, "d"),
+                    self.eliminate(rest, "d")
+                ),
+                ProofAssistant::Coq => format!(
+                    "match {} with or_introl d => {} | or_intror d => {} end",
+                    disjunction,
+                    refute(literal, "d"),
+                    self.eliminate(rest, "d")
+                ),
+            },
+        }
+    }
+
+    fn clause(self, clause: &Clause) -> String {
+        let (not, or, bottom) = match self {
+            ProofAssistant::Lean => ("¬", " ∨ ", "False"),
+            ProofAssistant::Coq => ("~ ", " \\/ ", "False"),
+        };
+        if clause.is_empty() {
+            return bottom.to_owned();
+        }
+        let mut literals = clause.literals().collect::<Vec<_>>();
+        literals.sort_by_key(|literal| literal.to_dimacs().abs());
+        literals
+            .iter()
+            .map(|literal| match literal.polarity() {
+                Polarity::Positive => format!("p{}", literal.identifier()),
+                Polarity::Negative => format!("{}p{}", not, literal.identifier()),
+            })
+            .collect::<Vec<String>>()
+            .join(or)
+    }
+}
+
+impl ResolutionProof {
+    pub fn export(&self, input: &[Clause], target: ProofAssistant) -> Result<String, ProofError> {
+        self.check(input)?;
+        let variables = self
+            .steps
+            .iter()
+            .flat_map(|step| step.clause().literals())
+            .map(|literal| literal.identifier())
+            .collect::<BTreeSet<Ident>>()
+            .into_iter()
+            .map(|ident| format!("p{}", ident))
+            .collect::<Vec<String>>();
+        let binders = match variables.is_empty() {
+            true => String::new(),
+            false => format!(" ({} : Prop)", variables.join(" ")),
+        };
+        let hypotheses = self
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| matches!(step, ProofStep::Premise(_)))
+            .map(|(index, step)| format!("    (h{} : {})", index, target.clause(step.clause())))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let lemmas = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let clause = step.clause();
+                let term = match step {
+                    ProofStep::Premise(_) => target.lambda(
+                        clause,
+                        target.eliminate(
+                            &clause.literals().collect::<Vec<_>>(),
+                            &format!("h{}", index),
+                        ),
+                    ),
+                    ProofStep::Resolution {
+                        left, right, pivot, ..
+                    } => {
+                        let positive = Literal::new(*pivot, Polarity::Positive);
+                        let (affirmed, denied) =
+                            match self.steps[*left].clause().contains(&positive) {
+                                true => (*left, *right),
+                                false => (*right, *left),
+                            };
+                        let contradiction = target.lambda(
+                            &Clause::new([positive.clone()]),
+                            call(
+                                affirmed,
+                                &self.steps[affirmed]
+                                    .clause()
+                                    .literals()
+                                    .map(binder)
+                                    .collect::<Vec<_>>(),
+                            ),
+                        );
+                        let witness = match target {
+                            ProofAssistant::Lean => {
+                                format!("(Classical.byContradiction {})", contradiction)
+                            }
+                            ProofAssistant::Coq => format!("(NNPP p{} ({}))", pivot, contradiction),
+                        };
+                        let arguments = self.steps[denied]
+                            .clause()
+                            .literals()
+                            .map(|literal| match literal.identifier() == *pivot {
+                                true => witness.clone(),
+                                false => binder(literal),
+                            })
+                            .collect::<Vec<_>>();
+                        target.lambda(clause, call(denied, &arguments))
+                    }
+                };
+                match target {
+                    ProofAssistant::Lean => format!(
+                        "  have c{} : {} := {}",
+                        index,
+                        target.implication(clause),
+                        term
+                    ),
+                    ProofAssistant::Coq => format!(
+                        "  assert (c{} : {}) by exact ({}).",
+                        index,
+                        target.implication(clause),
+                        term
+                    ),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let last = self.empty_clause().ok_or(ProofError::NotARefutation)?;
+        Ok(match target {
+            ProofAssistant::Lean => format!(
+                "theorem refutation{}\n{} : False := by\n{}\n  exact c{}\n",
+                binders, hypotheses, lemmas, last
+            ),
+            ProofAssistant::Coq => format!(
+                "Require Import Classical.\n\nTheorem refutation{}\n{} : False.\nProof.\n{}\n  exact c{}.\nQed.\n",
+                binders, hypotheses, lemmas, last
+            ),
+        })
+    }
+
+    pub fn empty_clause(&self) -> Option<usize> {
+        self.steps.iter().position(|step| step.clause().is_empty())
+    }
+
+    pub fn to_lean(&self, input: &[Clause]) -> Result<String, ProofError> {
+        self.export(input, ProofAssistant::Lean)
+    }
+
+    pub fn to_coq(&self, input: &[Clause]) -> Result<String, ProofError> {
+        self.export(input, ProofAssistant::Coq)
+    }
+
+    pub fn to_drat(&self) -> String {
+        let mut last_use = vec![None; self.steps.len()];
+        for (index, step) in self.steps.iter().enumerate() {
+            if let ProofStep::Resolution { left, right, .. } = step {
+                last_use[*left] = Some(index);
+                last_use[*right] = Some(index);
+            }
+        }
+        let mut drat = String::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            if let ProofStep::Resolution {
+                left,
+                right,
+                resolvent,
+                ..
+            } = step
+            {
+                drat.push_str(&resolvent.to_dimacs());
+                drat.push('\n');
+                if resolvent.is_empty() {
+                    break;
+                }
+                for antecedent in [left, right] {
+                    let derived = matches!(self.steps[*antecedent], ProofStep::Resolution { .. });
+                    if derived && last_use[*antecedent] == Some(index) && left != right {
+                        drat.push_str(&format!(
+                            "d {}\n",
+                            self.steps[*antecedent].clause().to_dimacs()
+                        ));
+                    }
+                }
+            }
+        }
+        drat
+    }
+
+    pub fn check(&self, input: &[Clause]) -> Result<(), ProofError> {
+        for (index, step) in self.steps.iter().enumerate() {
+            match step {
+                ProofStep::Premise(clause) => {
+                    if !input.contains(clause) {
+                        return Err(ProofError::UnknownPremise(index));
+                    }
+                }
+                ProofStep::Resolution {
+                    left,
+                    right,
+                    pivot,
+                    resolvent,
+                } => {
+                    if *left >= index || *right >= index {
+                        return Err(ProofError::InvalidReference(index));
+                    }
+                    let (left, right) = (self.steps[*left].clause(), self.steps[*right].clause());
+                    let positive = Literal::new(*pivot, Polarity::Positive);
+                    let negative = positive.not();
+                    let oriented = (left.contains(&positive) && right.contains(&negative))
+                        || (left.contains(&negative) && right.contains(&positive));
+                    if !oriented {
+                        return Err(ProofError::InvalidPivot(index));
+                    }
+                    if *resolvent != Clause::resolve(left, right, &positive) {
+                        return Err(ProofError::InvalidResolvent(index));
+                    }
+                }
+            }
+        }
+        match self.steps.last() {
+            Some(step) if step.clause().is_empty() => Ok(()),
+            _ => Err(ProofError::NotARefutation),
+        }
+    }
+}
+
+fn binder(literal: &Literal) -> String {
+    match literal.polarity() {
+        Polarity::Positive => format!("a{}", literal.identifier()),
+        Polarity::Negative => format!("b{}", literal.identifier()),
+    }
+}
+
+fn call(step: usize, arguments: &[String]) -> String {
+    std::iter::once(format!("c{}", step))
+        .chain(arguments.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
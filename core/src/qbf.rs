@@ -0,0 +1,240 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    dimacs::{self, DimacsError},
+    fol::Quantifier,
+    sat::{Clause, Literal, Polarity},
+    tseitin,
+};
+
+#[derive(Debug, Clone)]
+pub struct QuantifiedFormula {
+    pub prefix: Vec<(Quantifier, Vec<Ident>)>,
+    pub matrix: Vec<Clause>,
+}
+
+impl fmt::Display for QuantifiedFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (quantifier, block) in &self.prefix {
+            let symbol = match quantifier {
+                Quantifier::ForAll => "∀",
+                Quantifier::Exists => "∃",
+            };
+            for ident in block {
+                write!(f, "{}var{} ", symbol, ident)?;
+            }
+        }
+        write!(
+            f,
+            "{{{}}}",
+            self.matrix
+                .iter()
+                .map(|clause| clause.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl QuantifiedFormula {
+    pub fn new(mut prefix: Vec<(Quantifier, Vec<Ident>)>, matrix: &AST) -> Self {
+        let first = prefix
+            .iter()
+            .flat_map(|(_, block)| block.iter().copied())
+            .chain(matrix.variables())
+            .max()
+            .map_or(0, |ident| ident + 1);
+        let (clauses, auxiliary) = tseitin::cnf_clauses_from(matrix, first);
+        if !auxiliary.is_empty() {
+            prefix.push((Quantifier::Exists, auxiliary));
+        }
+        Self::from_clauses(prefix, clauses)
+    }
+
+    pub fn from_clauses(prefix: Vec<(Quantifier, Vec<Ident>)>, matrix: Vec<Clause>) -> Self {
+        Self { prefix, matrix }
+    }
+
+    pub fn from_qdimacs(input: &str) -> Result<Self, DimacsError> {
+        let lines = dimacs::read_header(input, "cnf")?;
+        let mut prefix = vec![];
+        let mut body = lines.body.iter().peekable();
+        while let Some((line, text)) =
+            body.next_if(|(_, text)| text.starts_with('a') || text.starts_with('e'))
+        {
+            let quantifier = match text.starts_with('a') {
+                true => Quantifier::ForAll,
+                false => Quantifier::Exists,
+            };
+            let mut block = dimacs::parse_literals(lines.variables, *line, &text[1..])?;
+            if block.pop() != Some(0) {
+                return Err(DimacsError::UnterminatedClause);
+            }
+            prefix.push((
+                quantifier,
+                block
+                    .into_iter()
+                    .map(|value| (value - 1) as Ident)
+                    .collect(),
+            ));
+        }
+        let matrix = dimacs::parse_clauses(lines.variables, body)?;
+        if matrix.len() != lines.clauses {
+            return Err(DimacsError::ClauseCountMismatch {
+                expected: lines.clauses,
+                found: matrix.len(),
+            });
+        }
+        Ok(Self { prefix, matrix })
+    }
+
+    pub fn to_qdimacs(&self) -> String {
+        let variables = self
+            .prefix
+            .iter()
+            .flat_map(|(_, block)| block.iter().map(|ident| *ident as usize + 1))
+            .chain([dimacs::variable_count(&self.matrix)])
+            .max()
+            .unwrap_or(0);
+        let mut qdimacs = format!("p cnf {} {}\n", variables, self.matrix.len());
+        for (quantifier, block) in &self.prefix {
+            qdimacs.push(match quantifier {
+                Quantifier::ForAll => 'a',
+                Quantifier::Exists => 'e',
+            });
+            for ident in block {
+                qdimacs.push_str(&format!(" {}", ident + 1));
+            }
+            qdimacs.push_str(" 0\n");
+        }
+        for clause in &self.matrix {
+            qdimacs.push_str(&clause.to_dimacs());
+            qdimacs.push('\n');
+        }
+        qdimacs
+    }
+
+    pub fn solve(&self) -> bool {
+        QDPLLSolver::new(self).qdpll(&mut Model::new())
+    }
+}
+
+struct QDPLLSolver<'a> {
+    matrix: Vec<&'a Clause>,
+    levels: HashMap<Ident, (usize, Quantifier)>,
+    order: Vec<Ident>,
+}
+
+impl<'a> QDPLLSolver<'a> {
+    fn new(formula: &'a QuantifiedFormula) -> Self {
+        let mut levels = HashMap::new();
+        for (level, (quantifier, block)) in formula.prefix.iter().enumerate() {
+            for ident in block {
+                levels.insert(*ident, (level + 1, *quantifier));
+            }
+        }
+        let mut order = formula
+            .matrix
+            .iter()
+            .flat_map(|clause| clause.literals())
+            .map(|literal| literal.identifier())
+            .filter(|ident| !levels.contains_key(ident))
+            .collect::<Vec<_>>();
+        order.sort();
+        order.dedup();
+        for ident in &order {
+            levels.insert(*ident, (0, Quantifier::Exists));
+        }
+        order.extend(formula.prefix.iter().flat_map(|(_, block)| block));
+        Self {
+            matrix: formula
+                .matrix
+                .iter()
+                .filter(|clause| {
+                    !clause
+                        .literals()
+                        .any(|literal| clause.contains(&literal.not()))
+                })
+                .collect(),
+            levels,
+            order,
+        }
+    }
+
+    fn value(literal: &Literal, assignment: &Model) -> Option<bool> {
+        assignment
+            .get(literal.identifier())
+            .map(|value| value == (*literal.polarity() == Polarity::Positive))
+    }
+
+    fn propagate(&self, assignment: &mut Model) -> Option<bool> {
+        loop {
+            let mut changed = false;
+            let mut satisfied = true;
+            for clause in &self.matrix {
+                if clause
+                    .literals()
+                    .any(|literal| Self::value(literal, assignment) == Some(true))
+                {
+                    continue;
+                }
+                satisfied = false;
+                let open = clause
+                    .literals()
+                    .filter(|literal| Self::value(literal, assignment).is_none())
+                    .collect::<Vec<_>>();
+                let innermost_existential = open
+                    .iter()
+                    .map(|literal| self.levels[&literal.identifier()])
+                    .filter(|(_, quantifier)| *quantifier == Quantifier::Exists)
+                    .map(|(level, _)| level)
+                    .max();
+                let reduced = open
+                    .into_iter()
+                    .filter(|literal| {
+                        let (level, quantifier) = self.levels[&literal.identifier()];
+                        quantifier == Quantifier::Exists
+                            || innermost_existential.is_some_and(|max| level < max)
+                    })
+                    .collect::<Vec<_>>();
+                match reduced.as_slice() {
+                    [] => return Some(false),
+                    [unit] if self.levels[&unit.identifier()].1 == Quantifier::Exists => {
+                        assignment.set(unit.identifier(), *unit.polarity() == Polarity::Positive);
+                        changed = true;
+                    }
+                    _ => (),
+                }
+            }
+            if satisfied {
+                return Some(true);
+            }
+            if !changed {
+                return None;
+            }
+        }
+    }
+
+    fn qdpll(&self, assignment: &mut Model) -> bool {
+        if let Some(result) = self.propagate(assignment) {
+            return result;
+        }
+        let Some(&ident) = self
+            .order
+            .iter()
+            .find(|ident| !assignment.contains(**ident))
+        else {
+            return true;
+        };
+        let branch = |value: bool| {
+            let mut assignment = assignment.clone();
+            assignment.set(ident, value);
+            self.qdpll(&mut assignment)
+        };
+        match self.levels[&ident].1 {
+            Quantifier::Exists => branch(true) || branch(false),
+            Quantifier::ForAll => branch(true) && branch(false),
+        }
+    }
+}
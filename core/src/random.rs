@@ -0,0 +1,72 @@
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, AST},
+    sat::{Clause, Literal, Polarity},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+pub fn random_cnf(rng: &mut Rng, variables: usize, clauses: usize, width: usize) -> Vec<Clause> {
+    let width = width.min(variables);
+    (0..clauses)
+        .map(|_| {
+            let mut chosen = Vec::with_capacity(width);
+            while chosen.len() < width {
+                let ident = rng.below(variables) as Ident;
+                if !chosen.contains(&ident) {
+                    chosen.push(ident);
+                }
+            }
+            Clause::new(chosen.into_iter().map(|ident| {
+                let polarity = match rng.next_bool() {
+                    true => Polarity::Positive,
+                    false => Polarity::Negative,
+                };
+                Literal::new(ident, polarity)
+            }))
+        })
+        .collect()
+}
+
+pub fn random_formula(rng: &mut Rng, depth: usize, variables: usize) -> AST {
+    if depth == 0 || rng.below(4) == 0 {
+        return AST::variable(rng.below(variables) as Ident);
+    }
+    match rng.below(3) {
+        0 => random_formula(rng, depth - 1, variables).not(),
+        1 => {
+            random_formula(rng, depth - 1, variables).and(random_formula(rng, depth - 1, variables))
+        }
+        _ => {
+            random_formula(rng, depth - 1, variables).or(random_formula(rng, depth - 1, variables))
+        }
+    }
+}
@@ -1,14 +1,63 @@
-use std::{collections::HashMap, fmt, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
-use crate::ast::{ASTNode, Ident, AST};
+use crate::{
+    ast::{ASTNode, AbstractSyntaxTree, Ident, Node, AST},
+    budget::{Budget, BudgetError},
+    config::{self, ConfigError, Value},
+    parse::{self, SymbolTable},
+    trace::{trace_event, trace_span},
+};
 
-#[derive(Debug)]
+const NEGATION_SCALE: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum RewriteError {
-    RuleDoesNotApply,
+    RuleDoesNotApply { rule: String },
+    Irreversible { rule: String, variable: Ident },
+    NonTerminating { ruleset: String, iterations: usize },
+    BudgetExceeded { ruleset: String, error: BudgetError },
+}
+
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RewriteError::RuleDoesNotApply { rule } => {
+                write!(f, "rule '{}' does not apply", rule)
+            }
+            RewriteError::Irreversible { rule, variable } => write!(
+                f,
+                "rule '{}' cannot be reversed: var{} is not bound by its result",
+                rule, variable
+            ),
+            RewriteError::NonTerminating {
+                ruleset,
+                iterations,
+            } => write!(
+                f,
+                "ruleset '{}' does not terminate (cycle after {} iterations)",
+                ruleset, iterations
+            ),
+            RewriteError::BudgetExceeded { ruleset, error } => {
+                write!(f, "ruleset '{}' exceeded its budget: {}", ruleset, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RewriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RewriteError::BudgetExceeded { error, .. } => Some(error),
+            _ => None,
+        }
+    }
 }
 
 pub struct RewriteRule {
-    pub name: &'static str,
+    pub name: String,
     pub top: AST,
     pub bot: AST,
 }
@@ -20,53 +69,246 @@ impl fmt::Display for RewriteRule {
 }
 
 impl RewriteRule {
-    pub fn rewrite(&self, target: AST) -> AST {
-        let matching = match Self::matching(&target, &self.top) {
-            Ok(m) => m,
-            Err(_) => return target,
+    pub fn new(name: &str, (top, bot): (AST, AST)) -> Self {
+        Self {
+            name: name.to_owned(),
+            top,
+            bot,
+        }
+    }
+
+    pub fn parse(name: &str, top: &str, bot: &str) -> Result<Self, ConfigError> {
+        let mut symtab = SymbolTable::new();
+        let mut pattern = |text: &str| {
+            parse::parse_with(text, &mut symtab)
+                .map_err(|err| ConfigError::InvalidPattern(text.to_owned(), err))
         };
-        Self::substitute(self.bot.clone(), &matching)
+        let (top, bot) = (pattern(top)?, pattern(bot)?);
+        let top_variables = top.variables();
+        if let Some(unbound) = bot
+            .variables()
+            .into_iter()
+            .find(|ident| !top_variables.contains(ident))
+        {
+            return Err(ConfigError::UnboundVariable(
+                symtab.name(unbound).unwrap().to_owned(),
+            ));
+        }
+        Ok(Self::new(name, (top, bot)))
     }
 
-    fn matching(target: &AST, pattern: &AST) -> Result<HashMap<Ident, AST>, RewriteError> {
-        match (&**pattern, &**target) {
-            (ASTNode::Not(template_p), ASTNode::Not(p)) => Ok(Self::matching(p, template_p)?),
-            (ASTNode::And(template_p1, template_p2), ASTNode::And(p1, p2)) => {
-                let mut matching_p1 = Self::matching(p1, template_p1)?;
-                matching_p1.extend(Self::matching(p2, template_p2)?);
-                Ok(matching_p1)
-            }
-            (ASTNode::Or(template_p1, template_p2), ASTNode::Or(p1, p2)) => {
-                let mut matching_p1 = Self::matching(p1, template_p1)?;
-                matching_p1.extend(Self::matching(p2, template_p2)?);
-                Ok(matching_p1)
+    pub fn rewrite<T: AbstractSyntaxTree>(&self, target: T) -> T {
+        self.apply(&target).unwrap_or(target)
+    }
+
+    pub fn try_rewrite<T: AbstractSyntaxTree>(&self, target: &T) -> Result<T, RewriteError> {
+        self.apply(target)
+            .ok_or_else(|| RewriteError::RuleDoesNotApply {
+                rule: self.name.clone(),
+            })
+    }
+
+    pub fn reversed(&self) -> Result<Self, RewriteError> {
+        let bot_variables = self.bot.variables();
+        if let Some(unbound) = self
+            .top
+            .variables()
+            .into_iter()
+            .find(|ident| !bot_variables.contains(ident))
+        {
+            return Err(RewriteError::Irreversible {
+                rule: self.name.clone(),
+                variable: unbound,
+            });
+        }
+        Ok(Self {
+            name: format!("reversed {}", self.name),
+            top: self.bot.clone(),
+            bot: self.top.clone(),
+        })
+    }
+
+    fn apply<T: AbstractSyntaxTree>(&self, target: &T) -> Option<T> {
+        let matching = Self::matching(target, &self.top)?;
+        Some(Self::substitute(&self.bot, &matching))
+    }
+
+    fn matching<T: AbstractSyntaxTree>(target: &T, pattern: &AST) -> Option<HashMap<Ident, T>> {
+        match (&**pattern, target.node()) {
+            (ASTNode::Not(template_p), Node::Not(p)) => Self::matching(&p, template_p),
+            (ASTNode::And(template_p1, template_p2), Node::And(p1, p2))
+            | (ASTNode::Or(template_p1, template_p2), Node::Or(p1, p2))
+            | (ASTNode::Nand(template_p1, template_p2), Node::Nand(p1, p2))
+            | (ASTNode::Nor(template_p1, template_p2), Node::Nor(p1, p2)) => Self::merge(
+                Self::matching(&p1, template_p1)?,
+                Self::matching(&p2, template_p2)?,
+            ),
+            (ASTNode::Ite(template_p1, template_p2, template_p3), Node::Ite(p1, p2, p3)) => {
+                let matching_p1 = Self::matching(&p1, template_p1)?;
+                let matching_p2 = Self::merge(matching_p1, Self::matching(&p2, template_p2)?)?;
+                Self::merge(matching_p2, Self::matching(&p3, template_p3)?)
             }
             (ASTNode::Variable(template_ident), _) => {
-                Ok(HashMap::from([(*template_ident, target.clone())]))
+                Some(HashMap::from([(*template_ident, target.clone())]))
+            }
+            _ => None,
+        }
+    }
+
+    fn merge<T: AbstractSyntaxTree>(
+        mut matching: HashMap<Ident, T>,
+        other: HashMap<Ident, T>,
+    ) -> Option<HashMap<Ident, T>> {
+        for (ident, p) in other {
+            match matching.get(&ident) {
+                Some(bound) if bound.key() != p.key() && *bound != p => return None,
+                _ => {
+                    matching.insert(ident, p);
+                }
             }
-            _ => Err(RewriteError::RuleDoesNotApply),
         }
+        Some(matching)
     }
 
-    fn substitute(template: AST, matching: &HashMap<Ident, AST>) -> AST {
-        match &*template {
+    fn substitute<T: AbstractSyntaxTree>(template: &AST, matching: &HashMap<Ident, T>) -> T {
+        let substitute = |p: &AST| Self::substitute(p, matching);
+        match &**template {
             ASTNode::Variable(ident) => matching.get(ident).unwrap().clone(),
-            ASTNode::Not(p) => Rc::new(ASTNode::Not(Self::substitute(p.clone(), matching))),
-            ASTNode::And(p1, p2) => Rc::new(ASTNode::And(
-                Self::substitute(p1.clone(), matching),
-                Self::substitute(p2.clone(), matching),
-            )),
-            ASTNode::Or(p1, p2) => Rc::new(ASTNode::Or(
-                Self::substitute(p1.clone(), matching),
-                Self::substitute(p2.clone(), matching),
-            )),
+            ASTNode::Not(p) => substitute(p).not(),
+            ASTNode::And(p1, p2) => substitute(p1).and(substitute(p2)),
+            ASTNode::Or(p1, p2) => substitute(p1).or(substitute(p2)),
+            ASTNode::Nand(p1, p2) => substitute(p1).nand(substitute(p2)),
+            ASTNode::Nor(p1, p2) => substitute(p1).nor(substitute(p2)),
+            ASTNode::Ite(p1, p2, p3) => substitute(p1).ite(substitute(p2), substitute(p3)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    NodeCount,
+    NegationDepth,
+}
+
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Measure::NodeCount => write!(f, "node count"),
+            Measure::NegationDepth => write!(f, "negation depth"),
+        }
+    }
+}
+
+impl Measure {
+    pub fn parse(key: &str) -> Option<Self> {
+        match key {
+            "node-count" => Some(Measure::NodeCount),
+            "negation-depth" => Some(Measure::NegationDepth),
+            _ => None,
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Measure::NodeCount => "node-count",
+            Measure::NegationDepth => "negation-depth",
         }
     }
+
+    pub fn evaluate<T: AbstractSyntaxTree>(&self, formula: &T) -> usize {
+        self.evaluate_memoized(formula, &mut HashMap::new())
+    }
+
+    pub fn decreases(&self, rule: &RewriteRule) -> bool {
+        let (top, top_coefficients) = self.decompose(&rule.top);
+        let (bot, bot_coefficients) = self.decompose(&rule.bot);
+        bot < top
+            && bot_coefficients.iter().all(|(ident, coefficient)| {
+                top_coefficients
+                    .get(ident)
+                    .is_some_and(|bound| coefficient <= bound)
+            })
+    }
+
+    fn scale<T>(&self, node: &Node<T>) -> usize {
+        match (self, node) {
+            (Measure::NegationDepth, Node::Not(_)) => NEGATION_SCALE,
+            _ => 1,
+        }
+    }
+
+    fn evaluate_memoized<T: AbstractSyntaxTree>(
+        &self,
+        formula: &T,
+        memo: &mut HashMap<usize, usize>,
+    ) -> usize {
+        if let Some(value) = memo.get(&formula.key()) {
+            return *value;
+        }
+        let node = formula.node();
+        let children = match &node {
+            Node::Variable(_) => vec![],
+            Node::Not(p) => vec![p.clone()],
+            Node::And(p1, p2) | Node::Or(p1, p2) | Node::Nand(p1, p2) | Node::Nor(p1, p2) => {
+                vec![p1.clone(), p2.clone()]
+            }
+            Node::Ite(p1, p2, p3) => vec![p1.clone(), p2.clone(), p3.clone()],
+        };
+        let value = children
+            .iter()
+            .map(|p| self.evaluate_memoized(p, memo))
+            .fold(0usize, usize::saturating_add)
+            .saturating_mul(self.scale(&node))
+            .saturating_add(1);
+        memo.insert(formula.key(), value);
+        value
+    }
+
+    fn decompose(&self, pattern: &AST) -> (usize, HashMap<Ident, usize>) {
+        let node = pattern.node();
+        let children = match &**pattern {
+            ASTNode::Variable(ident) => return (0, HashMap::from([(*ident, 1)])),
+            ASTNode::Not(p) => vec![p],
+            ASTNode::And(p1, p2)
+            | ASTNode::Or(p1, p2)
+            | ASTNode::Nand(p1, p2)
+            | ASTNode::Nor(p1, p2) => vec![p1, p2],
+            ASTNode::Ite(p1, p2, p3) => vec![p1, p2, p3],
+        };
+        let scale = self.scale(&node);
+        let mut base = 0;
+        let mut coefficients = HashMap::new();
+        for child in children {
+            let (child_base, child_coefficients) = self.decompose(child);
+            base += child_base;
+            for (ident, coefficient) in child_coefficients {
+                *coefficients.entry(ident).or_insert(0) += scale * coefficient;
+            }
+        }
+        (1 + scale * base, coefficients)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminationWarning {
+    pub rule: String,
+    pub measure: Measure,
+}
+
+impl fmt::Display for TerminationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rule '{}' does not strictly decrease the {}",
+            self.rule, self.measure
+        )
+    }
 }
 
 pub struct RewriteRuleset {
-    pub name: &'static str,
+    pub name: String,
     pub rules: Vec<RewriteRule>,
+    pub measure: Option<Measure>,
 }
 
 impl fmt::Display for RewriteRuleset {
@@ -80,40 +322,425 @@ impl fmt::Display for RewriteRuleset {
 }
 
 impl RewriteRuleset {
-    pub fn rewrite(&self, target: AST) -> AST {
+    pub fn dnf() -> Self {
+        RewriteRuleset {
+            name: "DNF conversion".to_owned(),
+            rules: vec![
+                RewriteRule::new(
+                    "disjunctive if-then-else expansion",
+                    procmacro::propositional_logic! { (c ? x : y) => ((c AND x) OR (NOT c AND y)) },
+                ),
+                RewriteRule::new(
+                    "nand expansion",
+                    procmacro::propositional_logic! { (x NAND y) => NOT (x AND y) },
+                ),
+                RewriteRule::new(
+                    "nor expansion",
+                    procmacro::propositional_logic! { (x NOR y) => NOT (x OR y) },
+                ),
+                RewriteRule::new(
+                    "double negation elimination",
+                    procmacro::propositional_logic! { NOT NOT x => x },
+                ),
+                RewriteRule::new(
+                    "de morgan's theorem for disjunction",
+                    procmacro::propositional_logic! { NOT (x OR y) => (NOT x AND NOT y) },
+                ),
+                RewriteRule::new(
+                    "de morgan's theorem for conjunction",
+                    procmacro::propositional_logic! { NOT (x AND y) => (NOT x OR NOT y) },
+                ),
+                RewriteRule::new(
+                    "left-distributive property of conjunction over disjunction",
+                    procmacro::propositional_logic! { (x AND (y OR z)) => ((x AND y) OR (x AND z)) },
+                ),
+                RewriteRule::new(
+                    "right-distributive property of conjunction over disjunction",
+                    procmacro::propositional_logic! { ((x OR y) AND z) => ((x AND z) OR (y AND z)) },
+                ),
+            ],
+            measure: None,
+        }
+    }
+
+    pub fn cnf() -> Self {
+        RewriteRuleset {
+            name: "CNF conversion".to_owned(),
+            rules: vec![
+                RewriteRule::new(
+                    "conjunctive if-then-else expansion",
+                    procmacro::propositional_logic! { (c ? x : y) => ((NOT c OR x) AND (c OR y)) },
+                ),
+                RewriteRule::new(
+                    "nand expansion",
+                    procmacro::propositional_logic! { (x NAND y) => NOT (x AND y) },
+                ),
+                RewriteRule::new(
+                    "nor expansion",
+                    procmacro::propositional_logic! { (x NOR y) => NOT (x OR y) },
+                ),
+                RewriteRule::new(
+                    "double negation elimination",
+                    procmacro::propositional_logic! { NOT NOT x => x },
+                ),
+                RewriteRule::new(
+                    "de morgan's theorem for disjunction",
+                    procmacro::propositional_logic! { NOT (x OR y) => (NOT x AND NOT y) },
+                ),
+                RewriteRule::new(
+                    "de morgan's theorem for conjunction",
+                    procmacro::propositional_logic! { NOT (x AND y) => (NOT x OR NOT y) },
+                ),
+                RewriteRule::new(
+                    "left-distributive property of disjunction over conjunction",
+                    procmacro::propositional_logic! { (x OR (y AND z)) => ((x OR y) AND (x OR z)) },
+                ),
+                RewriteRule::new(
+                    "right-distributive property of disjunction over conjunction",
+                    procmacro::propositional_logic! { ((x AND y) OR z) => ((x OR z) AND (y OR z)) },
+                ),
+            ],
+            measure: None,
+        }
+    }
+
+    pub fn ite() -> Self {
+        RewriteRuleset {
+            name: "if-then-else lowering".to_owned(),
+            rules: vec![
+                RewriteRule::new(
+                    "negated if-then-else",
+                    procmacro::propositional_logic! { NOT (c ? x : y) => (c ? NOT x : NOT y) },
+                ),
+                RewriteRule::new(
+                    "disjunctive if-then-else expansion",
+                    procmacro::propositional_logic! { (c ? x : y) => ((c AND x) OR (NOT c AND y)) },
+                ),
+            ],
+            measure: None,
+        }
+    }
+
+    pub fn reversed(&self) -> Result<Self, RewriteError> {
+        Ok(RewriteRuleset {
+            name: format!("reversed {}", self.name),
+            rules: self
+                .rules
+                .iter()
+                .rev()
+                .map(RewriteRule::reversed)
+                .collect::<Result<_, _>>()?,
+            measure: None,
+        })
+    }
+
+    pub fn with_measure(mut self, measure: Measure) -> Self {
+        self.measure = Some(measure);
+        self
+    }
+
+    pub fn termination_warnings(&self) -> Vec<TerminationWarning> {
+        let Some(measure) = self.measure else {
+            return vec![];
+        };
         self.rules
             .iter()
-            .fold(target, |ast, rule| rule.rewrite(ast))
-    }
-
-    pub fn rewrite_recursive(&self, mut target: AST) -> AST {
-        target = self.rewrite(target);
-        match &*target {
-            ASTNode::Not(p) => target = Rc::new(ASTNode::Not(self.rewrite_recursive(p.clone()))),
-            ASTNode::And(p1, p2) => {
-                target = Rc::new(ASTNode::And(
-                    self.rewrite_recursive(p1.clone()),
-                    self.rewrite_recursive(p2.clone()),
-                ))
-            }
-            ASTNode::Or(p1, p2) => {
-                target = Rc::new(ASTNode::Or(
-                    self.rewrite_recursive(p1.clone()),
-                    self.rewrite_recursive(p2.clone()),
-                ))
-            }
-            _ => (),
+            .filter(|rule| !measure.decreases(rule))
+            .map(|rule| TerminationWarning {
+                rule: rule.name.clone(),
+                measure,
+            })
+            .collect()
+    }
+
+    pub fn from_json(input: &str) -> Result<Self, ConfigError> {
+        Self::from_config(&config::parse_json(input)?)
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self, ConfigError> {
+        Self::from_config(&config::parse_toml(input)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        config::write_json(&self.to_config())
+    }
+
+    pub fn to_toml(&self) -> String {
+        config::write_toml(&self.to_config())
+    }
+
+    fn from_config(value: &Value) -> Result<Self, ConfigError> {
+        let mut rules = vec![];
+        for rule in value.as_array("rules")? {
+            rules.push(RewriteRule::parse(
+                rule.as_str("name")?,
+                rule.as_str("top")?,
+                rule.as_str("bot")?,
+            )?);
         }
-        target
+        let measure = match value.get("measure") {
+            Some(_) => Some(
+                Measure::parse(value.as_str("measure")?)
+                    .ok_or_else(|| ConfigError::InvalidType("measure".to_owned()))?,
+            ),
+            None => None,
+        };
+        Ok(RewriteRuleset {
+            name: value.as_str("name")?.to_owned(),
+            rules,
+            measure,
+        })
     }
 
-    pub fn rewrite_recursive_hull(&self, mut target: AST) -> AST {
+    fn to_config(&self) -> Value {
+        let mut entries = vec![("name".to_owned(), Value::String(self.name.clone()))];
+        if let Some(measure) = self.measure {
+            entries.push((
+                "measure".to_owned(),
+                Value::String(measure.key().to_owned()),
+            ));
+        }
+        entries.push((
+            "rules".to_owned(),
+            Value::Array(
+                self.rules
+                    .iter()
+                    .map(|rule| {
+                        Value::Table(vec![
+                            ("name".to_owned(), Value::String(rule.name.clone())),
+                            ("top".to_owned(), Value::String(rule.top.to_string())),
+                            ("bot".to_owned(), Value::String(rule.bot.to_string())),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ));
+        Value::Table(entries)
+    }
+
+    pub fn rewrite<T: AbstractSyntaxTree>(&self, target: T) -> T {
+        self.rewrite_reporting(target, &mut self.empty_report())
+    }
+
+    pub fn rewrite_recursive<T: AbstractSyntaxTree>(&self, target: T) -> T {
+        let mut memo = RewriteMemo::new(self.empty_report(), Budget::unlimited());
+        let Ok(result) = self.rewrite_recursive_memoized(target, &mut memo) else {
+            unreachable!("an unlimited budget is never exceeded")
+        };
+        result
+    }
+
+    pub fn rewrite_recursive_hull<T: AbstractSyntaxTree>(
+        &self,
+        target: T,
+    ) -> Result<T, RewriteError> {
+        Ok(self.rewrite_recursive_hull_with_report(target)?.0)
+    }
+
+    pub fn rewrite_recursive_hull_with_report<T: AbstractSyntaxTree>(
+        &self,
+        target: T,
+    ) -> Result<(T, RewriteReport), RewriteError> {
+        self.rewrite_recursive_hull_within(target, &Budget::unlimited())
+    }
+
+    pub fn rewrite_recursive_hull_within<T: AbstractSyntaxTree>(
+        &self,
+        mut target: T,
+        budget: &Budget,
+    ) -> Result<(T, RewriteReport), RewriteError> {
+        trace_span!("rewrite.hull");
+        let mut memo = RewriteMemo::new(self.empty_report(), *budget);
+        memo.report.warnings = self.termination_warnings();
+        let mut interner = Interner::new();
+        let mut seen = HashSet::new();
         loop {
-            let new = self.rewrite_recursive(target.clone());
-            if new == target {
-                return target;
+            memo.report.iterations += 1;
+            memo.produced.clear();
+            let new = self
+                .rewrite_recursive_memoized(target.clone(), &mut memo)
+                .and_then(|new| budget.check_nodes(&new).map(|_| new))
+                .map_err(|error| RewriteError::BudgetExceeded {
+                    ruleset: self.name.clone(),
+                    error,
+                })?;
+            trace_event!(
+                "rewrite.iteration",
+                iteration = memo.report.iterations,
+                firings = memo.report.total_firings()
+            );
+            let (old_id, new_id) = (interner.intern(&target), interner.intern(&new));
+            if new_id == old_id {
+                return Ok((target, memo.report));
+            }
+            if seen.contains(&new_id) {
+                return Err(RewriteError::NonTerminating {
+                    ruleset: self.name.clone(),
+                    iterations: memo.report.iterations,
+                });
             }
+            seen.insert(old_id);
             target = new;
         }
     }
+
+    fn empty_report(&self) -> RewriteReport {
+        RewriteReport {
+            ruleset: self.name.clone(),
+            iterations: 0,
+            warnings: vec![],
+            firings: self
+                .rules
+                .iter()
+                .map(|rule| (rule.name.clone(), 0))
+                .collect(),
+        }
+    }
+
+    fn rewrite_reporting<T: AbstractSyntaxTree>(&self, target: T, report: &mut RewriteReport) -> T {
+        self.rules
+            .iter()
+            .zip(report.firings.iter_mut())
+            .fold(target, |ast, (rule, (_, count))| match rule.apply(&ast) {
+                Some(new) => {
+                    *count += 1;
+                    new
+                }
+                None => ast,
+            })
+    }
+
+    fn rewrite_recursive_memoized<T: AbstractSyntaxTree>(
+        &self,
+        target: T,
+        memo: &mut RewriteMemo<T>,
+    ) -> Result<T, BudgetError> {
+        let key = target.key();
+        if let Some((_, result)) = memo.results.get(&key) {
+            let result = result.clone();
+            memo.produce(&result)?;
+            return Ok(result);
+        }
+        let rewritten = self.rewrite_reporting(target.clone(), &mut memo.report);
+        let mut recurse = |p: &T| {
+            let new_p = self.rewrite_recursive_memoized(p.clone(), memo)?;
+            let unchanged = new_p.key() == p.key();
+            Ok((new_p, unchanged))
+        };
+        let result = match rewritten.node() {
+            Node::Variable(_) => rewritten.clone(),
+            Node::Not(p) => match recurse(&p)? {
+                (_, true) => rewritten.clone(),
+                (new_p, false) => new_p.not(),
+            },
+            Node::Ite(p1, p2, p3) => match (recurse(&p1)?, recurse(&p2)?, recurse(&p3)?) {
+                ((_, true), (_, true), (_, true)) => rewritten.clone(),
+                ((new_p1, _), (new_p2, _), (new_p3, _)) => new_p1.ite(new_p2, new_p3),
+            },
+            Node::And(p1, p2) => match (recurse(&p1)?, recurse(&p2)?) {
+                ((_, true), (_, true)) => rewritten.clone(),
+                ((new_p1, _), (new_p2, _)) => new_p1.and(new_p2),
+            },
+            Node::Or(p1, p2) => match (recurse(&p1)?, recurse(&p2)?) {
+                ((_, true), (_, true)) => rewritten.clone(),
+                ((new_p1, _), (new_p2, _)) => new_p1.or(new_p2),
+            },
+            Node::Nand(p1, p2) => match (recurse(&p1)?, recurse(&p2)?) {
+                ((_, true), (_, true)) => rewritten.clone(),
+                ((new_p1, _), (new_p2, _)) => new_p1.nand(new_p2),
+            },
+            Node::Nor(p1, p2) => match (recurse(&p1)?, recurse(&p2)?) {
+                ((_, true), (_, true)) => rewritten.clone(),
+                ((new_p1, _), (new_p2, _)) => new_p1.nor(new_p2),
+            },
+        };
+        memo.produce(&result)?;
+        memo.results.insert(key, (target, result.clone()));
+        Ok(result)
+    }
+}
+
+struct RewriteMemo<T> {
+    results: HashMap<usize, (T, T)>,
+    report: RewriteReport,
+    budget: Budget,
+    produced: HashSet<usize>,
+}
+
+impl<T: AbstractSyntaxTree> RewriteMemo<T> {
+    fn new(report: RewriteReport, budget: Budget) -> Self {
+        Self {
+            results: HashMap::new(),
+            report,
+            budget,
+            produced: HashSet::new(),
+        }
+    }
+
+    fn produce(&mut self, result: &T) -> Result<(), BudgetError> {
+        self.produced.insert(result.key());
+        self.budget.check_node_count(self.produced.len())
+    }
+}
+
+struct Interner<T> {
+    ids: HashMap<Node<usize>, usize>,
+    nodes: HashMap<usize, (T, usize)>,
+}
+
+impl<T: AbstractSyntaxTree> Interner<T> {
+    fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            nodes: HashMap::new(),
+        }
+    }
+
+    fn intern(&mut self, formula: &T) -> usize {
+        if let Some((_, id)) = self.nodes.get(&formula.key()) {
+            return *id;
+        }
+        let node = match formula.node() {
+            Node::Variable(ident) => Node::Variable(ident),
+            Node::Not(p) => Node::Not(self.intern(&p)),
+            Node::And(p1, p2) => Node::And(self.intern(&p1), self.intern(&p2)),
+            Node::Or(p1, p2) => Node::Or(self.intern(&p1), self.intern(&p2)),
+            Node::Nand(p1, p2) => Node::Nand(self.intern(&p1), self.intern(&p2)),
+            Node::Nor(p1, p2) => Node::Nor(self.intern(&p1), self.intern(&p2)),
+            Node::Ite(p1, p2, p3) => {
+                Node::Ite(self.intern(&p1), self.intern(&p2), self.intern(&p3))
+            }
+        };
+        let next = self.ids.len();
+        let id = *self.ids.entry(node).or_insert(next);
+        self.nodes.insert(formula.key(), (formula.clone(), id));
+        id
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RewriteReport {
+    pub ruleset: String,
+    pub iterations: usize,
+    pub firings: Vec<(String, usize)>,
+    pub warnings: Vec<TerminationWarning>,
+}
+
+impl fmt::Display for RewriteReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} ({} iterations)", self.ruleset, self.iterations)?;
+        for (name, count) in &self.firings {
+            writeln!(f, "{:>8} × {}", count, name)?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+impl RewriteReport {
+    pub fn total_firings(&self) -> usize {
+        self.firings.iter().map(|(_, count)| count).sum()
+    }
 }
@@ -0,0 +1,133 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, AST},
+    counting::{cube_size, enumerate_cubes, ApproxMC},
+    random::Rng,
+    sat::{Clause, XorConstraint},
+    tseitin,
+};
+
+#[derive(Debug, Clone)]
+pub struct ModelSampler {
+    clauses: Vec<Clause>,
+    variables: BTreeSet<Ident>,
+    counter: ApproxMC,
+    hashes: usize,
+}
+
+impl From<&AST> for ModelSampler {
+    fn from(value: &AST) -> Self {
+        Self::new(tseitin::cnf_clauses(value), value.variables())
+    }
+}
+
+impl ModelSampler {
+    const ATTEMPTS: usize = 64;
+
+    pub fn new(clauses: Vec<Clause>, variables: BTreeSet<Ident>) -> Self {
+        Self {
+            clauses,
+            variables,
+            counter: ApproxMC::default(),
+            hashes: 1,
+        }
+    }
+
+    pub fn sample_model(&mut self, rng: &mut Rng) -> Option<Model> {
+        let threshold = self.counter.threshold();
+        let cubes = enumerate_cubes(self.clauses.clone(), &[], &self.variables, threshold);
+        if cubes.is_empty() {
+            return None;
+        }
+        let total = cubes
+            .iter()
+            .map(|cube| cube_size(cube, &self.variables))
+            .fold(0, u128::saturating_add);
+        if total < threshold as u128 {
+            return Some(self.pick_uniform(&cubes, rng));
+        }
+        for _ in 0..Self::ATTEMPTS {
+            let xors = (0..self.hashes)
+                .map(|_| {
+                    let parity = rng.next_bool();
+                    XorConstraint::new(
+                        self.variables.iter().copied().filter(|_| rng.next_bool()),
+                        parity,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let cell = enumerate_cubes(self.clauses.clone(), &xors, &self.variables, threshold);
+            let size = cell
+                .iter()
+                .map(|cube| cube_size(cube, &self.variables))
+                .fold(0, u128::saturating_add);
+            match size {
+                0 => self.hashes = self.hashes.saturating_sub(1).max(1),
+                size if size >= threshold as u128 => self.hashes += 1,
+                _ => return Some(self.pick_uniform(&cell, rng)),
+            }
+        }
+        Some(self.pick_uniform(&cubes, rng))
+    }
+
+    pub fn sample_weighted_model(
+        &self,
+        weights: &HashMap<Ident, f64>,
+        rng: &mut Rng,
+    ) -> Option<Model> {
+        let cubes = enumerate_cubes(self.clauses.clone(), &[], &self.variables, usize::MAX);
+        let masses = cubes
+            .iter()
+            .map(|cube| {
+                self.variables
+                    .iter()
+                    .filter_map(|ident| {
+                        let weight = weights.get(ident).copied().unwrap_or(0.5);
+                        cube.get(*ident).map(|value| match value {
+                            true => weight,
+                            false => 1.0 - weight,
+                        })
+                    })
+                    .product::<f64>()
+            })
+            .collect::<Vec<f64>>();
+        let mut target = rng.next_f64() * masses.iter().sum::<f64>();
+        for (cube, mass) in cubes.iter().zip(&masses) {
+            if target < *mass {
+                return Some(self.complete(cube, weights, rng));
+            }
+            target -= mass;
+        }
+        cubes.last().map(|cube| self.complete(cube, weights, rng))
+    }
+
+    fn pick_uniform(&self, cubes: &[Model], rng: &mut Rng) -> Model {
+        let sizes = cubes
+            .iter()
+            .map(|cube| cube_size(cube, &self.variables))
+            .collect::<Vec<u128>>();
+        let total = sizes.iter().copied().fold(0, u128::saturating_add);
+        let mut target = ((rng.next_u64() as u128) << 64 | rng.next_u64() as u128) % total;
+        for (cube, size) in cubes.iter().zip(sizes) {
+            if target < size {
+                return self.complete(cube, &HashMap::new(), rng);
+            }
+            target -= size;
+        }
+        unreachable!()
+    }
+
+    fn complete(&self, cube: &Model, weights: &HashMap<Ident, f64>, rng: &mut Rng) -> Model {
+        let mut model = cube.clone();
+        for ident in &self.variables {
+            if !model.contains(*ident) {
+                model.set(
+                    *ident,
+                    rng.next_f64() < weights.get(ident).copied().unwrap_or(0.5),
+                );
+            }
+        }
+        model
+    }
+}
@@ -1,18 +1,44 @@
 use std::{
-    collections::{HashMap, HashSet},
-    fmt, vec,
+    collections::{BTreeSet, HashMap},
+    fmt,
+    rc::Rc,
+    vec,
 };
 
-use crate::ast::{ASTNode, AbstractSyntaxTree, Ident, AST};
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Model, Node, AST},
+    cdcl::SolveError,
+    dimacs,
+    error::Error,
+    proof::{ProofStep, ResolutionProof},
+    trace::{trace_event, trace_span},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClauseError {
+    NotALiteral(String),
+}
 
-#[derive(Hash, PartialEq, Eq, Clone)]
-enum Polarity {
+impl fmt::Display for ClauseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClauseError::NotALiteral(subformula) => {
+                write!(f, "'{}' is not a literal of a cnf clause", subformula)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClauseError {}
+
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum Polarity {
     Positive,
     Negative,
 }
 
 impl Polarity {
-    fn flip(&self) -> Polarity {
+    pub fn flip(&self) -> Polarity {
         match self {
             Polarity::Positive => Polarity::Negative,
             Polarity::Negative => Polarity::Positive,
@@ -20,8 +46,8 @@ impl Polarity {
     }
 }
 
-#[derive(Hash, PartialEq, Eq, Clone)]
-struct Literal {
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Literal {
     identifier: Ident,
     polarity: Polarity,
 }
@@ -36,17 +62,69 @@ impl fmt::Display for Literal {
 }
 
 impl Literal {
-    fn not(&self) -> Self {
+    pub fn new(identifier: Ident, polarity: Polarity) -> Self {
+        Self {
+            identifier,
+            polarity,
+        }
+    }
+
+    pub fn positive(identifier: Ident) -> Self {
+        Self::new(identifier, Polarity::Positive)
+    }
+
+    pub fn negative(identifier: Ident) -> Self {
+        Self::new(identifier, Polarity::Negative)
+    }
+
+    pub fn identifier(&self) -> Ident {
+        self.identifier
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.polarity == Polarity::Positive
+    }
+
+    pub fn polarity(&self) -> &Polarity {
+        &self.polarity
+    }
+
+    pub fn from_dimacs(value: i64) -> Self {
+        Self {
+            identifier: (value.unsigned_abs() - 1) as Ident,
+            polarity: match value > 0 {
+                true => Polarity::Positive,
+                false => Polarity::Negative,
+            },
+        }
+    }
+
+    pub fn to_dimacs(&self) -> i64 {
+        match self.polarity {
+            Polarity::Positive => self.identifier as i64 + 1,
+            Polarity::Negative => -(self.identifier as i64 + 1),
+        }
+    }
+
+    pub fn not(&self) -> Self {
         Self {
             identifier: self.identifier,
             polarity: self.polarity.flip(),
         }
     }
+
+    pub fn to_ast<T: AbstractSyntaxTree>(&self) -> T {
+        let var = T::variable(self.identifier);
+        match self.polarity {
+            Polarity::Positive => var,
+            Polarity::Negative => var.not(),
+        }
+    }
 }
 
-#[derive(Clone)]
-struct Clause {
-    literals: HashSet<Literal>,
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Clause {
+    literals: BTreeSet<Literal>,
 }
 
 impl fmt::Display for Clause {
@@ -63,33 +141,193 @@ impl fmt::Display for Clause {
     }
 }
 
+impl FromIterator<Literal> for Clause {
+    fn from_iter<I: IntoIterator<Item = Literal>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
 impl Clause {
-    fn unit(unit: Literal) -> Self {
+    pub fn new(literals: impl IntoIterator<Item = Literal>) -> Self {
         Self {
-            literals: HashSet::from([unit]),
+            literals: literals.into_iter().collect(),
         }
     }
 
+    pub fn literals(&self) -> impl Iterator<Item = &Literal> {
+        self.literals.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.literals.len()
+    }
+
     fn is_unit_clause(&self) -> bool {
         self.literals.len() == 1
     }
 
-    fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.literals.is_empty()
     }
 
-    fn contains(&self, literal: &Literal) -> bool {
+    pub fn contains(&self, literal: &Literal) -> bool {
         self.literals.contains(literal)
     }
 
+    pub fn is_satisfied(&self, model: &Model) -> bool {
+        self.literals.iter().any(|literal| {
+            model.get(literal.identifier) == Some(literal.polarity == Polarity::Positive)
+        })
+    }
+
+    pub fn resolve(left: &Clause, right: &Clause, pivot: &Literal) -> Clause {
+        let not_pivot = pivot.not();
+        Clause {
+            literals: left
+                .literals
+                .iter()
+                .filter(|literal| !(*literal == pivot || *literal == &not_pivot))
+                .chain(
+                    right
+                        .literals
+                        .iter()
+                        .filter(|literal| !(*literal == pivot || *literal == &not_pivot)),
+                )
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn to_dimacs(&self) -> String {
+        let mut line = self
+            .literals
+            .iter()
+            .map(|literal| literal.to_dimacs().to_string())
+            .collect::<Vec<String>>();
+        line.push("0".to_owned());
+        line.join(" ")
+    }
+
+    pub fn to_ast<T: AbstractSyntaxTree>(&self) -> Option<T> {
+        self.literals
+            .iter()
+            .map(|literal| literal.to_ast::<T>())
+            .reduce(|lhs, rhs| lhs.or(rhs))
+    }
+
     fn remove(&mut self, literal: &Literal) {
         self.literals.remove(literal);
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorConstraint {
+    variables: BTreeSet<Ident>,
+    parity: bool,
+}
+
+impl fmt::Display for XorConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variables = self
+            .variables
+            .iter()
+            .map(|ident| format!("var{}", ident))
+            .collect::<Vec<String>>();
+        match variables.is_empty() {
+            true => write!(f, "0 = {}", self.parity as u8),
+            false => write!(f, "{} = {}", variables.join(" ⊕ "), self.parity as u8),
+        }
+    }
+}
+
+impl XorConstraint {
+    pub fn new(variables: impl IntoIterator<Item = Ident>, parity: bool) -> Self {
+        let mut set = BTreeSet::new();
+        for ident in variables {
+            if !set.remove(&ident) {
+                set.insert(ident);
+            }
+        }
+        Self {
+            variables: set,
+            parity,
+        }
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = &Ident> {
+        self.variables.iter()
+    }
+
+    pub fn parity(&self) -> bool {
+        self.parity
+    }
+
+    pub fn to_clauses(&self, next_aux: &mut Ident) -> Vec<Clause> {
+        let mut clauses = vec![];
+        let mut variables = self.variables.iter().copied().collect::<Vec<_>>();
+        while variables.len() > 4 {
+            let chunk = variables.drain(..3).collect::<Vec<_>>();
+            let aux = *next_aux;
+            *next_aux += 1;
+            clauses.extend(Self::parity_clauses(&[chunk, vec![aux]].concat(), false));
+            variables.push(aux);
+        }
+        clauses.extend(Self::parity_clauses(&variables, self.parity));
+        clauses
+    }
+
+    fn assign(&mut self, literal: &Literal) {
+        if self.variables.remove(&literal.identifier) && literal.polarity == Polarity::Positive {
+            self.parity = !self.parity;
+        }
+    }
+
+    fn add(&mut self, other: &XorConstraint) {
+        self.variables = self
+            .variables
+            .symmetric_difference(&other.variables)
+            .copied()
+            .collect();
+        self.parity ^= other.parity;
+    }
+
+    fn parity_clauses(variables: &[Ident], parity: bool) -> Vec<Clause> {
+        (0..1u32 << variables.len())
+            .filter(|mask| (mask.count_ones() % 2 == 1) != parity)
+            .map(|mask| {
+                Clause::new(variables.iter().enumerate().map(|(bit, ident)| {
+                    match mask >> bit & 1 {
+                        1 => Literal::new(*ident, Polarity::Negative),
+                        _ => Literal::new(*ident, Polarity::Positive),
+                    }
+                }))
+            })
+            .collect()
+    }
+}
+
+pub trait Solver {
+    fn solve(&self, clauses: &[Clause]) -> Option<Model>;
+
+    fn try_solve_clauses(&self, clauses: &[Clause]) -> Result<Option<Model>, SolveError> {
+        Ok(self.solve(clauses))
+    }
+}
+
+pub trait IncompleteSolver {
+    fn try_solve_clauses(&self, clauses: &[Clause]) -> Result<Option<Model>, SolveError>;
+}
+
 #[derive(Clone)]
 pub struct DPLLSolver {
-    clauses: Vec<Clause>,
+    input: Rc<Vec<Clause>>,
+    occurrences: Rc<HashMap<Literal, Vec<usize>>>,
+    clauses: Vec<Option<Clause>>,
+    remaining: usize,
+    units: Vec<usize>,
+    conflict: Option<usize>,
+    xors: Vec<XorConstraint>,
+    trail: Vec<(Literal, Option<usize>)>,
 }
 
 impl fmt::Display for DPLLSolver {
@@ -99,6 +337,7 @@ impl fmt::Display for DPLLSolver {
             "{{{}}}",
             self.clauses
                 .iter()
+                .flatten()
                 .map(|clause| clause.to_string())
                 .collect::<Vec<String>>()
                 .join(", ")
@@ -106,120 +345,446 @@ impl fmt::Display for DPLLSolver {
     }
 }
 
-impl From<&AST> for DPLLSolver {
-    fn from(value: &AST) -> Self {
+impl TryFrom<&AST> for DPLLSolver {
+    type Error = Error;
+
+    fn try_from(value: &AST) -> Result<Self, Self::Error> {
+        Ok(Self::from(try_generate_clauses_from_tree(
+            value.cnf().formula,
+        )?))
+    }
+}
+
+impl Solver for DPLLSolver {
+    fn solve(&self, clauses: &[Clause]) -> Option<Model> {
+        let mut solver = DPLLSolver::from([&self.input[..], clauses].concat());
+        for xor in &self.xors {
+            solver.add_xor(xor.clone());
+        }
+        solver.model()
+    }
+}
+
+impl From<Vec<Clause>> for DPLLSolver {
+    fn from(input: Vec<Clause>) -> Self {
+        let mut occurrences: HashMap<Literal, Vec<usize>> = HashMap::new();
+        for (origin, clause) in input.iter().enumerate() {
+            for literal in clause.literals() {
+                occurrences.entry(literal.clone()).or_default().push(origin);
+            }
+        }
         Self {
-            clauses: generate_clauses_from_tree(value.cnf()),
+            occurrences: Rc::new(occurrences),
+            clauses: input.iter().cloned().map(Some).collect(),
+            remaining: input.len(),
+            units: (0..input.len())
+                .rev()
+                .filter(|origin| input[*origin].is_unit_clause())
+                .collect(),
+            conflict: input.iter().position(|clause| clause.is_empty()),
+            input: Rc::new(input),
+            xors: vec![],
+            trail: vec![],
         }
     }
 }
 
 impl DPLLSolver {
-    fn get_unit_clause(&self) -> Option<&Literal> {
-        self.clauses
-            .iter()
-            .find(|clause| clause.is_unit_clause())
-            .map(|clause| clause.literals.iter().next().unwrap())
+    pub fn input(&self) -> &[Clause] {
+        &self.input
     }
 
-    fn unit_propagate(&mut self, unit: &Literal) {
-        self.clauses.retain(|clause| !clause.contains(unit));
-        let not_unit = unit.not();
-        self.clauses
-            .iter_mut()
-            .for_each(|clause| clause.remove(&not_unit));
+    pub fn to_dimacs(&self) -> String {
+        dimacs::write_dimacs(&self.input)
     }
 
-    fn unit_propagation(&mut self) {
-        while let Some(unit) = self.get_unit_clause().cloned() {
-            self.unit_propagate(&unit);
+    pub fn add_xor(&mut self, constraint: XorConstraint) {
+        let mut constraint = constraint;
+        for (literal, _) in &self.trail {
+            constraint.assign(literal);
         }
+        self.xors.push(constraint);
     }
 
-    fn pure_literal_elimination(&mut self) {
-        let mut purity_table = HashMap::new();
-        for clause in &self.clauses {
-            for literal in &clause.literals {
-                match purity_table.get_mut(literal) {
-                    Some((_, false)) => continue,
-                    Some((polarity, purity)) => *purity = *polarity == literal.polarity,
-                    None => {
-                        purity_table.insert(literal.clone(), (literal.polarity.clone(), true));
+    fn get_unit_clause(&mut self) -> Option<(usize, Literal)> {
+        while let Some(origin) = self.units.pop() {
+            if let Some(clause) = self.clauses[origin]
+                .as_ref()
+                .filter(|clause| clause.is_unit_clause())
+            {
+                return Some((origin, clause.literals.iter().next().unwrap().clone()));
+            }
+        }
+        None
+    }
+
+    fn assign(&mut self, unit: Literal, reason: Option<usize>) {
+        trace_event!(
+            match reason {
+                Some(_) => "dpll.propagation",
+                None => "dpll.decision",
+            },
+            variable = unit.identifier
+        );
+        let occurrences = Rc::clone(&self.occurrences);
+        for origin in occurrences.get(&unit).into_iter().flatten() {
+            if self.clauses[*origin].take().is_some() {
+                self.remaining -= 1;
+            }
+        }
+        let not_unit = unit.not();
+        for origin in occurrences.get(&not_unit).into_iter().flatten() {
+            if let Some(clause) = self.clauses[*origin].as_mut() {
+                clause.remove(&not_unit);
+                match clause.len() {
+                    0 => {
+                        self.conflict.get_or_insert(*origin);
                     }
+                    1 => self.units.push(*origin),
+                    _ => (),
                 }
             }
         }
-        purity_table.retain(|_, (_, purity)| *purity);
-        for literal in purity_table.keys() {
-            self.clauses.retain(|clause| clause.contains(literal));
+        self.xors.iter_mut().for_each(|xor| xor.assign(&unit));
+        self.trail.push((unit, reason));
+    }
+
+    fn unit_propagation(&mut self) -> Option<usize> {
+        loop {
+            if let Some(origin) = self.conflict {
+                return Some(origin);
+            }
+            if let Some((origin, unit)) = self.get_unit_clause() {
+                self.assign(unit, Some(origin));
+                continue;
+            }
+            match self.gaussian_elimination() {
+                None => return Some(self.input.len()),
+                Some(units) if units.is_empty() => return None,
+                Some(units) => units.into_iter().for_each(|unit| self.assign(unit, None)),
+            }
         }
     }
 
-    fn with_unit_clause(&mut self, unit: Literal) -> Self {
-        let mut new = self.clone();
-        new.clauses.push(Clause::unit(unit));
-        new
+    fn gaussian_elimination(&mut self) -> Option<Vec<Literal>> {
+        let mut reduced: Vec<(Ident, XorConstraint)> = vec![];
+        for mut row in self.xors.drain(..) {
+            for (pivot, other) in &reduced {
+                if row.variables.contains(pivot) {
+                    row.add(other);
+                }
+            }
+            let Some(&pivot) = row.variables.iter().next() else {
+                if row.parity {
+                    return None;
+                }
+                continue;
+            };
+            for (_, other) in reduced.iter_mut() {
+                if other.variables.contains(&pivot) {
+                    other.add(&row);
+                }
+            }
+            reduced.push((pivot, row));
+        }
+        self.xors = reduced.into_iter().map(|(_, row)| row).collect();
+        Some(
+            self.xors
+                .iter()
+                .filter(|row| row.variables.len() == 1)
+                .map(|row| {
+                    let ident = *row.variables.iter().next().unwrap();
+                    Literal::new(
+                        ident,
+                        match row.parity {
+                            true => Polarity::Positive,
+                            false => Polarity::Negative,
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn pure_literal_elimination(&mut self) {
+        let occurring = self
+            .clauses
+            .iter()
+            .flatten()
+            .flat_map(|clause| clause.literals.iter())
+            .cloned()
+            .collect::<BTreeSet<Literal>>();
+        let pure = occurring
+            .iter()
+            .filter(|literal| !occurring.contains(&literal.not()))
+            .filter(|literal| {
+                !self
+                    .xors
+                    .iter()
+                    .any(|xor| xor.variables.contains(&literal.identifier))
+            })
+            .collect::<BTreeSet<_>>();
+        self.trail
+            .extend(pure.iter().map(|literal| ((*literal).clone(), None)));
+        for literal in pure {
+            for origin in self.occurrences.get(literal).into_iter().flatten() {
+                if self.clauses[*origin].take().is_some() {
+                    self.remaining -= 1;
+                }
+            }
+        }
     }
 
     fn choose_literal(&self) -> Literal {
-        self.clauses[0].literals.iter().next().unwrap().clone()
+        match self.clauses.iter().flatten().next() {
+            Some(clause) => clause.literals.iter().next().unwrap().clone(),
+            None => Literal::new(
+                *self.xors[0].variables.iter().next().unwrap(),
+                Polarity::Positive,
+            ),
+        }
     }
 
     pub fn dpll(&mut self) -> bool {
-        self.unit_propagation();
-        self.pure_literal_elimination();
-        if self.clauses.is_empty() {
-            return true;
+        trace_span!("dpll");
+        self.search(&mut None).is_ok()
+    }
+
+    pub fn model(&mut self) -> Option<Model> {
+        trace_span!("dpll");
+        let input = self.input.clone();
+        let model = self.search(&mut None).ok();
+        debug_assert!(model
+            .as_ref()
+            .is_none_or(|model| input.iter().all(|clause| clause.is_satisfied(model))));
+        model
+    }
+
+    pub fn refute(&mut self) -> Option<ResolutionProof> {
+        self.model_or_refutation().err()
+    }
+
+    pub fn model_or_refutation(&mut self) -> Result<Model, ResolutionProof> {
+        if !self.xors.is_empty() {
+            return self.with_xors_as_clauses().model_or_refutation();
         }
-        if self.clauses.iter().any(|clause| clause.is_empty()) {
-            return false;
+        let mut log = Some(ProofLog::default());
+        self.search(&mut log).map_err(|derived| {
+            let mut steps = log.unwrap_or_default().steps;
+            steps.truncate(derived + 1);
+            ResolutionProof { steps }
+        })
+    }
+
+    fn with_xors_as_clauses(&self) -> DPLLSolver {
+        let mut next_aux = self
+            .input
+            .iter()
+            .flat_map(|clause| clause.literals())
+            .map(|literal| literal.identifier)
+            .chain(self.xors.iter().flat_map(|xor| xor.variables().copied()))
+            .max()
+            .map_or(0, |ident| ident + 1);
+        let mut input = self.input.to_vec();
+        for xor in &self.xors {
+            input.extend(xor.to_clauses(&mut next_aux));
         }
-        let unit = self.choose_literal();
-        self.with_unit_clause(unit.not()).dpll() || self.with_unit_clause(unit).dpll()
+        DPLLSolver::from(input)
+    }
+
+    fn branch(&self, decision: Literal, log: &mut Option<ProofLog>) -> Result<Model, usize> {
+        let mut new = self.clone();
+        new.assign(decision, None);
+        new.search(log)
+    }
+
+    fn search(&mut self, log: &mut Option<ProofLog>) -> Result<Model, usize> {
+        let trail_start = self.trail.len();
+        let conflict = match self.unit_propagation() {
+            Some(origin) => {
+                trace_event!("dpll.conflict", trail = self.trail.len());
+                Err(self.premise(origin, log))
+            }
+            None => {
+                self.pure_literal_elimination();
+                if self.remaining == 0 && self.xors.is_empty() {
+                    return Ok(self
+                        .trail
+                        .iter()
+                        .map(|(literal, _)| {
+                            (literal.identifier, literal.polarity == Polarity::Positive)
+                        })
+                        .collect());
+                }
+                let unit = self.choose_literal();
+                match self.branch(unit.not(), log) {
+                    Ok(model) => return Ok(model),
+                    Err(negative) if Self::independent(negative, &unit, log) => Err(negative),
+                    Err(negative) => match self.branch(unit.clone(), log) {
+                        Ok(model) => return Ok(model),
+                        Err(positive) => Err(Self::combine(negative, positive, &unit, log)),
+                    },
+                }
+            }
+        };
+        conflict.map_err(|derived| self.explain(derived, trail_start, log))
+    }
+
+    fn premise(&self, origin: usize, log: &mut Option<ProofLog>) -> usize {
+        match log {
+            Some(log) => log.premise(&self.input[origin]),
+            None => 0,
+        }
+    }
+
+    fn independent(negative: usize, unit: &Literal, log: &Option<ProofLog>) -> bool {
+        log.as_ref()
+            .is_some_and(|log| !log.clause(negative).contains(unit))
+    }
+
+    fn combine(
+        negative: usize,
+        positive: usize,
+        unit: &Literal,
+        log: &mut Option<ProofLog>,
+    ) -> usize {
+        let Some(log) = log else { return 0 };
+        let in_negative = log.clause(negative).contains(unit);
+        let in_positive = log.clause(positive).contains(&unit.not());
+        match (in_negative, in_positive) {
+            (true, true) => log.resolve(negative, positive, unit),
+            (false, _) => negative,
+            (true, false) => positive,
+        }
+    }
+
+    fn explain(&self, mut derived: usize, trail_start: usize, log: &mut Option<ProofLog>) -> usize {
+        let Some(log) = log else { return 0 };
+        for (literal, reason) in self.trail[trail_start..].iter().rev() {
+            if let Some(origin) = reason {
+                if log.clause(derived).contains(&literal.not()) {
+                    let antecedent = log.premise(&self.input[*origin]);
+                    derived = log.resolve(antecedent, derived, literal);
+                }
+            }
+        }
+        derived
     }
 }
 
-fn generate_clauses_from_tree(ast: AST) -> Vec<Clause> {
-    let mut clauses = Vec::new();
-    let mut subtrees = vec![&ast];
-    while let Some(subtree) = subtrees.pop() {
-        match &**subtree {
-            ASTNode::And(p1, p2) => {
-                subtrees.push(p1);
-                subtrees.push(p2);
+#[derive(Default)]
+struct ProofLog {
+    steps: Vec<ProofStep>,
+    premises: HashMap<Vec<(Ident, bool)>, usize>,
+}
+
+impl ProofLog {
+    fn clause(&self, index: usize) -> &Clause {
+        self.steps[index].clause()
+    }
+
+    fn premise(&mut self, clause: &Clause) -> usize {
+        let mut key = clause
+            .literals
+            .iter()
+            .map(|literal| (literal.identifier, literal.polarity == Polarity::Positive))
+            .collect::<Vec<_>>();
+        key.sort();
+        if let Some(index) = self.premises.get(&key) {
+            return *index;
+        }
+        self.steps.push(ProofStep::Premise(clause.clone()));
+        self.premises.insert(key, self.steps.len() - 1);
+        self.steps.len() - 1
+    }
+
+    fn resolve(&mut self, left: usize, right: usize, pivot: &Literal) -> usize {
+        let resolvent = Clause::resolve(self.clause(left), self.clause(right), pivot);
+        self.steps.push(ProofStep::Resolution {
+            left,
+            right,
+            pivot: pivot.identifier,
+            resolvent,
+        });
+        self.steps.len() - 1
+    }
+}
+
+pub fn try_generate_clauses_from_tree<T: AbstractSyntaxTree>(
+    ast: T,
+) -> Result<Vec<Clause>, ClauseError> {
+    stream_clauses_from_tree(ast).collect()
+}
+
+pub fn stream_clauses_from_tree<T: AbstractSyntaxTree>(ast: T) -> ClauseStream<T> {
+    ClauseStream {
+        subtrees: vec![ast],
+    }
+}
+
+pub struct ClauseStream<T> {
+    subtrees: Vec<T>,
+}
+
+impl<T: AbstractSyntaxTree> Iterator for ClauseStream<T> {
+    type Item = Result<Clause, ClauseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(subtree) = self.subtrees.pop() {
+            match subtree.node() {
+                Node::And(p1, p2) => {
+                    self.subtrees.push(p2);
+                    self.subtrees.push(p1);
+                }
+                _ => return Some(generate_clause_from_subtree(subtree)),
             }
-            _ => clauses.push(generate_clause_from_subtree(subtree)),
         }
+        None
     }
-    clauses
 }
 
-fn generate_clause_from_subtree(ast: &AST) -> Clause {
-    let mut literals = HashSet::new();
+fn generate_clause_from_subtree<T: AbstractSyntaxTree>(ast: T) -> Result<Clause, ClauseError> {
+    let mut literals = BTreeSet::new();
     let mut subtrees = vec![ast];
-    while !subtrees.is_empty() {
-        match &**subtrees.pop().unwrap() {
-            ASTNode::Variable(ident) => {
+    while let Some(subtree) = subtrees.pop() {
+        match subtree.node() {
+            Node::Variable(ident) => {
                 literals.insert(Literal {
-                    identifier: *ident,
+                    identifier: ident,
                     polarity: Polarity::Positive,
                 });
             }
-            ASTNode::Not(variable) => match &**variable {
-                ASTNode::Variable(ident) => {
+            Node::Not(variable) => match variable.node() {
+                Node::Variable(ident) => {
                     literals.insert(Literal {
-                        identifier: *ident,
+                        identifier: ident,
                         polarity: Polarity::Negative,
                     });
                 }
-                _ => unreachable!(),
+                _ => return Err(ClauseError::NotALiteral(subtree.to_ast().to_string())),
             },
-            ASTNode::Or(p1, p2) => {
+            Node::Or(p1, p2) => {
                 subtrees.push(p1);
                 subtrees.push(p2);
             }
-            _ => unreachable!(),
+            _ => return Err(ClauseError::NotALiteral(subtree.to_ast().to_string())),
         }
     }
-    Clause { literals }
+    Ok(Clause { literals })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DPLLSolver;
+    use crate::dimacs::parse_dimacs;
+
+    #[test]
+    fn refutation_ends_at_the_empty_clause() {
+        let clauses = parse_dimacs(
+            "p cnf 4 7\n-1 -2 3 -3 0\n-3 -4 0\n-3 4 0\n3 -4 0\n1 4 0\n3 4 0\n1 2 4 0\n",
+        )
+        .unwrap();
+        let proof = DPLLSolver::from(clauses.clone()).refute().unwrap();
+        assert!(proof.check(&clauses).is_ok(), "{}", proof);
+    }
 }
@@ -0,0 +1,150 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    ast::{ASTNode, AbstractSyntaxTree, Ident, ParseError, AST},
+    parse::{self, SymbolTable},
+    transition::substitute,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    ArityMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::ArityMismatch { expected, found } => write!(
+                f,
+                "schema expects {} metavariables but {} formulas were given",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FormulaSchema {
+    pub name: String,
+    pattern: AST,
+    metavariables: Vec<Ident>,
+}
+
+impl fmt::Display for FormulaSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.pattern, self.name)
+    }
+}
+
+impl FormulaSchema {
+    pub fn new(name: &str, pattern: AST) -> Self {
+        Self {
+            name: name.to_owned(),
+            metavariables: pattern.variables().into_iter().collect(),
+            pattern,
+        }
+    }
+
+    pub fn parse(name: &str, pattern: &str) -> Result<Self, ParseError> {
+        Ok(Self::new(
+            name,
+            parse::parse_with(pattern, &mut SymbolTable::new())?,
+        ))
+    }
+
+    pub fn k() -> Self {
+        let (p, q) = (metavariable(0), metavariable(1));
+        Self::new("K", implies(&p, implies(&q, p.clone())))
+    }
+
+    pub fn s() -> Self {
+        let (p, q, r) = (metavariable(0), metavariable(1), metavariable(2));
+        Self::new(
+            "S",
+            implies(
+                &implies(&p, implies(&q, r.clone())),
+                implies(&implies(&p, q), implies(&p, r)),
+            ),
+        )
+    }
+
+    pub fn contraposition() -> Self {
+        let (p, q) = (metavariable(0), metavariable(1));
+        Self::new(
+            "contraposition",
+            implies(&implies(&p.not(), q.not()), implies(&q, p)),
+        )
+    }
+
+    pub fn pattern(&self) -> &AST {
+        &self.pattern
+    }
+
+    pub fn arity(&self) -> usize {
+        self.metavariables.len()
+    }
+
+    pub fn instantiate(&self, formulas: &[AST]) -> Result<AST, SchemaError> {
+        if formulas.len() != self.arity() {
+            return Err(SchemaError::ArityMismatch {
+                expected: self.arity(),
+                found: formulas.len(),
+            });
+        }
+        let substitution = self
+            .metavariables
+            .iter()
+            .copied()
+            .zip(formulas.iter().cloned())
+            .collect::<HashMap<_, _>>();
+        Ok(substitute(&self.pattern, &substitution))
+    }
+
+    pub fn matches(&self, formula: &AST) -> Option<Vec<AST>> {
+        let mut bindings = HashMap::new();
+        if !bind(&self.pattern, formula, &mut bindings) {
+            return None;
+        }
+        Some(
+            self.metavariables
+                .iter()
+                .map(|ident| bindings[ident].clone())
+                .collect(),
+        )
+    }
+}
+
+pub fn implies(antecedent: &AST, consequent: AST) -> AST {
+    antecedent.not().or(consequent)
+}
+
+fn metavariable(ident: Ident) -> AST {
+    <AST as AbstractSyntaxTree>::variable(ident)
+}
+
+fn bind(pattern: &AST, formula: &AST, bindings: &mut HashMap<Ident, AST>) -> bool {
+    match (&**pattern, &**formula) {
+        (ASTNode::Variable(ident), _) => match bindings.get(ident) {
+            Some(bound) => bound == formula,
+            None => {
+                bindings.insert(*ident, formula.clone());
+                true
+            }
+        },
+        (ASTNode::Not(template_p), ASTNode::Not(p)) => bind(template_p, p, bindings),
+        (ASTNode::And(template_p1, template_p2), ASTNode::And(p1, p2))
+        | (ASTNode::Or(template_p1, template_p2), ASTNode::Or(p1, p2))
+        | (ASTNode::Nand(template_p1, template_p2), ASTNode::Nand(p1, p2))
+        | (ASTNode::Nor(template_p1, template_p2), ASTNode::Nor(p1, p2)) => {
+            bind(template_p1, p1, bindings) && bind(template_p2, p2, bindings)
+        }
+        (ASTNode::Ite(template_p1, template_p2, template_p3), ASTNode::Ite(p1, p2, p3)) => {
+            bind(template_p1, p1, bindings)
+                && bind(template_p2, p2, bindings)
+                && bind(template_p3, p3, bindings)
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,342 @@
+use std::fmt;
+
+use crate::ast::{ASTNode, AST};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sequent {
+    pub antecedent: Vec<AST>,
+    pub succedent: Vec<AST>,
+}
+
+impl fmt::Display for Sequent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |formulas: &[AST]| {
+            formulas
+                .iter()
+                .map(|formula| formula.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        write!(f, "{} ⊢ {}", join(&self.antecedent), join(&self.succedent))
+    }
+}
+
+impl From<&AST> for Sequent {
+    fn from(value: &AST) -> Self {
+        Self::new(vec![], vec![value.clone()])
+    }
+}
+
+impl Sequent {
+    pub fn new(antecedent: Vec<AST>, succedent: Vec<AST>) -> Self {
+        Self {
+            antecedent,
+            succedent,
+        }
+    }
+
+    fn is_axiom(&self) -> bool {
+        self.antecedent.iter().any(|lhs| {
+            matches!(**lhs, ASTNode::Variable(_)) && self.succedent.iter().any(|rhs| lhs == rhs)
+        })
+    }
+
+    fn to_latex(&self) -> String {
+        let join = |formulas: &[AST]| {
+            formulas
+                .iter()
+                .map(formula_to_latex)
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        format!(
+            "{} \\vdash {}",
+            join(&self.antecedent),
+            join(&self.succedent)
+        )
+    }
+}
+
+fn formula_to_latex(formula: &AST) -> String {
+    match &**formula {
+        ASTNode::Variable(ident) => format!("p_{{{}}}", ident),
+        ASTNode::Not(p) => format!("\\neg {}", formula_to_latex(p)),
+        ASTNode::And(p1, p2) => {
+            format!("({} \\land {})", formula_to_latex(p1), formula_to_latex(p2))
+        }
+        ASTNode::Or(p1, p2) => format!("({} \\lor {})", formula_to_latex(p1), formula_to_latex(p2)),
+        ASTNode::Nand(p1, p2) => format!(
+            "({} \\uparrow {})",
+            formula_to_latex(p1),
+            formula_to_latex(p2)
+        ),
+        ASTNode::Nor(p1, p2) => format!(
+            "({} \\downarrow {})",
+            formula_to_latex(p1),
+            formula_to_latex(p2)
+        ),
+        ASTNode::Ite(p1, p2, p3) => format!(
+            "({} \\mathrel{{?}} {} : {})",
+            formula_to_latex(p1),
+            formula_to_latex(p2),
+            formula_to_latex(p3)
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LKRule {
+    Axiom,
+    NotLeft,
+    NotRight,
+    AndLeft,
+    AndRight,
+    OrLeft,
+    OrRight,
+    NandLeft,
+    NandRight,
+    NorLeft,
+    NorRight,
+    IteLeft,
+    IteRight,
+}
+
+impl fmt::Display for LKRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LKRule::Axiom => write!(f, "Ax"),
+            LKRule::NotLeft => write!(f, "¬L"),
+            LKRule::NotRight => write!(f, "¬R"),
+            LKRule::AndLeft => write!(f, "∧L"),
+            LKRule::AndRight => write!(f, "∧R"),
+            LKRule::OrLeft => write!(f, "∨L"),
+            LKRule::OrRight => write!(f, "∨R"),
+            LKRule::NandLeft => write!(f, "↑L"),
+            LKRule::NandRight => write!(f, "↑R"),
+            LKRule::NorLeft => write!(f, "↓L"),
+            LKRule::NorRight => write!(f, "↓R"),
+            LKRule::IteLeft => write!(f, "?L"),
+            LKRule::IteRight => write!(f, "?R"),
+        }
+    }
+}
+
+impl LKRule {
+    fn to_latex(self) -> &'static str {
+        match self {
+            LKRule::Axiom => "\\textsc{Ax}",
+            LKRule::NotLeft => "$\\neg L$",
+            LKRule::NotRight => "$\\neg R$",
+            LKRule::AndLeft => "$\\land L$",
+            LKRule::AndRight => "$\\land R$",
+            LKRule::OrLeft => "$\\lor L$",
+            LKRule::OrRight => "$\\lor R$",
+            LKRule::NandLeft => "$\\uparrow L$",
+            LKRule::NandRight => "$\\uparrow R$",
+            LKRule::NorLeft => "$\\downarrow L$",
+            LKRule::NorRight => "$\\downarrow R$",
+            LKRule::IteLeft => "$? L$",
+            LKRule::IteRight => "$? R$",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SequentProof {
+    pub conclusion: Sequent,
+    pub rule: LKRule,
+    pub premises: Vec<SequentProof>,
+}
+
+impl fmt::Display for SequentProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tree(f, 0)
+    }
+}
+
+impl SequentProof {
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{}{} ({})",
+            "  ".repeat(depth),
+            self.conclusion,
+            self.rule
+        )?;
+        for premise in &self.premises {
+            premise.write_tree(f, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_bussproofs(&self) -> String {
+        let mut latex = "\\begin{prooftree}\n".to_owned();
+        self.write_bussproofs(&mut latex);
+        latex.push_str("\\end{prooftree}\n");
+        latex
+    }
+
+    fn write_bussproofs(&self, latex: &mut String) {
+        for premise in &self.premises {
+            premise.write_bussproofs(latex);
+        }
+        let inference = match self.premises.len() {
+            0 => {
+                latex.push_str("\\AxiomC{}\n");
+                "UnaryInfC"
+            }
+            1 => "UnaryInfC",
+            _ => "BinaryInfC",
+        };
+        latex.push_str(&format!("\\RightLabel{{{}}}\n", self.rule.to_latex()));
+        latex.push_str(&format!(
+            "\\{}{{${}$}}\n",
+            inference,
+            self.conclusion.to_latex()
+        ));
+    }
+
+    pub fn depth(&self) -> usize {
+        1 + self
+            .premises
+            .iter()
+            .map(|premise| premise.depth())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn decompose(formulas: &[AST]) -> Option<(usize, &AST)> {
+    formulas
+        .iter()
+        .enumerate()
+        .find(|(_, formula)| !matches!(***formula, ASTNode::Variable(_)))
+}
+
+fn without(formulas: &[AST], index: usize) -> Vec<AST> {
+    let mut rest = formulas.to_vec();
+    rest.remove(index);
+    rest
+}
+
+pub fn prove(sequent: &Sequent) -> Option<SequentProof> {
+    let (rule, premises) = if sequent.is_axiom() {
+        (LKRule::Axiom, vec![])
+    } else if let Some((index, formula)) = decompose(&sequent.antecedent) {
+        let rest = without(&sequent.antecedent, index);
+        let with = |extra: Vec<AST>, succedent: Vec<AST>| {
+            let mut antecedent = rest.clone();
+            antecedent.extend(extra);
+            Sequent::new(antecedent, succedent)
+        };
+        match &**formula {
+            ASTNode::Not(p) => {
+                let mut succedent = sequent.succedent.clone();
+                succedent.push(p.clone());
+                (LKRule::NotLeft, vec![with(vec![], succedent)])
+            }
+            ASTNode::And(p1, p2) => (
+                LKRule::AndLeft,
+                vec![with(
+                    vec![p1.clone(), p2.clone()],
+                    sequent.succedent.clone(),
+                )],
+            ),
+            ASTNode::Or(p1, p2) => (
+                LKRule::OrLeft,
+                vec![
+                    with(vec![p1.clone()], sequent.succedent.clone()),
+                    with(vec![p2.clone()], sequent.succedent.clone()),
+                ],
+            ),
+            ASTNode::Nand(p1, p2) => {
+                let premise = |p: &AST| {
+                    let mut succedent = sequent.succedent.clone();
+                    succedent.push(p.clone());
+                    with(vec![], succedent)
+                };
+                (LKRule::NandLeft, vec![premise(p1), premise(p2)])
+            }
+            ASTNode::Nor(p1, p2) => {
+                let mut succedent = sequent.succedent.clone();
+                succedent.extend([p1.clone(), p2.clone()]);
+                (LKRule::NorLeft, vec![with(vec![], succedent)])
+            }
+            ASTNode::Ite(p1, p2, p3) => {
+                let mut succedent = sequent.succedent.clone();
+                succedent.push(p1.clone());
+                (
+                    LKRule::IteLeft,
+                    vec![
+                        with(vec![p1.clone(), p2.clone()], sequent.succedent.clone()),
+                        with(vec![p3.clone()], succedent),
+                    ],
+                )
+            }
+            ASTNode::Variable(_) => unreachable!(),
+        }
+    } else if let Some((index, formula)) = decompose(&sequent.succedent) {
+        let rest = without(&sequent.succedent, index);
+        let with = |antecedent: Vec<AST>, extra: Vec<AST>| {
+            let mut succedent = rest.clone();
+            succedent.extend(extra);
+            Sequent::new(antecedent, succedent)
+        };
+        match &**formula {
+            ASTNode::Not(p) => {
+                let mut antecedent = sequent.antecedent.clone();
+                antecedent.push(p.clone());
+                (LKRule::NotRight, vec![with(antecedent, vec![])])
+            }
+            ASTNode::And(p1, p2) => (
+                LKRule::AndRight,
+                vec![
+                    with(sequent.antecedent.clone(), vec![p1.clone()]),
+                    with(sequent.antecedent.clone(), vec![p2.clone()]),
+                ],
+            ),
+            ASTNode::Or(p1, p2) => (
+                LKRule::OrRight,
+                vec![with(
+                    sequent.antecedent.clone(),
+                    vec![p1.clone(), p2.clone()],
+                )],
+            ),
+            ASTNode::Nand(p1, p2) => {
+                let mut antecedent = sequent.antecedent.clone();
+                antecedent.extend([p1.clone(), p2.clone()]);
+                (LKRule::NandRight, vec![with(antecedent, vec![])])
+            }
+            ASTNode::Nor(p1, p2) => {
+                let premise = |p: &AST| {
+                    let mut antecedent = sequent.antecedent.clone();
+                    antecedent.push(p.clone());
+                    with(antecedent, vec![])
+                };
+                (LKRule::NorRight, vec![premise(p1), premise(p2)])
+            }
+            ASTNode::Ite(p1, p2, p3) => {
+                let mut antecedent = sequent.antecedent.clone();
+                antecedent.push(p1.clone());
+                (
+                    LKRule::IteRight,
+                    vec![
+                        with(antecedent, vec![p2.clone()]),
+                        with(sequent.antecedent.clone(), vec![p1.clone(), p3.clone()]),
+                    ],
+                )
+            }
+            ASTNode::Variable(_) => unreachable!(),
+        }
+    } else {
+        return None;
+    };
+    Some(SequentProof {
+        conclusion: sequent.clone(),
+        rule,
+        premises: premises
+            .iter()
+            .map(prove)
+            .collect::<Option<Vec<SequentProof>>>()?,
+    })
+}
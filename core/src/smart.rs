@@ -0,0 +1,71 @@
+use crate::ast::{AbstractSyntaxTree, Node};
+
+pub trait SmartBuild: AbstractSyntaxTree {
+    fn smart_not(&self) -> Self {
+        match (self.node(), self.as_constant()) {
+            (_, Some(value)) => constant(!value, self),
+            (Node::Not(p), None) => p,
+            _ => self.not(),
+        }
+    }
+
+    fn smart_and(&self, other: Self) -> Self {
+        match (self.as_constant(), other.as_constant()) {
+            (Some(false), _) => self.clone(),
+            (_, Some(false)) => other,
+            (Some(true), _) => other,
+            (_, Some(true)) => self.clone(),
+            _ if *self == other => self.clone(),
+            _ if complementary(self, &other) => constant(false, self),
+            _ => self.and(other),
+        }
+    }
+
+    fn smart_or(&self, other: Self) -> Self {
+        match (self.as_constant(), other.as_constant()) {
+            (Some(true), _) => self.clone(),
+            (_, Some(true)) => other,
+            (Some(false), _) => other,
+            (_, Some(false)) => self.clone(),
+            _ if *self == other => self.clone(),
+            _ if complementary(self, &other) => constant(true, self),
+            _ => self.or(other),
+        }
+    }
+
+    fn as_constant(&self) -> Option<bool> {
+        let (Node::And(p1, p2) | Node::Or(p1, p2)) = self.node() else {
+            return None;
+        };
+        let literal_pair = match (p1.node(), p2.node()) {
+            (Node::Variable(var), Node::Not(p)) | (Node::Not(p), Node::Variable(var)) => {
+                p.node() == Node::Variable(var)
+            }
+            _ => false,
+        };
+        match (self.node(), literal_pair) {
+            (Node::And(..), true) => Some(false),
+            (Node::Or(..), true) => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl<T: AbstractSyntaxTree> SmartBuild for T {}
+
+fn complementary<T: AbstractSyntaxTree>(lhs: &T, rhs: &T) -> bool {
+    match (lhs.node(), rhs.node()) {
+        (Node::Not(p), _) if p == *rhs => true,
+        (_, Node::Not(p)) => p == *lhs,
+        _ => false,
+    }
+}
+
+fn constant<T: AbstractSyntaxTree>(value: bool, within: &T) -> T {
+    let ident = within.variables().first().copied().unwrap_or(0);
+    let var = T::variable(ident);
+    match value {
+        true => var.or(var.not()),
+        false => var.and(var.not()),
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use crate::encodings::{self, Explanation, Sudoku};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SudokuError {
+    InvalidCharacter {
+        character: char,
+        position: usize,
+    },
+    CellCount {
+        count: usize,
+    },
+    InvalidSize {
+        size: usize,
+    },
+    RaggedRow {
+        row: usize,
+        length: usize,
+    },
+    InvalidDigit {
+        row: usize,
+        column: usize,
+        digit: usize,
+    },
+    Unsolvable(Explanation),
+}
+
+impl fmt::Display for SudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SudokuError::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "unexpected character '{}' at position {}",
+                character, position
+            ),
+            SudokuError::CellCount { count } => {
+                write!(f, "{} cells do not form a sudoku grid", count)
+            }
+            SudokuError::InvalidSize { size } => {
+                write!(f, "a grid with {} rows has no square boxes", size)
+            }
+            SudokuError::RaggedRow { row, length } => {
+                write!(f, "row {} has {} cells", row, length)
+            }
+            SudokuError::InvalidDigit { row, column, digit } => {
+                write!(
+                    f,
+                    "cell ({}, {}) holds invalid digit {}",
+                    row, column, digit
+                )
+            }
+            SudokuError::Unsolvable(explanation) => {
+                write!(f, "the puzzle has no solution:\n{}", explanation)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SudokuError {}
+
+pub fn parse(input: &str) -> Result<Vec<Vec<usize>>, SudokuError> {
+    let mut cells = vec![];
+    for (position, character) in input.chars().enumerate() {
+        match character {
+            '.' | '0' => cells.push(0),
+            '1'..='9' => cells.push(character as usize - '0' as usize),
+            '|' | '-' | '+' => (),
+            character if character.is_whitespace() => (),
+            character => {
+                return Err(SudokuError::InvalidCharacter {
+                    character,
+                    position,
+                })
+            }
+        }
+    }
+    let size = match cells.len() {
+        1 => 1,
+        16 => 4,
+        81 => 9,
+        count => return Err(SudokuError::CellCount { count }),
+    };
+    Ok(cells.chunks(size).map(<[usize]>::to_vec).collect())
+}
+
+pub fn format(grid: &[Vec<usize>]) -> String {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|digit| match digit {
+                    0 => ".".to_owned(),
+                    digit => digit.to_string(),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn solve(grid: &[Vec<usize>]) -> Result<Vec<Vec<usize>>, SudokuError> {
+    let puzzle = Sudoku::with_block(block(grid)?, grid.to_vec());
+    match encodings::solve(&puzzle) {
+        Some(solution) => {
+            debug_assert!(is_solution(grid, &solution));
+            Ok(solution)
+        }
+        None => Err(SudokuError::Unsolvable(
+            encodings::explain(&puzzle).unwrap_or(Explanation { conflicts: vec![] }),
+        )),
+    }
+}
+
+pub fn is_solution(grid: &[Vec<usize>], solution: &[Vec<usize>]) -> bool {
+    let Ok(block) = self::block(solution) else {
+        return false;
+    };
+    let size = block * block;
+    let complete = |cells: Vec<usize>| {
+        let mut seen = vec![false; size + 1];
+        cells
+            .into_iter()
+            .all(|digit| (1..=size).contains(&digit) && !std::mem::replace(&mut seen[digit], true))
+    };
+    grid.len() == size
+        && grid.iter().zip(solution).all(|(given, row)| {
+            given.len() == size
+                && given
+                    .iter()
+                    .zip(row)
+                    .all(|(given, digit)| *given == 0 || given == digit)
+        })
+        && (0..size).all(|index| {
+            let (top, left) = (index / block * block, index % block * block);
+            complete(solution[index].clone())
+                && complete(solution.iter().map(|row| row[index]).collect())
+                && complete(
+                    (0..size)
+                        .map(|cell| solution[top + cell / block][left + cell % block])
+                        .collect(),
+                )
+        })
+}
+
+fn block(grid: &[Vec<usize>]) -> Result<usize, SudokuError> {
+    let size = grid.len();
+    let block = (0..=size)
+        .find(|block| block * block >= size)
+        .filter(|block| block * block == size)
+        .ok_or(SudokuError::InvalidSize { size })?;
+    for (row, cells) in grid.iter().enumerate() {
+        if cells.len() != size {
+            return Err(SudokuError::RaggedRow {
+                row,
+                length: cells.len(),
+            });
+        }
+        if let Some((column, digit)) = cells.iter().enumerate().find(|(_, digit)| **digit > size) {
+            return Err(SudokuError::InvalidDigit {
+                row,
+                column,
+                digit: *digit,
+            });
+        }
+    }
+    Ok(block)
+}
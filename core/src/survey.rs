@@ -0,0 +1,302 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    ast::{Ident, Model},
+    cdcl::CDCLSolver,
+    random::Rng,
+    sat::{Clause, Literal, Polarity, Solver},
+    trace::{trace_event, trace_span},
+    walksat::WalkSat,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurveyPropagation {
+    pub max_iterations: usize,
+    pub tolerance: f64,
+    pub fraction: f64,
+    pub seed: u64,
+    pub noise: f64,
+    pub flips_per_clause: usize,
+}
+
+impl Default for SurveyPropagation {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            tolerance: 1e-3,
+            fraction: 0.01,
+            seed: 0,
+            noise: 0.5,
+            flips_per_clause: 256,
+        }
+    }
+}
+
+impl Solver for SurveyPropagation {
+    fn solve(&self, clauses: &[Clause]) -> Option<Model> {
+        trace_span!("survey");
+        let idents = clauses
+            .iter()
+            .flat_map(|clause| clause.literals())
+            .map(|literal| literal.identifier())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let variables = idents
+            .iter()
+            .enumerate()
+            .map(|(index, ident)| (*ident, index))
+            .collect::<BTreeMap<_, _>>();
+        let input = clauses
+            .iter()
+            .map(|clause| {
+                clause
+                    .literals()
+                    .map(|literal| {
+                        2 * variables[&literal.identifier()] + !literal.is_positive() as usize
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|clause| !clause.iter().any(|lit| clause.contains(&(lit ^ 1))))
+            .collect::<Vec<_>>();
+        if input.iter().any(Vec::is_empty) {
+            return None;
+        }
+        let mut rng = Rng::new(self.seed);
+        let mut decimation = Decimation::new(input, idents.len());
+        let residual = loop {
+            if !decimation.propagate() {
+                trace_event!("survey.contradiction");
+                return CDCLSolver::default().solve(clauses);
+            }
+            let active = decimation.active();
+            if active.is_empty() {
+                break vec![];
+            }
+            let Some(surveys) = self.surveys(&active, idents.len(), &mut rng) else {
+                trace_event!("survey.unconverged", clauses = active.len());
+                break active;
+            };
+            if surveys
+                .iter()
+                .flatten()
+                .all(|survey| *survey < self.tolerance)
+            {
+                trace_event!("survey.paramagnetic", clauses = active.len());
+                break active;
+            }
+            let mut biases = biases(&active, &surveys, idents.len());
+            biases.sort_by(|left, right| right.0.total_cmp(&left.0).then(left.1.cmp(&right.1)));
+            let count = ((self.fraction * biases.len() as f64) as usize).max(1);
+            trace_event!("survey.decimated", fixed = count, clauses = active.len());
+            for (_, lit) in biases.into_iter().take(count) {
+                decimation.enqueue(lit);
+            }
+        };
+        let residual = residual
+            .iter()
+            .map(|clause| {
+                clause
+                    .iter()
+                    .map(|lit| {
+                        let polarity = match lit & 1 {
+                            0 => Polarity::Positive,
+                            _ => Polarity::Negative,
+                        };
+                        Literal::new(idents[lit / 2], polarity)
+                    })
+                    .collect::<Clause>()
+            })
+            .collect::<Vec<_>>();
+        let walksat = WalkSat {
+            noise: self.noise,
+            max_flips: self.flips_per_clause * residual.len(),
+            max_tries: 1,
+            ..WalkSat::default()
+        };
+        let Some(completion) = walksat
+            .solve(&residual, &mut rng)
+            .or_else(|| CDCLSolver::default().solve(&residual))
+        else {
+            trace_event!("survey.fallback");
+            return CDCLSolver::default().solve(clauses);
+        };
+        Some(
+            idents
+                .iter()
+                .enumerate()
+                .map(|(index, ident): (usize, &Ident)| {
+                    let value = match decimation.values[index] {
+                        Some(value) => value,
+                        None => completion.get(*ident).unwrap_or(false),
+                    };
+                    (*ident, value)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl SurveyPropagation {
+    fn surveys(
+        &self,
+        clauses: &[Vec<usize>],
+        variables: usize,
+        rng: &mut Rng,
+    ) -> Option<Vec<Vec<f64>>> {
+        let mut occurrences = vec![vec![]; 2 * variables];
+        for (index, clause) in clauses.iter().enumerate() {
+            for (position, lit) in clause.iter().enumerate() {
+                occurrences[*lit].push((index, position));
+            }
+        }
+        let mut surveys = clauses
+            .iter()
+            .map(|clause| clause.iter().map(|_| rng.next_f64()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut order = (0..clauses.len()).collect::<Vec<_>>();
+        for _ in 0..self.max_iterations {
+            for index in (1..order.len()).rev() {
+                order.swap(index, rng.below(index + 1));
+            }
+            let mut change = 0.0f64;
+            for &clause in &order {
+                let ratios = clauses[clause]
+                    .iter()
+                    .map(|lit| {
+                        let product = |lit: usize| {
+                            occurrences[lit]
+                                .iter()
+                                .filter(|(other, _)| *other != clause)
+                                .map(|(other, position)| 1.0 - surveys[*other][*position])
+                                .product::<f64>()
+                        };
+                        let (satisfying, unsatisfying) = (product(*lit), product(lit ^ 1));
+                        let unsatisfied = (1.0 - unsatisfying) * satisfying;
+                        let satisfied = (1.0 - satisfying) * unsatisfying;
+                        let total = unsatisfied + satisfied + satisfying * unsatisfying;
+                        match total > 0.0 {
+                            true => unsatisfied / total,
+                            false => 0.0,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                for (position, current) in surveys[clause].iter_mut().enumerate() {
+                    let survey = ratios
+                        .iter()
+                        .enumerate()
+                        .filter(|(other, _)| *other != position)
+                        .map(|(_, ratio)| ratio)
+                        .product::<f64>();
+                    change = change.max((survey - *current).abs());
+                    *current = survey;
+                }
+            }
+            if change < self.tolerance {
+                return Some(surveys);
+            }
+        }
+        None
+    }
+}
+
+fn biases(clauses: &[Vec<usize>], surveys: &[Vec<f64>], variables: usize) -> Vec<(f64, usize)> {
+    let mut products = vec![1.0; 2 * variables];
+    let mut present = vec![false; variables];
+    for (clause, lits) in clauses.iter().enumerate() {
+        for (position, lit) in lits.iter().enumerate() {
+            products[*lit] *= 1.0 - surveys[clause][position];
+            present[lit / 2] = true;
+        }
+    }
+    (0..variables)
+        .filter(|variable| present[*variable])
+        .map(|variable| {
+            let (positive, negative) = (products[2 * variable], products[2 * variable + 1]);
+            let towards_true = (1.0 - positive) * negative;
+            let towards_false = (1.0 - negative) * positive;
+            let total = towards_true + towards_false + positive * negative;
+            let (up, down) = match total > 0.0 {
+                true => (towards_true / total, towards_false / total),
+                false => (0.0, 0.0),
+            };
+            match up >= down {
+                true => (up - down, 2 * variable),
+                false => (down - up, 2 * variable + 1),
+            }
+        })
+        .collect()
+}
+
+struct Decimation {
+    clauses: Vec<Vec<usize>>,
+    occurrences: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    queue: Vec<usize>,
+}
+
+impl Decimation {
+    fn new(clauses: Vec<Vec<usize>>, variables: usize) -> Self {
+        let mut occurrences = vec![vec![]; 2 * variables];
+        let mut queue = vec![];
+        for (index, clause) in clauses.iter().enumerate() {
+            for lit in clause {
+                occurrences[*lit].push(index);
+            }
+            if let [unit] = clause[..] {
+                queue.push(unit);
+            }
+        }
+        Self {
+            clauses,
+            occurrences,
+            values: vec![None; variables],
+            queue,
+        }
+    }
+
+    fn value(&self, lit: usize) -> Option<bool> {
+        self.values[lit / 2].map(|value| value == (lit & 1 == 0))
+    }
+
+    fn enqueue(&mut self, lit: usize) {
+        self.queue.push(lit);
+    }
+
+    fn propagate(&mut self) -> bool {
+        while let Some(lit) = self.queue.pop() {
+            match self.value(lit) {
+                Some(true) => continue,
+                Some(false) => return false,
+                None => self.values[lit / 2] = Some(lit & 1 == 0),
+            }
+            let mut units = vec![];
+            for clause in &self.occurrences[lit ^ 1] {
+                let mut free = self.clauses[*clause]
+                    .iter()
+                    .filter(|lit| self.value(**lit) != Some(false));
+                match (free.next(), free.next()) {
+                    (None, _) => return false,
+                    (Some(unit), None) => units.push(*unit),
+                    _ => (),
+                }
+            }
+            self.queue.extend(units);
+        }
+        true
+    }
+
+    fn active(&self) -> Vec<Vec<usize>> {
+        self.clauses
+            .iter()
+            .filter(|clause| !clause.iter().any(|lit| self.value(*lit) == Some(true)))
+            .map(|clause| {
+                clause
+                    .iter()
+                    .copied()
+                    .filter(|lit| self.value(*lit).is_none())
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    ast::Ident,
+    sat::{Clause, Literal, Polarity},
+};
+
+pub type Permutation = BTreeMap<Ident, Ident>;
+
+struct Graph {
+    variables: usize,
+    edges: Vec<Vec<(u64, usize)>>,
+    clauses: BTreeSet<Vec<Literal>>,
+}
+
+impl Graph {
+    fn new(clauses: &[Clause]) -> Self {
+        let variables = clauses
+            .iter()
+            .flat_map(|clause| clause.literals())
+            .map(|literal| literal.identifier() as usize + 1)
+            .max()
+            .unwrap_or(0);
+        let mut edges = vec![vec![]; variables + clauses.len()];
+        for (index, clause) in clauses.iter().enumerate() {
+            for literal in clause.literals() {
+                let label = match literal.polarity() {
+                    Polarity::Positive => 0,
+                    Polarity::Negative => 1,
+                };
+                let var = literal.identifier() as usize;
+                edges[var].push((label, variables + index));
+                edges[variables + index].push((label, var));
+            }
+        }
+        Self {
+            variables,
+            edges,
+            clauses: clauses
+                .iter()
+                .map(|clause| clause.literals().cloned().collect())
+                .collect(),
+        }
+    }
+
+    fn initial(&self) -> Vec<u64> {
+        let mut colors = (0..self.edges.len())
+            .map(|vertex| match vertex < self.variables {
+                true => mix(0, 0),
+                false => mix(1, self.edges[vertex].len() as u64),
+            })
+            .collect::<Vec<_>>();
+        self.refine(&mut colors);
+        colors
+    }
+
+    fn refine(&self, colors: &mut Vec<u64>) {
+        let mut cells = distinct(colors);
+        loop {
+            let refined = (0..colors.len())
+                .map(|vertex| {
+                    let mut signature = self.edges[vertex]
+                        .iter()
+                        .map(|(label, neighbour)| mix(*label, colors[*neighbour]))
+                        .collect::<Vec<_>>();
+                    signature.sort_unstable();
+                    signature.into_iter().fold(colors[vertex], mix)
+                })
+                .collect::<Vec<_>>();
+            let refined_cells = distinct(&refined);
+            *colors = refined;
+            if refined_cells == cells {
+                return;
+            }
+            cells = refined_cells;
+        }
+    }
+
+    fn individualize(&self, colors: &[u64], vertex: usize, tag: u64) -> Vec<u64> {
+        let mut colors = colors.to_vec();
+        colors[vertex] = mix(mix(colors[vertex], u64::MAX), tag);
+        self.refine(&mut colors);
+        colors
+    }
+
+    fn align(&self, mut left: Vec<u64>, mut right: Vec<u64>) -> Option<Permutation> {
+        for tag in 0.. {
+            let left_cells = cells(&left);
+            let right_cells = cells(&right);
+            if left_cells.len() != right_cells.len()
+                || left_cells
+                    .iter()
+                    .any(|(color, cell)| right_cells.get(color).map(Vec::len) != Some(cell.len()))
+            {
+                return None;
+            }
+            let Some((color, cell)) = left_cells.iter().find(|(_, cell)| cell.len() > 1) else {
+                return Some(
+                    (0..self.variables)
+                        .map(|var| (var as Ident, right_cells[&left[var]][0] as Ident))
+                        .filter(|(var, image)| var != image)
+                        .collect(),
+                );
+            };
+            let target = right_cells[color][0];
+            left = self.individualize(&left, cell[0], tag);
+            right = self.individualize(&right, target, tag);
+        }
+        None
+    }
+
+    fn is_automorphism(&self, permutation: &Permutation) -> bool {
+        permutation
+            .values()
+            .all(|image| (*image as usize) < self.variables)
+            && self.clauses.iter().all(|clause| {
+                let mut image = clause
+                    .iter()
+                    .map(|literal| apply(permutation, literal))
+                    .collect::<Vec<_>>();
+                image.sort();
+                self.clauses.contains(&image)
+            })
+    }
+}
+
+fn mix(seed: u64, value: u64) -> u64 {
+    let mut z = seed ^ value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn distinct(colors: &[u64]) -> usize {
+    colors.iter().collect::<BTreeSet<_>>().len()
+}
+
+fn cells(colors: &[u64]) -> BTreeMap<u64, Vec<usize>> {
+    let mut cells = BTreeMap::<u64, Vec<usize>>::new();
+    for (vertex, color) in colors.iter().enumerate() {
+        cells.entry(*color).or_default().push(vertex);
+    }
+    cells
+}
+
+fn apply(permutation: &Permutation, literal: &Literal) -> Literal {
+    let ident = literal.identifier();
+    Literal::new(
+        permutation.get(&ident).copied().unwrap_or(ident),
+        literal.polarity().clone(),
+    )
+}
+
+pub fn find_symmetries(clauses: &[Clause]) -> Vec<Permutation> {
+    let graph = Graph::new(clauses);
+    let base = graph.initial();
+    let mut symmetries = vec![];
+    for cell in cells(&base[..graph.variables]).values() {
+        let Some((&first, rest)) = cell.split_first() else {
+            continue;
+        };
+        let left = graph.individualize(&base, first, 0);
+        for &other in rest {
+            let right = graph.individualize(&base, other, 0);
+            let Some(permutation) = graph.align(left.clone(), right) else {
+                continue;
+            };
+            if !permutation.is_empty()
+                && !symmetries.contains(&permutation)
+                && graph.is_automorphism(&permutation)
+            {
+                symmetries.push(permutation);
+            }
+        }
+    }
+    symmetries
+}
+
+pub fn symmetry_breaking_clauses(clauses: &[Clause], symmetries: &[Permutation]) -> Vec<Clause> {
+    let mut next = clauses
+        .iter()
+        .flat_map(|clause| clause.literals())
+        .map(|literal| literal.identifier() + 1)
+        .max()
+        .unwrap_or(0);
+    let positive = |ident| Literal::new(ident, Polarity::Positive);
+    let mut breaking = vec![];
+    for permutation in symmetries {
+        let mut equal: Option<Literal> = None;
+        for (var, image) in permutation {
+            let (a, b) = (positive(*var), positive(*image));
+            let guard = equal.iter().map(Literal::not);
+            breaking.push(Clause::new(guard.clone().chain([a.not(), b.clone()])));
+            let prefix = positive(next);
+            next += 1;
+            breaking.push(Clause::new(guard.clone().chain([
+                a.clone(),
+                b.clone(),
+                prefix.clone(),
+            ])));
+            breaking.push(Clause::new(guard.chain([a.not(), b.not(), prefix.clone()])));
+            equal = Some(prefix);
+        }
+    }
+    breaking
+}
+
+pub fn break_symmetries(clauses: &[Clause]) -> Vec<Clause> {
+    let symmetries = find_symmetries(clauses);
+    let mut broken = clauses.to_vec();
+    broken.extend(symmetry_breaking_clauses(clauses, &symmetries));
+    broken
+}
@@ -0,0 +1,192 @@
+use std::{collections::HashMap, fmt};
+
+use crate::ast::{ASTNode, AbstractSyntaxTree, Ident, Model, AST};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BranchStatus {
+    Closed(Ident),
+    Open,
+    Expanded,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableauNode {
+    pub formulas: Vec<AST>,
+    pub children: Vec<TableauNode>,
+    pub status: BranchStatus,
+}
+
+impl TableauNode {
+    fn expand(
+        mut pending: Vec<AST>,
+        mut branching: Vec<(AST, AST)>,
+        mut literals: HashMap<Ident, bool>,
+    ) -> Self {
+        let mut formulas = vec![];
+        while let Some(formula) = pending.pop() {
+            formulas.push(formula.clone());
+            match &*formula {
+                ASTNode::Variable(ident) => {
+                    if literals.insert(*ident, true) == Some(false) {
+                        return Self::closed(formulas, *ident);
+                    }
+                }
+                ASTNode::And(p1, p2) => {
+                    pending.push(p2.clone());
+                    pending.push(p1.clone());
+                }
+                ASTNode::Or(p1, p2) => branching.push((p1.clone(), p2.clone())),
+                ASTNode::Nand(p1, p2) => branching.push((p1.not(), p2.not())),
+                ASTNode::Nor(p1, p2) => {
+                    pending.push(p2.not());
+                    pending.push(p1.not());
+                }
+                ASTNode::Ite(p1, p2, p3) => {
+                    branching.push((p1.and(p2.clone()), p1.not().and(p3.clone())))
+                }
+                ASTNode::Not(p) => match &**p {
+                    ASTNode::Variable(ident) => {
+                        if literals.insert(*ident, false) == Some(true) {
+                            return Self::closed(formulas, *ident);
+                        }
+                    }
+                    ASTNode::Not(q) => pending.push(q.clone()),
+                    ASTNode::Or(q1, q2) => {
+                        pending.push(q2.not());
+                        pending.push(q1.not());
+                    }
+                    ASTNode::And(q1, q2) => branching.push((q1.not(), q2.not())),
+                    ASTNode::Nand(q1, q2) => {
+                        pending.push(q2.clone());
+                        pending.push(q1.clone());
+                    }
+                    ASTNode::Nor(q1, q2) => branching.push((q1.clone(), q2.clone())),
+                    ASTNode::Ite(q1, q2, q3) => {
+                        branching.push((q1.and(q2.not()), q1.not().and(q3.not())))
+                    }
+                },
+            }
+        }
+        match branching.pop() {
+            None => Self {
+                formulas,
+                children: vec![],
+                status: BranchStatus::Open,
+            },
+            Some((left, right)) => Self {
+                formulas,
+                children: [left, right]
+                    .into_iter()
+                    .map(|formula| Self::expand(vec![formula], branching.clone(), literals.clone()))
+                    .collect(),
+                status: BranchStatus::Expanded,
+            },
+        }
+    }
+
+    fn closed(formulas: Vec<AST>, ident: Ident) -> Self {
+        Self {
+            formulas,
+            children: vec![],
+            status: BranchStatus::Closed(ident),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        match self.status {
+            BranchStatus::Closed(_) => true,
+            BranchStatus::Open => false,
+            BranchStatus::Expanded => self.children.iter().all(|child| child.is_closed()),
+        }
+    }
+
+    fn open_branch(&self, literals: &mut Model) -> bool {
+        for formula in &self.formulas {
+            match &**formula {
+                ASTNode::Variable(ident) => {
+                    literals.set(*ident, true);
+                }
+                ASTNode::Not(p) => {
+                    if let ASTNode::Variable(ident) = &**p {
+                        literals.set(*ident, false);
+                    }
+                }
+                _ => (),
+            }
+        }
+        match self.status {
+            BranchStatus::Closed(_) => false,
+            BranchStatus::Open => true,
+            BranchStatus::Expanded => self.children.iter().any(|child| {
+                let mut branch = literals.clone();
+                let open = child.open_branch(&mut branch);
+                if open {
+                    *literals = branch;
+                }
+                open
+            }),
+        }
+    }
+
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, prefix: &str) -> fmt::Result {
+        for formula in &self.formulas {
+            writeln!(f, "{}{}", prefix, formula)?;
+        }
+        match self.status {
+            BranchStatus::Closed(ident) => writeln!(f, "{}× (var{})", prefix, ident)?,
+            BranchStatus::Open => writeln!(f, "{}○", prefix)?,
+            BranchStatus::Expanded => (),
+        }
+        for (index, child) in self.children.iter().enumerate() {
+            let last = index + 1 == self.children.len();
+            writeln!(f, "{}{}", prefix, if last { "└─┐" } else { "├─┐" })?;
+            let nested = format!("{}{}", prefix, if last { "  " } else { "│ " });
+            child.write_tree(f, &nested)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tableau {
+    pub formula: AST,
+    pub root: TableauNode,
+}
+
+impl fmt::Display for Tableau {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.write_tree(f, "")
+    }
+}
+
+impl Tableau {
+    pub fn new(formula: &AST) -> Self {
+        Self {
+            formula: formula.clone(),
+            root: TableauNode::expand(vec![formula.not()], vec![], HashMap::new()),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.root.is_closed()
+    }
+
+    pub fn countermodel(&self) -> Option<Model> {
+        let mut literals = Model::new();
+        if !self.root.open_branch(&mut literals) {
+            return None;
+        }
+        for ident in self.formula.variables() {
+            literals.set_default(ident, false);
+        }
+        Some(literals)
+    }
+}
+
+pub fn prove(formula: &AST) -> Result<Tableau, Model> {
+    let tableau = Tableau::new(formula);
+    match tableau.countermodel() {
+        Some(model) => Err(model),
+        None => Ok(tableau),
+    }
+}
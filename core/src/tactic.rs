@@ -0,0 +1,232 @@
+use std::fmt;
+
+use crate::{
+    ast::ParseError,
+    interactive::{ProofState, ProofStateError, Rule},
+};
+
+const REPEAT_LIMIT: usize = 1_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tactic {
+    Rule(Rule),
+    Then(Box<Tactic>, Box<Tactic>),
+    Repeat(Box<Tactic>),
+    First(Vec<Tactic>),
+    Try(Box<Tactic>),
+}
+
+impl fmt::Display for Tactic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tactic::Rule(rule) => write!(f, "{}", rule),
+            Tactic::Then(first, second) => write!(f, "{}; {}", first, second),
+            Tactic::Repeat(tactic) => write!(f, "repeat ({})", tactic),
+            Tactic::First(alternatives) => {
+                let alternatives = alternatives
+                    .iter()
+                    .map(|tactic| tactic.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "first [{}]", alternatives.join(" | "))
+            }
+            Tactic::Try(tactic) => write!(f, "try ({})", tactic),
+        }
+    }
+}
+
+impl From<Rule> for Tactic {
+    fn from(value: Rule) -> Self {
+        Tactic::Rule(value)
+    }
+}
+
+impl Tactic {
+    pub fn then(self, next: impl Into<Tactic>) -> Self {
+        Tactic::Then(Box::new(self), Box::new(next.into()))
+    }
+
+    pub fn repeat(self) -> Self {
+        Tactic::Repeat(Box::new(self))
+    }
+
+    pub fn first(alternatives: impl IntoIterator<Item = Tactic>) -> Self {
+        Tactic::First(alternatives.into_iter().collect())
+    }
+
+    pub fn attempt(self) -> Self {
+        Tactic::Try(Box::new(self))
+    }
+
+    pub fn auto() -> Self {
+        Tactic::Rule(Rule::Auto)
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            index: 0,
+            end: input.chars().count(),
+        };
+        let tactic = parser.sequence()?;
+        match parser.tokens.get(parser.index) {
+            Some((token, position)) => Err(ParseError::UnexpectedToken {
+                token: token.clone(),
+                position: *position,
+            }),
+            None => Ok(tactic),
+        }
+    }
+
+    pub fn run(&self, state: &mut ProofState) -> Result<(), ProofStateError> {
+        let saved = state.clone();
+        let result = self.run_unguarded(state);
+        if result.is_err() {
+            *state = saved;
+        }
+        result
+    }
+
+    fn run_unguarded(&self, state: &mut ProofState) -> Result<(), ProofStateError> {
+        match self {
+            Tactic::Rule(rule) => state.apply(*rule),
+            Tactic::Then(first, second) => {
+                first.run_unguarded(state)?;
+                second.run_unguarded(state)
+            }
+            Tactic::Repeat(tactic) => {
+                for _ in 0..REPEAT_LIMIT {
+                    if state.is_complete() || tactic.run(state).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            Tactic::First(alternatives) => {
+                let mut error = ProofStateError::NoGoals;
+                for alternative in alternatives {
+                    match alternative.run(state) {
+                        Ok(()) => return Ok(()),
+                        Err(err) => error = err,
+                    }
+                }
+                Err(error)
+            }
+            Tactic::Try(tactic) => {
+                let _ = tactic.run(state);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(String, usize)>, ParseError> {
+    let mut tokens = vec![];
+    let mut word: Option<(String, usize)> = None;
+    for (position, c) in input.chars().enumerate() {
+        if c.is_alphanumeric() || c == '_' {
+            word.get_or_insert_with(|| (String::new(), position))
+                .0
+                .push(c);
+            continue;
+        }
+        tokens.extend(word.take());
+        match c {
+            ';' | '|' | '[' | ']' | '(' | ')' => tokens.push((c.to_string(), position)),
+            c if c.is_whitespace() => (),
+            character => {
+                return Err(ParseError::UnexpectedCharacter {
+                    character,
+                    position,
+                })
+            }
+        }
+    }
+    tokens.extend(word);
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(String, usize)>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Result<(String, usize), ParseError> {
+        let token = self
+            .tokens
+            .get(self.index)
+            .cloned()
+            .ok_or(ParseError::UnexpectedEndOfInput { position: self.end })?;
+        self.index += 1;
+        Ok(token)
+    }
+
+    fn next_if(&mut self, expected: &str) -> bool {
+        let matches = self
+            .tokens
+            .get(self.index)
+            .is_some_and(|(token, _)| token == expected);
+        if matches {
+            self.index += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), ParseError> {
+        match self.next()? {
+            (token, _) if token == expected => Ok(()),
+            (token, position) => Err(ParseError::UnexpectedToken { token, position }),
+        }
+    }
+
+    fn sequence(&mut self) -> Result<Tactic, ParseError> {
+        let mut tactic = self.tactic()?;
+        while self.next_if(";") {
+            tactic = tactic.then(self.tactic()?);
+        }
+        Ok(tactic)
+    }
+
+    fn tactic(&mut self) -> Result<Tactic, ParseError> {
+        let (token, position) = self.next()?;
+        let rule = match token.as_str() {
+            "(" => {
+                let inner = self.sequence()?;
+                self.expect(")")?;
+                return Ok(inner);
+            }
+            "repeat" => return Ok(self.tactic()?.repeat()),
+            "try" => return Ok(self.tactic()?.attempt()),
+            "first" => {
+                self.expect("[")?;
+                let mut alternatives = vec![self.sequence()?];
+                while self.next_if("|") {
+                    alternatives.push(self.sequence()?);
+                }
+                self.expect("]")?;
+                return Ok(Tactic::First(alternatives));
+            }
+            "intro" => Rule::Intro,
+            "split" => Rule::Split,
+            "left" => Rule::Left,
+            "right" => Rule::Right,
+            "by_contradiction" => Rule::ByContradiction,
+            "assumption" => Rule::Assumption,
+            "contradiction" => Rule::Contradiction,
+            "auto" => Rule::Auto,
+            "destruct" => Rule::Destruct(self.index_argument()?),
+            "cases" => Rule::Cases(self.index_argument()?),
+            "apply" => Rule::Apply(self.index_argument()?),
+            _ => return Err(ParseError::UnexpectedToken { token, position }),
+        };
+        Ok(Tactic::Rule(rule))
+    }
+
+    fn index_argument(&mut self) -> Result<usize, ParseError> {
+        let (token, position) = self.next()?;
+        token
+            .parse()
+            .map_err(|_| ParseError::UnexpectedToken { token, position })
+    }
+}
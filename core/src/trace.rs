@@ -0,0 +1,159 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static SUBSCRIBER: RefCell<Option<Box<dyn Subscriber>>> = RefCell::new(None);
+}
+
+pub trait Subscriber {
+    fn enter(&mut self, span: &'static str);
+    fn exit(&mut self, span: &'static str, elapsed: Duration);
+    fn event(&mut self, name: &'static str, fields: &[(&'static str, i64)]);
+}
+
+impl<S: Subscriber> Subscriber for Rc<RefCell<S>> {
+    fn enter(&mut self, span: &'static str) {
+        self.borrow_mut().enter(span)
+    }
+
+    fn exit(&mut self, span: &'static str, elapsed: Duration) {
+        self.borrow_mut().exit(span, elapsed)
+    }
+
+    fn event(&mut self, name: &'static str, fields: &[(&'static str, i64)]) {
+        self.borrow_mut().event(name, fields)
+    }
+}
+
+pub fn set_subscriber(subscriber: impl Subscriber + 'static) -> Option<Box<dyn Subscriber>> {
+    SUBSCRIBER.with(|current| current.borrow_mut().replace(Box::new(subscriber)))
+}
+
+pub fn clear_subscriber() -> Option<Box<dyn Subscriber>> {
+    SUBSCRIBER.with(|current| current.borrow_mut().take())
+}
+
+fn with_subscriber(f: impl FnOnce(&mut dyn Subscriber)) {
+    SUBSCRIBER.with(|current| {
+        if let Ok(mut current) = current.try_borrow_mut() {
+            if let Some(subscriber) = current.as_mut() {
+                f(subscriber.as_mut());
+            }
+        }
+    });
+}
+
+pub fn event(name: &'static str, fields: &[(&'static str, i64)]) {
+    with_subscriber(|subscriber| subscriber.event(name, fields));
+}
+
+pub fn span(name: &'static str) -> Span {
+    with_subscriber(|subscriber| subscriber.enter(name));
+    Span {
+        name,
+        start: Instant::now(),
+    }
+}
+
+pub struct Span {
+    name: &'static str,
+    start: Instant,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        with_subscriber(|subscriber| subscriber.exit(self.name, elapsed));
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub spans: BTreeMap<&'static str, (usize, Duration)>,
+    pub events: BTreeMap<&'static str, usize>,
+}
+
+impl Subscriber for Profile {
+    fn enter(&mut self, _: &'static str) {}
+
+    fn exit(&mut self, span: &'static str, elapsed: Duration) {
+        let (count, total) = self.spans.entry(span).or_default();
+        *count += 1;
+        *total += elapsed;
+    }
+
+    fn event(&mut self, name: &'static str, _: &[(&'static str, i64)]) {
+        *self.events.entry(name).or_default() += 1;
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (span, (count, total)) in &self.spans {
+            writeln!(f, "{:>8} × {} ({:?})", count, span, total)?;
+        }
+        for (name, count) in &self.events {
+            writeln!(f, "{:>8} × {}", count, name)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    pub lines: Vec<String>,
+    depth: usize,
+}
+
+impl Subscriber for Log {
+    fn enter(&mut self, span: &'static str) {
+        self.lines
+            .push(format!("{}{} {{", "  ".repeat(self.depth), span));
+        self.depth += 1;
+    }
+
+    fn exit(&mut self, _: &'static str, elapsed: Duration) {
+        self.depth = self.depth.saturating_sub(1);
+        self.lines
+            .push(format!("{}}} {:?}", "  ".repeat(self.depth), elapsed));
+    }
+
+    fn event(&mut self, name: &'static str, fields: &[(&'static str, i64)]) {
+        let fields = fields
+            .iter()
+            .map(|(key, value)| format!(" {}={}", key, value))
+            .collect::<String>();
+        self.lines
+            .push(format!("{}{}{}", "  ".repeat(self.depth), name, fields));
+    }
+}
+
+impl fmt::Display for Log {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! trace_event {
+    ($name:expr $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "trace")]
+        $crate::trace::event($name, &[$((stringify!($field), $value as i64)),*]);
+    };
+}
+
+macro_rules! trace_span {
+    ($name:expr) => {
+        #[cfg(feature = "trace")]
+        let _span = $crate::trace::span($name);
+    };
+}
+
+pub(crate) use {trace_event, trace_span};
@@ -0,0 +1,217 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
+
+use crate::{
+    ast::{ASTNode, AbstractSyntaxTree, Ident, Model, AST},
+    counting::ProjectedModels,
+    ltl::KripkeStructure,
+    modal::KripkeModel,
+    sat::{Clause, Literal},
+    tseitin,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionSystem {
+    pub state: Vec<(Ident, Ident)>,
+    pub init: AST,
+    pub trans: AST,
+    pub bad: AST,
+    pub labels: Vec<(Ident, AST)>,
+}
+
+impl TransitionSystem {
+    pub fn new(
+        state: impl IntoIterator<Item = (Ident, Ident)>,
+        init: AST,
+        trans: AST,
+        bad: AST,
+    ) -> Self {
+        Self {
+            state: state.into_iter().collect(),
+            init,
+            trans,
+            bad,
+            labels: vec![],
+        }
+    }
+
+    pub fn label(mut self, ident: Ident, predicate: AST) -> Self {
+        self.labels.push((ident, predicate));
+        self
+    }
+
+    pub fn current(&self) -> impl Iterator<Item = Ident> + '_ {
+        self.state.iter().map(|(current, _)| *current)
+    }
+
+    pub fn next(&self) -> impl Iterator<Item = Ident> + '_ {
+        self.state.iter().map(|(_, next)| *next)
+    }
+
+    pub fn primed(&self, ident: Ident) -> Option<Ident> {
+        self.state
+            .iter()
+            .find(|(current, _)| *current == ident)
+            .map(|(_, next)| *next)
+    }
+
+    pub fn unprimed(&self, ident: Ident) -> Option<Ident> {
+        self.state
+            .iter()
+            .find(|(_, next)| *next == ident)
+            .map(|(current, _)| *current)
+    }
+
+    pub fn inputs(&self) -> BTreeSet<Ident> {
+        let mut inputs = self.init.variables();
+        inputs.extend(self.trans.variables());
+        inputs.extend(self.bad.variables());
+        for (current, next) in &self.state {
+            inputs.remove(current);
+            inputs.remove(next);
+        }
+        inputs
+    }
+
+    pub fn prime(&self, formula: &AST) -> AST {
+        rename(formula, &self.state.iter().copied().collect())
+    }
+
+    pub fn unprime(&self, formula: &AST) -> AST {
+        let renaming = self
+            .state
+            .iter()
+            .map(|(current, next)| (*next, *current))
+            .collect();
+        rename(formula, &renaming)
+    }
+
+    pub fn to_kripke(&self, max_states: usize) -> Option<KripkeStructure> {
+        let mut first = [&self.init, &self.trans]
+            .into_iter()
+            .flat_map(|formula| formula.variables())
+            .chain(self.current())
+            .chain(self.next())
+            .max()
+            .map_or(0, |ident| ident + 1);
+        let mut encode = |formula: &AST| {
+            let (clauses, auxiliary) = tseitin::cnf_clauses_from(formula, first);
+            first = auxiliary.last().map_or(first, |ident| ident + 1);
+            clauses
+        };
+        let (init, trans) = (encode(&self.init), encode(&self.trans));
+        let mut explorer = Explorer::default();
+        let mut initial = BTreeSet::new();
+        for model in ProjectedModels::new(&init, self.current().collect()) {
+            initial.insert(
+                explorer.intern(
+                    self.current()
+                        .map(|ident| model.get(ident) == Some(true))
+                        .collect(),
+                ),
+            );
+        }
+        let mut world = 0;
+        while world < explorer.states.len() {
+            if explorer.states.len() > max_states {
+                return None;
+            }
+            let mut clauses = trans.clone();
+            for (ident, value) in self.current().zip(explorer.states[world].clone()) {
+                clauses.push(Clause::new([match value {
+                    true => Literal::positive(ident),
+                    false => Literal::negative(ident),
+                }]));
+            }
+            for model in ProjectedModels::new(&clauses, self.next().collect()) {
+                let successor = explorer.intern(
+                    self.next()
+                        .map(|ident| model.get(ident) == Some(true))
+                        .collect(),
+                );
+                explorer.relation[world].insert(successor);
+            }
+            world += 1;
+        }
+        if explorer.states.len() > max_states {
+            return None;
+        }
+        let mut kripke = KripkeModel::new(explorer.states.len());
+        for (world, values) in explorer.states.iter().enumerate() {
+            let model = self
+                .current()
+                .zip(values.iter().copied())
+                .collect::<Model>();
+            for (ident, value) in &model {
+                if value {
+                    kripke.set_true(world, ident);
+                }
+            }
+            for (ident, predicate) in &self.labels {
+                if predicate.verify_model(&model) {
+                    kripke.set_true(world, *ident);
+                }
+            }
+        }
+        kripke.relation = explorer.relation;
+        Some(KripkeStructure::new(kripke, initial))
+    }
+}
+
+#[derive(Default)]
+struct Explorer {
+    index: HashMap<Vec<bool>, usize>,
+    states: Vec<Vec<bool>>,
+    relation: Vec<BTreeSet<usize>>,
+}
+
+impl Explorer {
+    fn intern(&mut self, state: Vec<bool>) -> usize {
+        if let Some(world) = self.index.get(&state) {
+            return *world;
+        }
+        self.index.insert(state.clone(), self.states.len());
+        self.states.push(state);
+        self.relation.push(BTreeSet::new());
+        self.states.len() - 1
+    }
+}
+
+pub fn rename(formula: &AST, renaming: &HashMap<Ident, Ident>) -> AST {
+    let substitution = renaming
+        .iter()
+        .map(|(from, to)| (*from, <AST as AbstractSyntaxTree>::variable(*to)))
+        .collect();
+    substitute(formula, &substitution)
+}
+
+pub fn substitute(formula: &AST, substitution: &HashMap<Ident, AST>) -> AST {
+    substitute_memoized(formula, substitution, &mut HashMap::new())
+}
+
+fn substitute_memoized(
+    formula: &AST,
+    substitution: &HashMap<Ident, AST>,
+    memo: &mut HashMap<*const ASTNode, AST>,
+) -> AST {
+    if let Some(substituted) = memo.get(&Rc::as_ptr(formula)) {
+        return substituted.clone();
+    }
+    let mut recurse = |p: &AST| substitute_memoized(p, substitution, memo);
+    let substituted = match &**formula {
+        ASTNode::Variable(ident) => match substitution.get(ident) {
+            Some(replacement) => replacement.clone(),
+            None => formula.clone(),
+        },
+        ASTNode::Not(p) => recurse(p).not(),
+        ASTNode::And(p1, p2) => recurse(p1).and(recurse(p2)),
+        ASTNode::Or(p1, p2) => recurse(p1).or(recurse(p2)),
+        ASTNode::Nand(p1, p2) => recurse(p1).nand(recurse(p2)),
+        ASTNode::Nor(p1, p2) => recurse(p1).nor(recurse(p2)),
+        ASTNode::Ite(p1, p2, p3) => recurse(p1).ite(recurse(p2), recurse(p3)),
+    };
+    memo.insert(Rc::as_ptr(formula), substituted.clone());
+    substituted
+}
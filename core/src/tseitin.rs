@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{AbstractSyntaxTree, Ident, Node},
+    budget::Budget,
+    error::Error,
+    rewrite::{RewriteError, RewriteRuleset},
+    sat::{stream_clauses_from_tree, try_generate_clauses_from_tree, Clause, Literal, Polarity},
+};
+
+pub const DISTRIBUTION_LIMIT: usize = 4096;
+pub const DISTRIBUTION_NODE_LIMIT: usize = 1 << 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Equivalence {
+    Equivalent,
+    Equisatisfiable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CnfConversion<T> {
+    pub formula: T,
+    pub equivalence: Equivalence,
+    pub auxiliary: Vec<Ident>,
+}
+
+type Lit = (Ident, bool);
+
+pub fn cnf_conversion<T: AbstractSyntaxTree>(formula: &T) -> CnfConversion<T> {
+    let first = formula.variables().last().map_or(0, |ident| ident + 1);
+    cnf_conversion_from(formula, first)
+}
+
+pub fn cnf_conversion_from<T: AbstractSyntaxTree>(formula: &T, first: Ident) -> CnfConversion<T> {
+    match distributive_cnf_within(formula, &distribution_budget()) {
+        Ok(formula) => CnfConversion {
+            formula,
+            equivalence: Equivalence::Equivalent,
+            auxiliary: vec![],
+        },
+        Err(_) => tseitin(formula, first),
+    }
+}
+
+pub fn distributive_cnf<T: AbstractSyntaxTree>(formula: &T) -> Result<T, RewriteError> {
+    RewriteRuleset::cnf().rewrite_recursive_hull(formula.clone())
+}
+
+pub fn distributive_cnf_within<T: AbstractSyntaxTree>(
+    formula: &T,
+    budget: &Budget,
+) -> Result<T, Error> {
+    budget.check_clauses(estimate_cnf_clauses(formula))?;
+    Ok(RewriteRuleset::cnf()
+        .rewrite_recursive_hull_within(formula.clone(), budget)?
+        .0)
+}
+
+pub fn distribution_budget() -> Budget {
+    Budget {
+        max_nodes: Some(DISTRIBUTION_NODE_LIMIT),
+        max_clauses: Some(DISTRIBUTION_LIMIT),
+    }
+}
+
+pub fn estimate_cnf_clauses<T: AbstractSyntaxTree>(formula: &T) -> usize {
+    estimate(formula, &mut HashMap::new()).0
+}
+
+pub fn estimate_dnf_terms<T: AbstractSyntaxTree>(formula: &T) -> usize {
+    estimate(formula, &mut HashMap::new()).1
+}
+
+pub fn cnf_conversion_within<T: AbstractSyntaxTree>(
+    formula: &T,
+    first: Ident,
+    budget: &Budget,
+) -> Result<CnfConversion<T>, Error> {
+    let cap = |max: Option<usize>, limit: usize| Some(max.map_or(limit, |max| max.min(limit)));
+    let distribution = Budget {
+        max_nodes: cap(budget.max_nodes, DISTRIBUTION_NODE_LIMIT),
+        max_clauses: cap(budget.max_clauses, DISTRIBUTION_LIMIT),
+    };
+    if let Ok(formula) = distributive_cnf_within(formula, &distribution) {
+        return Ok(CnfConversion {
+            formula,
+            equivalence: Equivalence::Equivalent,
+            auxiliary: vec![],
+        });
+    }
+    let conversion = tseitin(formula, first);
+    budget.check_nodes(&conversion.formula)?;
+    budget.check_clauses(stream_clauses_from_tree(conversion.formula.clone()).count())?;
+    Ok(conversion)
+}
+
+fn estimate<T: AbstractSyntaxTree>(
+    formula: &T,
+    memo: &mut HashMap<usize, (usize, usize)>,
+) -> (usize, usize) {
+    if let Some(sizes) = memo.get(&formula.key()) {
+        return *sizes;
+    }
+    let conjunction = |(cnf1, dnf1): (usize, usize), (cnf2, dnf2): (usize, usize)| {
+        (cnf1.saturating_add(cnf2), dnf1.saturating_mul(dnf2))
+    };
+    let disjunction = |(cnf1, dnf1): (usize, usize), (cnf2, dnf2): (usize, usize)| {
+        (cnf1.saturating_mul(cnf2), dnf1.saturating_add(dnf2))
+    };
+    let negation = |(cnf, dnf): (usize, usize)| (dnf, cnf);
+    let sizes = match formula.node() {
+        Node::Variable(_) => (1, 1),
+        Node::Not(p) => negation(estimate(&p, memo)),
+        Node::And(p1, p2) => conjunction(estimate(&p1, memo), estimate(&p2, memo)),
+        Node::Or(p1, p2) => disjunction(estimate(&p1, memo), estimate(&p2, memo)),
+        Node::Nand(p1, p2) => negation(conjunction(estimate(&p1, memo), estimate(&p2, memo))),
+        Node::Nor(p1, p2) => negation(disjunction(estimate(&p1, memo), estimate(&p2, memo))),
+        Node::Ite(p1, p2, p3) => {
+            let condition = estimate(&p1, memo);
+            let (then, otherwise) = (estimate(&p2, memo), estimate(&p3, memo));
+            disjunction(
+                conjunction(condition, then),
+                conjunction(negation(condition), otherwise),
+            )
+        }
+    };
+    memo.insert(formula.key(), sizes);
+    sizes
+}
+
+pub(crate) fn cnf_clauses<T: AbstractSyntaxTree>(formula: &T) -> Vec<Clause> {
+    let first = formula.variables().last().map_or(0, |ident| ident + 1);
+    cnf_clauses_from(formula, first).0
+}
+
+pub(crate) fn cnf_clauses_from<T: AbstractSyntaxTree>(
+    formula: &T,
+    first: Ident,
+) -> (Vec<Clause>, Vec<Ident>) {
+    let distributed = distributive_cnf_within(formula, &distribution_budget())
+        .ok()
+        .and_then(|cnf| try_generate_clauses_from_tree(cnf).ok());
+    if let Some(clauses) = distributed {
+        return (clauses, vec![]);
+    }
+    let mut encoder = Encoder::run(formula, first);
+    (encoder.take_clauses(), (first..encoder.next).collect())
+}
+
+pub fn tseitin<T: AbstractSyntaxTree>(formula: &T, first: Ident) -> CnfConversion<T> {
+    let encoder = Encoder::run(formula, first);
+    let literal = |(ident, positive): Lit| match positive {
+        true => T::variable(ident),
+        false => T::variable(ident).not(),
+    };
+    let formula = encoder
+        .clauses
+        .into_iter()
+        .map(|clause| {
+            clause
+                .into_iter()
+                .map(literal)
+                .reduce(|lhs, rhs| lhs.or(rhs))
+                .unwrap()
+        })
+        .reduce(|lhs, rhs| lhs.and(rhs))
+        .unwrap();
+    let auxiliary = (first..encoder.next).collect::<Vec<_>>();
+    CnfConversion {
+        formula,
+        equivalence: match auxiliary.is_empty() {
+            true => Equivalence::Equivalent,
+            false => Equivalence::Equisatisfiable,
+        },
+        auxiliary,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Encoder {
+    next: Ident,
+    clauses: Vec<Vec<Lit>>,
+    memo: HashMap<usize, Lit>,
+    renaming: Option<HashMap<Ident, Ident>>,
+}
+
+impl Encoder {
+    fn run<T: AbstractSyntaxTree>(formula: &T, first: Ident) -> Self {
+        let mut encoder = Encoder {
+            next: first,
+            ..Encoder::default()
+        };
+        let root = encoder.encode(formula);
+        encoder.clauses.push(vec![root]);
+        encoder
+    }
+
+    pub(crate) fn renaming() -> Self {
+        Encoder {
+            renaming: Some(HashMap::new()),
+            ..Encoder::default()
+        }
+    }
+
+    pub(crate) fn root<T: AbstractSyntaxTree>(&mut self, formula: &T) -> Literal {
+        self.memo.clear();
+        literal(self.encode(formula))
+    }
+
+    pub(crate) fn fresh_literal(&mut self) -> Literal {
+        literal(self.fresh())
+    }
+
+    pub(crate) fn take_clauses(&mut self) -> Vec<Clause> {
+        std::mem::take(&mut self.clauses)
+            .into_iter()
+            .map(|clause| Clause::new(clause.into_iter().map(literal)))
+            .collect()
+    }
+
+    pub(crate) fn renamed(&self) -> impl Iterator<Item = (Ident, Ident)> + '_ {
+        self.renaming
+            .iter()
+            .flatten()
+            .map(|(ident, renamed)| (*ident, *renamed))
+    }
+
+    fn encode<T: AbstractSyntaxTree>(&mut self, formula: &T) -> Lit {
+        if let Some(literal) = self.memo.get(&formula.key()) {
+            return *literal;
+        }
+        let not = |(ident, positive): Lit| (ident, !positive);
+        let literal = match formula.node() {
+            Node::Variable(ident) => self.variable(ident),
+            Node::Not(p) => not(self.encode(&p)),
+            Node::And(p1, p2) => {
+                let (lhs, rhs) = (self.encode(&p1), self.encode(&p2));
+                self.and_gate(lhs, rhs)
+            }
+            Node::Or(p1, p2) => {
+                let (lhs, rhs) = (self.encode(&p1), self.encode(&p2));
+                not(self.and_gate(not(lhs), not(rhs)))
+            }
+            Node::Nand(p1, p2) => {
+                let (lhs, rhs) = (self.encode(&p1), self.encode(&p2));
+                not(self.and_gate(lhs, rhs))
+            }
+            Node::Nor(p1, p2) => {
+                let (lhs, rhs) = (self.encode(&p1), self.encode(&p2));
+                self.and_gate(not(lhs), not(rhs))
+            }
+            Node::Ite(p1, p2, p3) => {
+                let condition = self.encode(&p1);
+                let (then, otherwise) = (self.encode(&p2), self.encode(&p3));
+                let output = self.fresh();
+                self.clauses.extend([
+                    vec![not(condition), not(then), output],
+                    vec![not(condition), then, not(output)],
+                    vec![condition, not(otherwise), output],
+                    vec![condition, otherwise, not(output)],
+                ]);
+                output
+            }
+        };
+        self.memo.insert(formula.key(), literal);
+        literal
+    }
+
+    fn variable(&mut self, ident: Ident) -> Lit {
+        match self.renaming.as_ref().map(|renaming| renaming.get(&ident)) {
+            None => (ident, true),
+            Some(Some(renamed)) => (*renamed, true),
+            Some(None) => {
+                let output = self.fresh();
+                self.renaming
+                    .get_or_insert_default()
+                    .insert(ident, output.0);
+                output
+            }
+        }
+    }
+
+    fn fresh(&mut self) -> Lit {
+        self.next += 1;
+        (self.next - 1, true)
+    }
+
+    fn and_gate(&mut self, lhs: Lit, rhs: Lit) -> Lit {
+        let not = |(ident, positive): Lit| (ident, !positive);
+        let output = self.fresh();
+        self.clauses.extend([
+            vec![not(output), lhs],
+            vec![not(output), rhs],
+            vec![output, not(lhs), not(rhs)],
+        ]);
+        output
+    }
+}
+
+fn literal((ident, positive): Lit) -> Literal {
+    match positive {
+        true => Literal::new(ident, Polarity::Positive),
+        false => Literal::new(ident, Polarity::Negative),
+    }
+}
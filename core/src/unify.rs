@@ -0,0 +1,121 @@
+use std::{collections::HashMap, fmt};
+
+use crate::fol::{FirstOrderTerm, FolLiteral, Substitution, Term, TermNode};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnificationError {
+    SymbolClash(String, String),
+    ArityMismatch(String),
+    OccursCheck(String, Term),
+    PolarityMismatch,
+}
+
+impl fmt::Display for UnificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnificationError::SymbolClash(lhs, rhs) => {
+                write!(f, "cannot unify '{}' with '{}'", lhs, rhs)
+            }
+            UnificationError::ArityMismatch(symbol) => {
+                write!(f, "'{}' is used with different arities", symbol)
+            }
+            UnificationError::OccursCheck(variable, term) => {
+                write!(f, "'{}' occurs in '{}'", variable, term)
+            }
+            UnificationError::PolarityMismatch => write!(f, "literals have the same polarity"),
+        }
+    }
+}
+
+impl std::error::Error for UnificationError {}
+
+pub fn unify(lhs: &Term, rhs: &Term) -> Result<Substitution, UnificationError> {
+    unify_all(vec![(lhs.clone(), rhs.clone())])
+}
+
+pub fn unify_all(mut pairs: Vec<(Term, Term)>) -> Result<Substitution, UnificationError> {
+    let mut substitution = Substitution::new();
+    while let Some((lhs, rhs)) = pairs.pop() {
+        let (lhs, rhs) = (lhs.substitute(&substitution), rhs.substitute(&substitution));
+        match (&*lhs, &*rhs) {
+            (TermNode::Variable(x), TermNode::Variable(y)) if x == y => continue,
+            (TermNode::Variable(x), _) => bind(&mut substitution, x, rhs.clone())?,
+            (_, TermNode::Variable(x)) => bind(&mut substitution, x, lhs.clone())?,
+            (TermNode::Function(f, lhs_arguments), TermNode::Function(g, rhs_arguments)) => {
+                if f != g {
+                    return Err(UnificationError::SymbolClash(f.clone(), g.clone()));
+                }
+                if lhs_arguments.len() != rhs_arguments.len() {
+                    return Err(UnificationError::ArityMismatch(f.clone()));
+                }
+                pairs.extend(
+                    lhs_arguments
+                        .iter()
+                        .cloned()
+                        .zip(rhs_arguments.iter().cloned()),
+                );
+            }
+        }
+    }
+    Ok(substitution)
+}
+
+fn bind(
+    substitution: &mut Substitution,
+    variable: &str,
+    term: Term,
+) -> Result<(), UnificationError> {
+    if term.occurs(variable) {
+        return Err(UnificationError::OccursCheck(variable.to_owned(), term));
+    }
+    let binding = HashMap::from([(variable.to_owned(), term.clone())]);
+    for value in substitution.values_mut() {
+        *value = value.substitute(&binding);
+    }
+    substitution.insert(variable.to_owned(), term);
+    Ok(())
+}
+
+pub fn unify_atoms(lhs: &FolLiteral, rhs: &FolLiteral) -> Result<Substitution, UnificationError> {
+    if lhs.predicate != rhs.predicate {
+        return Err(UnificationError::SymbolClash(
+            lhs.predicate.clone(),
+            rhs.predicate.clone(),
+        ));
+    }
+    if lhs.arguments.len() != rhs.arguments.len() {
+        return Err(UnificationError::ArityMismatch(lhs.predicate.clone()));
+    }
+    unify_all(
+        lhs.arguments
+            .iter()
+            .cloned()
+            .zip(rhs.arguments.iter().cloned())
+            .collect(),
+    )
+}
+
+pub fn unify_complementary(
+    lhs: &FolLiteral,
+    rhs: &FolLiteral,
+) -> Result<Substitution, UnificationError> {
+    if lhs.polarity == rhs.polarity {
+        return Err(UnificationError::PolarityMismatch);
+    }
+    unify_atoms(lhs, rhs)
+}
+
+pub fn compose(first: &Substitution, second: &Substitution) -> Substitution {
+    let mut composed = first
+        .iter()
+        .map(|(variable, term)| (variable.clone(), term.substitute(second)))
+        .collect::<Substitution>();
+    for (variable, term) in second {
+        composed
+            .entry(variable.clone())
+            .or_insert_with(|| term.clone());
+    }
+    composed
+        .retain(|variable, term| !matches!(&**term, TermNode::Variable(name) if name == variable));
+    composed
+}
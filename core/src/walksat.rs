@@ -0,0 +1,196 @@
+use crate::{
+    ast::{Ident, Model},
+    cdcl::SolveError,
+    random::Rng,
+    sat::{Clause, IncompleteSolver, Polarity},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalkSat {
+    pub noise: f64,
+    pub max_flips: usize,
+    pub max_tries: usize,
+    pub seed: u64,
+}
+
+impl Default for WalkSat {
+    fn default() -> Self {
+        Self {
+            noise: 0.5,
+            max_flips: 100_000,
+            max_tries: 10,
+            seed: 0,
+        }
+    }
+}
+
+struct Search {
+    clauses: Vec<Vec<(usize, bool)>>,
+    occurrences: Vec<Vec<usize>>,
+    values: Vec<bool>,
+    satisfied: Vec<usize>,
+    unsatisfied: Vec<usize>,
+    positions: Vec<Option<usize>>,
+}
+
+impl Search {
+    fn new(clauses: &[Clause]) -> Self {
+        let clauses = clauses
+            .iter()
+            .map(|clause| {
+                clause
+                    .literals()
+                    .map(|literal| {
+                        (
+                            literal.identifier() as usize,
+                            *literal.polarity() == Polarity::Positive,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let variables = clauses
+            .iter()
+            .flatten()
+            .map(|(var, _)| var + 1)
+            .max()
+            .unwrap_or(0);
+        let mut occurrences = vec![vec![]; variables];
+        for (index, clause) in clauses.iter().enumerate() {
+            for (var, _) in clause {
+                if occurrences[*var].last() != Some(&index) {
+                    occurrences[*var].push(index);
+                }
+            }
+        }
+        Self {
+            satisfied: vec![0; clauses.len()],
+            positions: vec![None; clauses.len()],
+            clauses,
+            occurrences,
+            values: vec![false; variables],
+            unsatisfied: vec![],
+        }
+    }
+
+    fn restart(&mut self, rng: &mut Rng) {
+        for value in &mut self.values {
+            *value = rng.next_bool();
+        }
+        self.unsatisfied.clear();
+        for index in 0..self.clauses.len() {
+            self.satisfied[index] = self.clauses[index]
+                .iter()
+                .filter(|(var, positive)| self.values[*var] == *positive)
+                .count();
+            self.positions[index] = None;
+            if self.satisfied[index] == 0 {
+                self.mark_unsatisfied(index);
+            }
+        }
+    }
+
+    fn mark_unsatisfied(&mut self, index: usize) {
+        self.positions[index] = Some(self.unsatisfied.len());
+        self.unsatisfied.push(index);
+    }
+
+    fn mark_satisfied(&mut self, index: usize) {
+        if let Some(position) = self.positions[index].take() {
+            self.unsatisfied.swap_remove(position);
+            if let Some(&moved) = self.unsatisfied.get(position) {
+                self.positions[moved] = Some(position);
+            }
+        }
+    }
+
+    fn breaks(&self, var: usize) -> usize {
+        self.occurrences[var]
+            .iter()
+            .filter(|index| {
+                self.satisfied[**index] == 1
+                    && self.clauses[**index]
+                        .iter()
+                        .any(|(other, positive)| *other == var && self.values[var] == *positive)
+            })
+            .count()
+    }
+
+    fn flip(&mut self, var: usize) {
+        self.values[var] = !self.values[var];
+        for position in 0..self.occurrences[var].len() {
+            let index = self.occurrences[var][position];
+            let (gained, lost) = self.clauses[index]
+                .iter()
+                .filter(|(other, _)| *other == var)
+                .fold((0, 0), |(gained, lost), (_, positive)| {
+                    match self.values[var] == *positive {
+                        true => (gained + 1, lost),
+                        false => (gained, lost + 1),
+                    }
+                });
+            let before = self.satisfied[index];
+            self.satisfied[index] = before + gained - lost;
+            match (before, self.satisfied[index]) {
+                (0, after) if after > 0 => self.mark_satisfied(index),
+                (before, 0) if before > 0 => self.mark_unsatisfied(index),
+                _ => (),
+            }
+        }
+    }
+
+    fn model(&self) -> Model {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(var, value)| (var as Ident, *value))
+            .collect()
+    }
+}
+
+impl IncompleteSolver for WalkSat {
+    fn try_solve_clauses(&self, clauses: &[Clause]) -> Result<Option<Model>, SolveError> {
+        if clauses.iter().any(Clause::is_empty) {
+            return Ok(None);
+        }
+        match self.solve(clauses, &mut Rng::new(self.seed)) {
+            Some(model) => Ok(Some(model)),
+            None => Err(SolveError::FlipLimitExceeded {
+                tries: self.max_tries,
+                flips: self.max_flips,
+            }),
+        }
+    }
+}
+
+impl WalkSat {
+    pub fn solve(&self, clauses: &[Clause], rng: &mut Rng) -> Option<Model> {
+        if clauses.iter().any(Clause::is_empty) {
+            return None;
+        }
+        let mut search = Search::new(clauses);
+        for _ in 0..self.max_tries {
+            search.restart(rng);
+            for _ in 0..self.max_flips {
+                if search.unsatisfied.is_empty() {
+                    return Some(search.model());
+                }
+                let index = search.unsatisfied[rng.below(search.unsatisfied.len())];
+                let candidates = search.clauses[index]
+                    .iter()
+                    .map(|(var, _)| (search.breaks(*var), *var))
+                    .collect::<Vec<_>>();
+                let (breaks, var) = *candidates.iter().min().unwrap();
+                let var = match breaks > 0 && rng.next_f64() < self.noise {
+                    true => candidates[rng.below(candidates.len())].1,
+                    false => var,
+                };
+                search.flip(var);
+            }
+            if search.unsatisfied.is_empty() {
+                return Some(search.model());
+            }
+        }
+        None
+    }
+}
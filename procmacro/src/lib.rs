@@ -2,60 +2,121 @@ extern crate proc_macro;
 
 use std::collections::HashMap;
 
-use proc_macro::{TokenStream, TokenTree};
+use proc_macro::{Spacing, TokenStream, TokenTree};
 
 #[proc_macro]
 pub fn propositional_logic(input: TokenStream) -> TokenStream {
-    let mut tokens = vec![];
-    tokenize_propositional_logic(input, &mut tokens);
-    let mut ast_code = "".to_owned();
     let (mut symtab, mut n) = (HashMap::new(), 0);
-    codegen(
-        &mut tokens.into_iter(),
-        &mut ast_code,
-        (&mut symtab, &mut n),
-    );
-    ast_code.parse().unwrap()
+    let result = split_rule(input)
+        .into_iter()
+        .map(|formula| {
+            let mut tokens = vec![];
+            tokenize_propositional_logic(formula, &mut tokens);
+            let mut ast_code = "".to_owned();
+            let mut tokens = tokens.into_iter();
+            codegen(&mut tokens, &mut ast_code, (&mut symtab, &mut n))?;
+            match tokens.next() {
+                Some(token) => Err(format!("unexpected token `{}`", token)),
+                None => Ok(ast_code),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|formulas| match formulas.as_slice() {
+            [formula] => Ok(formula.clone()),
+            [top, bot] => Ok(format!("({}, {})", top, bot)),
+            _ => Err("expected a formula or a rule `top => bot`".to_owned()),
+        });
+    match result {
+        Ok(ast_code) => ast_code.parse().unwrap(),
+        Err(message) => format!(
+            "compile_error!({:?})",
+            format!("propositional_logic: {}", message)
+        )
+        .parse()
+        .unwrap(),
+    }
+}
+
+fn split_rule(input: TokenStream) -> Vec<TokenStream> {
+    let trees = input.into_iter().collect::<Vec<_>>();
+    let arrow = trees.windows(2).position(|pair| match pair {
+        [TokenTree::Punct(eq), TokenTree::Punct(gt)] => {
+            eq.as_char() == '=' && eq.spacing() == Spacing::Joint && gt.as_char() == '>'
+        }
+        _ => false,
+    });
+    match arrow {
+        Some(index) => vec![
+            trees[..index].iter().cloned().collect(),
+            trees[index + 2..].iter().cloned().collect(),
+        ],
+        None => vec![trees.into_iter().collect()],
+    }
 }
 
 fn codegen<I: Iterator<Item = String>>(
     tokens: &mut I,
     code: &mut String,
     (symtab, n): (&mut HashMap<String, i32>, &mut i32),
-) {
-    if let Some(token) = tokens.next() {
-        match token.as_str() {
-            "NOT" => {
-                codegen(tokens, code, (symtab, n));
-                code.push_str(".not()");
-            }
-            "AND" => {
-                codegen(tokens, code, (symtab, n));
-                code.push_str(".and(");
-                codegen(tokens, code, (symtab, n));
-                code.push(')');
-            }
-            "OR" => {
-                codegen(tokens, code, (symtab, n));
-                code.push_str(".or(");
-                codegen(tokens, code, (symtab, n));
-                code.push(')');
-            }
-            ident => {
-                code.push_str(&format!(
-                    "<AST as AbstractSyntaxTree>::variable({})",
-                    match symtab.get(ident) {
-                        Some(id) => *id,
-                        None => {
-                            symtab.insert(ident.to_owned(), *n);
-                            *n += 1;
-                            *n - 1
-                        }
+) -> Result<(), String> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| "unexpected end of formula".to_owned())?;
+    match token.as_str() {
+        "NOT" => {
+            codegen(tokens, code, (symtab, n))?;
+            code.push_str(".not()");
+        }
+        "AND" => {
+            codegen(tokens, code, (symtab, n))?;
+            code.push_str(".and(");
+            codegen(tokens, code, (symtab, n))?;
+            code.push(')');
+        }
+        "OR" => {
+            codegen(tokens, code, (symtab, n))?;
+            code.push_str(".or(");
+            codegen(tokens, code, (symtab, n))?;
+            code.push(')');
+        }
+        "NAND" => {
+            codegen(tokens, code, (symtab, n))?;
+            code.push_str(".nand(");
+            codegen(tokens, code, (symtab, n))?;
+            code.push(')');
+        }
+        "NOR" => {
+            codegen(tokens, code, (symtab, n))?;
+            code.push_str(".nor(");
+            codegen(tokens, code, (symtab, n))?;
+            code.push(')');
+        }
+        "?" => {
+            codegen(tokens, code, (symtab, n))?;
+            code.push_str(".ite(");
+            codegen(tokens, code, (symtab, n))?;
+            code.push_str(", ");
+            codegen(tokens, code, (symtab, n))?;
+            code.push(')');
+        }
+        ident if !ident.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+            return Err(format!("unexpected token `{}`", ident))
+        }
+        ident => {
+            code.push_str(&format!(
+                "<AST as AbstractSyntaxTree>::variable({})",
+                match symtab.get(ident) {
+                    Some(id) => *id,
+                    None => {
+                        symtab.insert(ident.to_owned(), *n);
+                        *n += 1;
+                        *n - 1
                     }
-                ));
-            }
+                }
+            ));
         }
     }
+    Ok(())
 }
 
 fn tokenize_propositional_logic(stream: TokenStream, tokens: &mut Vec<String>) {
@@ -68,13 +129,20 @@ fn tokenize_propositional_logic(stream: TokenStream, tokens: &mut Vec<String>) {
                     match subtree {
                         TokenTree::Group(_) => operator_index += 1,
                         TokenTree::Ident(ident)
-                            if !matches!(ident.to_string().as_str(), "AND" | "OR") =>
+                            if !matches!(
+                                ident.to_string().as_str(),
+                                "AND" | "OR" | "NAND" | "NOR"
+                            ) =>
                         {
                             operator_index += 1
                         }
                         _ => break,
                     }
                 }
+                if operator_index == group_trees.len() {
+                    tokenize_propositional_logic(group.stream(), tokens);
+                    continue;
+                }
                 tokens.push(group_trees[operator_index].to_string());
                 tokenize_propositional_logic(
                     group_trees[..operator_index].iter().cloned().collect(),